@@ -0,0 +1,104 @@
+use std::io;
+use std::io::{IsTerminal, Write};
+
+use log::error;
+
+use crate::models::{is_consensus, nearest_card, GamePhase, Room, UserType, Vote, UNKNOWN_PHASE_WARNING};
+use crate::ui::sanitize;
+
+/// Announcements for votes that arrived or got retracted while the round is running.
+pub fn vote_announcements(old: &Room, new: &Room) -> Vec<String> {
+    if old.phase != GamePhase::Playing || new.phase != GamePhase::Playing {
+        return Vec::new();
+    }
+    let (voted, total) = new.vote_progress();
+    new.players.iter()
+        .filter(|player| player.user_type == UserType::Player)
+        .filter_map(|player| {
+            let had_voted = old.players.iter()
+                .find(|p| p.is_same(player))
+                .is_some_and(|p| p.vote != Vote::Missing);
+            let name = sanitize(&player.name);
+            match (had_voted, player.vote != Vote::Missing) {
+                (false, true) => Some(format!("{} voted, {} of {} votes.", name, voted, total)),
+                (true, false) => Some(format!("{} retracted the vote, {} of {} votes.", name, voted, total)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Announcement of a new round or of the revealed result.
+pub fn phase_announcement(room: &Room, round_number: u32) -> String {
+    match room.phase {
+        GamePhase::Playing => format!("Round {} started.", round_number),
+        GamePhase::Unknown => String::from(UNKNOWN_PHASE_WARNING),
+        GamePhase::Revealed => {
+            let average = room.average_votes();
+            let mut text = if average.is_nan() {
+                String::from("Cards revealed, no numeric votes")
+            } else {
+                format!("Cards revealed, average {:.1}", average)
+            };
+            if let Some(card) = nearest_card(average, &room.deck) {
+                text.push_str(&format!(", suggested {}", card));
+            }
+            if is_consensus(&room.players) {
+                text.push_str(", consensus");
+            }
+            text.push('.');
+            text
+        }
+    }
+}
+
+/// Passes an announcement on to the screen reader. It goes to stdout if that is redirected, e.g. into a speech
+/// synthesizer, and otherwise to the terminal as an OSC 9 notification, the ui itself is drawn on stderr.
+pub fn emit(text: &str) {
+    let result = if io::stdout().is_terminal() {
+        let mut stderr = io::stderr();
+        write!(stderr, "\x1b]9;{}\x07", text).and_then(|_| stderr.flush())
+    } else {
+        let mut stdout = io::stdout();
+        writeln!(stdout, "{}", text).and_then(|_| stdout.flush())
+    };
+    if let Err(e) = result {
+        error!("Failed to emit announcement: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{Player, VoteData};
+
+    use super::*;
+
+    fn room(phase: GamePhase, votes: &[Vote]) -> Room {
+        Room {
+            name: "room".to_string(),
+            deck: ["1", "2", "3", "5", "8"].iter().map(|c| c.to_string()).collect(),
+            phase,
+            players: votes.iter().enumerate().map(|(i, vote)| Player::new(&format!("p{}", i), vote.clone())).collect(),
+        }
+    }
+
+    #[test]
+    fn announces_arriving_and_retracted_votes() {
+        let old = room(GamePhase::Playing, &[Vote::Missing, Vote::Hidden, Vote::Missing]);
+        let new = room(GamePhase::Playing, &[Vote::Hidden, Vote::Missing, Vote::Missing]);
+        assert_eq!(vote_announcements(&old, &new), vec![
+            "p0 voted, 1 of 3 votes.".to_string(),
+            "p1 retracted the vote, 1 of 3 votes.".to_string(),
+        ]);
+
+        let revealed = room(GamePhase::Revealed, &[Vote::Hidden, Vote::Hidden, Vote::Hidden]);
+        assert!(vote_announcements(&old, &revealed).is_empty());
+    }
+
+    #[test]
+    fn announces_the_result() {
+        let votes = [Vote::Revealed(VoteData::Number(3)), Vote::Revealed(VoteData::Number(5))];
+        assert_eq!(phase_announcement(&room(GamePhase::Revealed, &votes), 2), "Cards revealed, average 4.0, suggested 5.");
+        assert_eq!(phase_announcement(&room(GamePhase::Playing, &[]), 3), "Round 3 started.");
+    }
+}
@@ -1,16 +1,47 @@
-use std::{error, mem};
-use std::time::{Duration, Instant};
+use std::{error, fs, mem};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant, SystemTime};
 
-use log::{debug, info};
+use log::{debug, error, info};
 
-use crate::config::Config;
-use crate::models::{GamePhase, LogEntry, LogLevel, LogSource, Player, Room, Vote, VoteData};
-use crate::notification::show_notification;
-use crate::web::client::PokerClient;
+use crate::announce::{phase_announcement, vote_announcements};
+use crate::chat_filter::ChatFilter;
+use crate::config::{AutoReveal, Config, IdleSpectate, IntegrationSummary};
+use crate::estimation::{command_argument, technique_command, EstimationState, COMMAND_PREFIX};
+use crate::export::{append_journal, export_session_summary};
+use crate::format::format_time_of_day;
+use crate::github::{Github, GithubResult, Issue};
+use crate::groups;
+use crate::hooks::{self, HookEvent};
+use crate::i18n::{tr, tr_args};
+use crate::integration;
+use crate::invite;
+use crate::links::{find_urls, open_url};
+use crate::models::{count_special_votes, deck_card, is_consensus, join_leave_rate, majority_vote, mentions, nearest_card, parse_confidence, pick_first_speaker, suggest_card, MAX_CONFIDENCE, UNKNOWN_PHASE_WARNING, GamePhase, LogEntry, LogLevel, LogSource, Player, Reaction, Room, UserType, Vote, VoteData};
+#[cfg(unix)]
+use crate::ipc::FollowerClient;
+use crate::notification::{ring_bell, show_notification};
+use crate::metrics::{write_textfile, Metrics};
+use crate::overlay::OverlayServer;
+use crate::roles::{self, Role};
+use crate::room_names;
+use crate::state_file::{self, RoomState};
+use crate::stats::{record_round, PlayerStats};
+use crate::status::{self, status_file, Status};
+use crate::update::{check_in_background, AvailableUpdate};
+use crate::web::client::{PokerClient, WebPokerClient};
+use crate::web::peer::PeerPokerClient;
+use crate::web::latency::LatencyStats;
 
 pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
 
-
+// tests build entries from the default and set only the fields they check
+#[cfg_attr(test, derive(Default))]
 pub struct HistoryEntry {
     pub round_number: u32,
     pub average: f32,
@@ -18,6 +49,15 @@ pub struct HistoryEntry {
     pub votes: Vec<Player>,
     pub deck: Vec<String>,
     pub own_vote: Option<VoteData>,
+    pub topic: Option<String>,
+    /// Votes like `?` or coffee that are not part of the average.
+    pub special_votes: usize,
+    /// All numeric votes of the round were the same.
+    pub consensus: bool,
+    /// Confidence of the players that gave one, by `Player::key`.
+    pub confidence: HashMap<String, u8>,
+    /// Entries of `App::log` from the start of the round until the next round started.
+    pub log: Range<usize>,
 }
 
 pub struct App {
@@ -26,49 +66,442 @@ pub struct App {
     pub name: String,
 
     pub room: Room,
-    pub client: PokerClient,
+    pub client: Box<dyn PokerClient>,
     pub log: Vec<LogEntry>,
 
     pub round_number: u32,
     pub round_start: Instant,
+    /// Length of the log when the current round started.
+    round_log_start: usize,
+    /// Index into `history` of the round whose log is shown instead of the whole log.
+    pub log_round: Option<usize>,
 
     pub config: Config,
 
     pub has_focus: bool,
     notify_vote_at: Option<Instant>,
     is_notified: bool,
+    last_chat_notification: Option<Instant>,
     pub has_updates: bool,
 
     pub history: Vec<HistoryEntry>,
+    pub player_stats: HashMap<String, PlayerStats>,
+    player_activity: HashMap<String, Instant>,
+    /// Names of everyone who was in the room this session.
+    pub seen_names: HashSet<String>,
+
+    last_interaction: Instant,
+    pub idle_prompt: bool,
+    auto_spectating: bool,
+
+    timebox_alerted: bool,
+
+    pub estimation: EstimationState,
+    pub topic: Option<String>,
+    /// Confidence given by the players for the current round, by `Player::key`.
+    pub confidence: HashMap<String, u8>,
+    /// Cards replacing the deck of the server.
+    custom_deck: Option<Vec<String>>,
+    /// Short status message shown until it expires.
+    flash: Option<(String, Instant)>,
+
+    /// When the cards get revealed automatically, armed once everyone has voted.
+    pub auto_reveal_at: Option<Instant>,
+    auto_reveal_cancelled: bool,
+    /// When the next round starts automatically, armed on reveal.
+    pub auto_restart_at: Option<Instant>,
+    /// Seconds the reveal of this round was postponed with `!pp wait`.
+    reveal_extension: u64,
+    /// Reveal a player scheduled for everyone with `!pp reveal-in`.
+    pub scheduled_reveal: Option<ScheduledReveal>,
+    /// Player picked to explain their vote first after this reveal.
+    pub first_speaker: Option<String>,
+    /// Latest reaction of each player to the revealed cards, by name.
+    pub reactions: Vec<(String, Reaction)>,
+    chat_filter: ChatFilter,
+    /// Chat messages shown unfiltered, by the time they were logged.
+    revealed_chat: HashSet<SystemTime>,
+    /// Pending announcements for screen readers, only collected with `screen_reader`.
+    pub announcements: Vec<String>,
+    /// Last status written for `ppoker status` and when.
+    status: Option<(Status, Instant)>,
+    overlay: Option<OverlayServer>,
+    pub metrics: Metrics,
+    metrics_written: Option<Instant>,
+    /// Update check running in the background.
+    update_check: Option<Receiver<AvailableUpdate>>,
+    /// Newer release found by the update check.
+    pub available_update: Option<AvailableUpdate>,
+    /// Start the installed update after the tui has been closed.
+    pub restart_after_update: bool,
+    github: Option<Github>,
+    /// Imported issues not estimated yet.
+    pub stories: VecDeque<Issue>,
+    /// Issue estimated in the current round, the estimate is posted to it.
+    pub story: Option<Issue>,
 }
 
+/// Reveal counted down on every client, only the client of the player who scheduled it reveals the cards.
+pub struct ScheduledReveal {
+    pub at: Instant,
+    pub by: String,
+}
+
+/// Random number from the seed std uses for hash maps, good enough to pick a player.
+pub(crate) fn random() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_nanos());
+    hasher.finish()
+}
+
+/// Joins the room of the config, with the peers in the local network in peer mode and through the server otherwise.
+fn connect(config: &Config) -> AppResult<(Box<dyn PokerClient>, Room, Vec<LogEntry>)> {
+    if config.peer {
+        let (client, room, log) = PeerPokerClient::new(config)?;
+        Ok((Box::new(client), room, log))
+    } else {
+        let (client, room, log) = WebPokerClient::new(config)?;
+        Ok((Box::new(client), room, log))
+    }
+}
+
+/// Longest delay a reveal can be scheduled with.
+pub const MAX_REVEAL_DELAY: u64 = 600;
+
+/// How often the metrics are written at most.
+const METRICS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Seconds added to the auto reveal countdown when someone needs more time.
+pub const AUTO_REVEAL_EXTENSION: u64 = 5;
+
+/// Seconds all `!pp wait` of a round may postpone the reveal together, so nobody can hold it back for good.
+const MAX_REVEAL_EXTENSION: u64 = 60;
+
 impl App {
     pub fn new(config: Config) -> AppResult<Self> {
-        let (client, room, log) = PokerClient::new(&config)?;
+        let (client, room, log): (Box<dyn PokerClient>, Room, Vec<LogEntry>) = match &config.follow {
+            #[cfg(unix)]
+            Some(socket) => {
+                let (client, room, log) = FollowerClient::new(socket)?;
+                (Box::new(client), room, log)
+            }
+            #[cfg(not(unix))]
+            Some(_) => return Err("Follower mode is only supported on unix platforms.".into()),
+            None => connect(&config)?,
+        };
+
+        let name = if client.is_read_only() {
+            room.players.iter().find(|p| p.is_you).map_or(config.name.clone(), |p| p.name.clone())
+        } else {
+            config.name.clone()
+        };
+
+        let chat_filter = ChatFilter::new(&config.chat_filter_words, config.hide_suspicious_links);
+
+        let overlay = match config.overlay_port {
+            Some(port) => Some(OverlayServer::bind(port)?),
+            None => None,
+        };
 
         let mut result = Self {
             running: true,
             vote: None,
-            name: config.name.clone(),
+            name,
             room,
             client,
             log: vec![],
             round_number: 1,
             round_start: Instant::now(),
+            round_log_start: 0,
+            log_round: None,
             config,
             has_focus: true,
+            last_chat_notification: None,
             notify_vote_at: None,
             is_notified: false,
             has_updates: false,
             history: vec![],
+            player_stats: HashMap::new(),
+            player_activity: HashMap::new(),
+            seen_names: HashSet::new(),
+            last_interaction: Instant::now(),
+            idle_prompt: false,
+            auto_spectating: false,
+            timebox_alerted: false,
+            estimation: EstimationState::default(),
+            topic: None,
+            confidence: HashMap::new(),
+            custom_deck: None,
+            flash: None,
+            auto_reveal_at: None,
+            auto_reveal_cancelled: false,
+            auto_restart_at: None,
+            reveal_extension: 0,
+            scheduled_reveal: None,
+            first_speaker: None,
+            reactions: vec![],
+            chat_filter,
+            revealed_chat: HashSet::new(),
+            announcements: Vec::new(),
+            status: None,
+            overlay,
+            metrics: Metrics { connections: 1, ..Metrics::default() },
+            metrics_written: None,
+            update_check: None,
+            available_update: None,
+            restart_after_update: false,
+            github: None,
+            stories: VecDeque::new(),
+            story: None,
         };
+        if let Some(source) = result.config.github_source() {
+            let github = Github::new(source);
+            github.import_issues();
+            result.github = Some(github);
+        }
+        if !result.config.skip_update_check && !result.config.update_now {
+            result.update_check = Some(check_in_background(result.config.update_source(), result.config.update_channel(), result.config.update_check_interval()));
+        }
+        result.custom_deck = result.config.custom_deck();
+        if let Some(deck) = &result.custom_deck {
+            info!("Using custom deck {} instead of the deck of the server.", deck.join(", "));
+        }
+        result.apply_custom_deck();
+        result.remember_names();
         result.update_server_log(log);
+        result.publish_state();
 
         Ok(result)
     }
 
-    pub fn tick(&mut self) {
+    pub fn tick(&mut self) -> AppResult<()> {
         self.check_notification();
+        self.check_timebox();
+        self.check_auto_reveal()?;
+        self.check_scheduled_reveal()?;
+        self.check_auto_restart()?;
+        self.check_idle()
+    }
+
+    pub fn timebox(&self) -> Option<Duration> {
+        let minutes = self.config.room_timeboxes.get(&self.room.name)
+            .copied()
+            .unwrap_or(self.config.round_timebox_minutes);
+        if minutes == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(minutes * 60))
+        }
+    }
+
+    fn check_timebox(&mut self) {
+        if self.timebox_alerted || self.room.phase != GamePhase::Playing {
+            return;
+        }
+        if let Some(timebox) = self.timebox() {
+            if self.round_start.elapsed() >= timebox {
+                self.timebox_alerted = true;
+                self.has_updates = true;
+                self.log_message(LogLevel::Error, tr("The timebox for this round has expired.").to_string());
+                ring_bell();
+                if !self.config.disable_notifications {
+                    show_notification(&self.config, tr("The timebox for this round has expired."));
+                }
+            }
+        }
+    }
+
+    fn update_auto_reveal(&mut self, old: &Room) {
+        if !self.room.everyone_voted() {
+            self.auto_reveal_at = None;
+            return;
+        }
+        if self.auto_reveal_at.is_some() || self.auto_reveal_cancelled || self.config.auto_reveal_seconds == 0
+            || self.client.is_read_only() {
+            return;
+        }
+        let armed = match self.config.auto_reveal {
+            AutoReveal::Off => false,
+            // only right when the room completes, so others voting again later don't arm it
+            AutoReveal::WhenLastVoter => !old.everyone_voted() && old.phase == GamePhase::Playing
+                && old.players.iter().any(|p| p.is_you && p.user_type != UserType::Spectator && p.vote == Vote::Missing),
+            AutoReveal::WhenAllVoted => true,
+        };
+        if armed {
+            let seconds = self.config.auto_reveal_seconds;
+            self.auto_reveal_at = Some(Instant::now() + Duration::from_secs(seconds));
+            self.log_message(LogLevel::Info, tr_args("Everyone has voted, revealing in {}s.", &[&seconds.to_string()]));
+            if self.config.screen_reader {
+                self.announcements.push(tr_args("Everyone has voted, revealing in {} seconds.", &[&seconds.to_string()]));
+            }
+            self.has_updates = true;
+        }
+    }
+
+    fn check_auto_reveal(&mut self) -> AppResult<()> {
+        if self.auto_reveal_at.is_some_and(|at| Instant::now() >= at) {
+            self.auto_reveal_at = None;
+            self.reveal()?;
+        }
+        Ok(())
+    }
+
+    fn check_scheduled_reveal(&mut self) -> AppResult<()> {
+        let Some(scheduled) = self.scheduled_reveal.take_if(|s| Instant::now() >= s.at) else {
+            return Ok(());
+        };
+        if scheduled.by == self.name && self.room.phase == GamePhase::Playing {
+            self.reveal()?;
+        }
+        Ok(())
+    }
+
+    /// Schedules the reveal for everyone in the room, announced in the chat.
+    pub fn schedule_reveal(&mut self, seconds: u64) -> AppResult<()> {
+        if self.room.phase != GamePhase::Playing {
+            self.flash_message(tr("The reveal can only be scheduled during a round."));
+            return Ok(());
+        }
+        self.chat(format!("{}reveal-in {}", COMMAND_PREFIX, seconds.clamp(1, MAX_REVEAL_DELAY)))
+    }
+
+    /// Cancels the scheduled reveal for everyone in the room.
+    pub fn cancel_scheduled_reveal(&mut self) -> AppResult<()> {
+        if self.scheduled_reveal.is_none() {
+            return Ok(());
+        }
+        self.chat(format!("{}reveal-in cancel", COMMAND_PREFIX))
+    }
+
+    /// Randomly picks who explains their vote first and announces it in the chat.
+    pub fn pick_first_speaker(&mut self) -> AppResult<()> {
+        let Some(entry) = self.history.last().filter(|_| self.room.phase == GamePhase::Revealed) else {
+            self.flash_message(tr("The first speaker is picked after the reveal."));
+            return Ok(());
+        };
+        let Some(player) = pick_first_speaker(&entry.votes, self.config.first_speaker_outliers, random()) else {
+            self.flash_message(tr("Nobody voted."));
+            return Ok(());
+        };
+        let name = player.name.clone();
+        self.chat(format!("{} explains first", name))?;
+        self.first_speaker = Some(name);
+        Ok(())
+    }
+
+    /// Reacts to the revealed cards, shown to everyone in the room.
+    pub fn react(&mut self, reaction: Reaction) -> AppResult<()> {
+        if self.room.phase != GamePhase::Revealed {
+            self.flash_message(tr("Reactions are for the revealed cards."));
+            return Ok(());
+        }
+        self.chat(format!("{}react {}", COMMAND_PREFIX, reaction.name()))
+    }
+
+    fn check_auto_restart(&mut self) -> AppResult<()> {
+        if self.auto_restart_at.is_some_and(|at| Instant::now() >= at) {
+            self.auto_restart_at = None;
+            self.restart()?;
+        }
+        Ok(())
+    }
+
+    /// Stops the auto reveal countdown until the next round.
+    pub fn cancel_auto_reveal(&mut self) {
+        self.auto_reveal_at = None;
+        self.auto_reveal_cancelled = true;
+    }
+
+    /// Gives everyone a few more seconds before the cards are revealed.
+    pub fn extend_auto_reveal(&mut self) -> AppResult<()> {
+        if self.auto_reveal_at.is_none() {
+            return Ok(());
+        }
+        self.chat(format!("{}wait {}", COMMAND_PREFIX, AUTO_REVEAL_EXTENSION))
+    }
+
+    pub fn register_interaction(&mut self) -> AppResult<()> {
+        self.last_interaction = Instant::now();
+        if self.auto_spectating {
+            self.auto_spectating = false;
+            self.log_message(LogLevel::Info, tr("Welcome back, switching you back to player.").to_string());
+            self.client.change_user_type(UserType::Player)?;
+        }
+        Ok(())
+    }
+
+    fn check_idle(&mut self) -> AppResult<()> {
+        if self.config.idle_spectate == IdleSpectate::Off
+            || self.auto_spectating
+            || self.idle_prompt
+            || self.vote.is_some()
+            || self.room.phase != GamePhase::Playing
+            || self.client.is_read_only() {
+            return Ok(());
+        }
+        if !self.room.players.iter().any(|p| p.is_you && p.user_type == UserType::Player) {
+            return Ok(());
+        }
+        let idle_since = self.last_interaction.max(self.round_start);
+        if idle_since.elapsed() < Duration::from_secs(self.config.idle_spectate_minutes * 60) {
+            return Ok(());
+        }
+
+        match self.config.idle_spectate {
+            IdleSpectate::Ask => {
+                self.idle_prompt = true;
+                self.has_updates = true;
+            }
+            IdleSpectate::Auto => {
+                self.log_message(LogLevel::Info, tr("You seem to be away, switching you to spectator until you press a key.").to_string());
+                self.client.change_user_type(UserType::Spectator)?;
+                self.auto_spectating = true;
+                self.has_updates = true;
+            }
+            IdleSpectate::Off => {}
+        }
+        Ok(())
+    }
+
+    pub fn change_user_type(&mut self, user_type: UserType) -> AppResult<()> {
+        if !self.check_writable() {
+            return Ok(());
+        }
+        self.client.change_user_type(user_type)
+    }
+
+    /// Runs the hook of the event with the room, round and own name added to `env`.
+    fn run_hook(&self, event: HookEvent, mut env: Vec<(&str, String)>) {
+        env.push(("ROOM", self.room.name.clone()));
+        env.push(("ROUND", self.round_number.to_string()));
+        env.push(("NAME", self.name.clone()));
+        hooks::run(&self.config.hooks, event, &env);
+    }
+
+    /// Shows a chat message as notification while the terminal isn't focused, at most one every 30 seconds.
+    fn notify_chat(&mut self, sender: &str, message: &str) {
+        const CHAT_NOTIFICATION_INTERVAL: Duration = Duration::from_secs(30);
+        if self.has_focus || !self.config.notify_chat || self.config.disable_notifications {
+            return;
+        }
+        if self.last_chat_notification.is_some_and(|at| at.elapsed() < CHAT_NOTIFICATION_INTERVAL) {
+            return;
+        }
+        self.last_chat_notification = Some(Instant::now());
+        let preview: String = message.chars().take(80).collect();
+        show_notification(&self.config, &format!("{}: {}", sender, preview));
+    }
+
+    /// Tells whoever reveals the cards that the last vote came in.
+    fn notify_all_voted(&mut self) {
+        if !self.config.notify_all_voted {
+            return;
+        }
+        self.log_message(LogLevel::Info, tr("All votes are in, reveal when ready.").to_string());
+        ring_bell();
+        if !self.config.disable_notifications && !self.has_focus {
+            show_notification(&self.config, tr("All votes are in, reveal when ready."));
+        }
     }
 
     fn check_notification(&mut self) {
@@ -81,7 +514,7 @@ impl App {
                         info!("Skipping notification because user has them disabled.");
                     } else {
                         info!("Notifying user of missing vote.");
-                        show_notification();
+                        show_notification(&self.config, tr("Your vote is the last one missing."));
                     }
                 }
                 self.is_notified = true;
@@ -91,25 +524,146 @@ impl App {
     }
 
     #[inline]
-    fn deck_has_value(&self, vote: &str) -> bool {
-        self.room.deck.iter().find(|item| item.eq_ignore_ascii_case(vote)).is_some()
+    fn apply_custom_deck(&mut self) {
+        if let Some(deck) = &self.custom_deck {
+            self.room.deck = deck.clone();
+        }
+    }
+
+    /// Whether the own vote should be masked, so it doesn't leak on a shared screen before the reveal.
+    pub fn hides_own_vote(&self) -> bool {
+        self.config.hide_own_vote && self.room.phase == GamePhase::Playing
+    }
+
+    /// A player is away if they still have to vote but haven't voted or chatted for a while.
+    pub fn is_away(&self, player: &Player) -> bool {
+        if self.config.away_after_minutes == 0
+            || player.is_you
+            || player.vote != Vote::Missing
+            || self.room.phase != GamePhase::Playing {
+            return false;
+        }
+        self.player_activity.get(player.key()).is_some_and(|last_activity| {
+            last_activity.elapsed() > Duration::from_secs(self.config.away_after_minutes * 60)
+        })
+    }
+
+    fn remember_names(&mut self) {
+        self.seen_names.extend(self.room.players.iter().map(|p| p.name.clone()));
+    }
+
+    fn track_activity(&mut self, old: &Room) {
+        self.remember_names();
+        let now = Instant::now();
+        for player in &self.room.players {
+            let previous = old.players.iter().find(|p| p.is_same(player));
+            if previous.is_none_or(|p| p.vote != player.vote) {
+                self.player_activity.insert(player.key().to_string(), now);
+            }
+        }
+    }
+
+    /// Whether another player in the room has your name, which makes the log and the history ambiguous.
+    pub fn has_duplicate_name(&self) -> bool {
+        self.room.players.iter().any(|p| !p.is_you && p.name == self.name)
+    }
+
+    fn log_renames(&mut self, old: &Room) {
+        let renames: Vec<String> = self.room.players.iter()
+            .filter_map(|player| {
+                let previous = old.players.iter().find(|p| p.id.is_some() && p.id == player.id)?;
+                (previous.name != player.name).then(|| tr_args("{} is now known as {}.", &[&previous.name, &player.name]))
+            })
+            .collect();
+        for message in renames {
+            self.log_message(LogLevel::Info, message);
+        }
+    }
+
+    /// Tells about a changed deck and takes back the own vote if its card is gone.
+    fn check_deck(&mut self, old: &Room) {
+        if old.deck == self.room.deck {
+            return;
+        }
+        self.log_message(LogLevel::Info, tr_args("Deck changed to: {}", &[&self.room.deck.join(", ")]));
+        self.flash_message(tr("The deck changed."));
+        let Some(vote) = &self.vote else {
+            return;
+        };
+        let vote = vote.to_string();
+        if deck_card(&vote, &self.room.deck).is_some() {
+            return;
+        }
+        self.vote = None;
+        self.auto_reveal_at = None;
+        if self.room.phase == GamePhase::Playing && !self.client.is_read_only() {
+            if let Err(e) = self.client.vote(None) {
+                error!("Failed to retract the vote: {}", e);
+            }
+        }
+        self.log_message(LogLevel::Error, tr_args("Your vote {} is not in the deck anymore, please vote again.", &[&vote.to_string()]));
     }
 
     #[inline]
     fn is_my_vote_last_missing(&self) -> bool {
         self.room.players.len() > 1
-            && self.room.players.iter().filter(|p| p.vote == Vote::Missing).count() == 1
+            && self.room.players.iter()
+                // spectators never vote, like players switched to spectator while idle
+                .filter(|p| p.user_type != UserType::Spectator && p.vote == Vote::Missing)
+                .filter(|p| !self.config.ignore_away_players || !self.is_away(p))
+                .count() == 1
             && self.vote.is_none()
             && self.room.phase == GamePhase::Playing
+            && !self.client.is_read_only()
+    }
+
+    /// Round trip times to the server.
+    pub fn latency(&self) -> Option<LatencyStats> {
+        self.client.latency()
+    }
+
+    /// Joins and leaves of the last minute, many of them point to clients that keep reconnecting.
+    pub fn join_leave_rate(&self) -> usize {
+        join_leave_rate(&self.log, Instant::now())
+    }
+
+    /// Whether the round waits for your vote, ignoring the other players.
+    pub fn is_own_vote_missing(&self) -> bool {
+        self.room.phase == GamePhase::Playing
+            && self.room.players.iter().any(|p| p.is_you && p.user_type == UserType::Player && p.vote == Vote::Missing)
+    }
+
+    fn check_writable(&mut self) -> bool {
+        if self.client.is_read_only() {
+            self.log_message(LogLevel::Error, tr("This is a read-only mirror of another instance.").to_string());
+            return false;
+        }
+        true
     }
 
     pub fn new_phase(&mut self, _old: &Room) {
+        self.scheduled_reveal = None;
+        self.first_speaker = None;
         if self.room.phase == GamePhase::Playing {
             self.vote = None;
             self.round_number += 1;
             self.is_notified = false;
             self.notify_vote_at = None;
+            self.timebox_alerted = false;
+            self.topic = None;
+            self.confidence.clear();
+            self.reactions.clear();
+            self.auto_reveal_at = None;
+            self.auto_reveal_cancelled = false;
+            self.auto_restart_at = None;
+            self.reveal_extension = 0;
             self.round_start = Instant::now();
+            // the discussion after the reveal belongs to the previous round
+            if let Some(previous) = self.history.last_mut() {
+                previous.log.end = self.log.len();
+            }
+            self.round_log_start = self.log.len();
+            self.run_hook(HookEvent::RoundStart, vec![]);
         }
         self.has_updates = true;
 
@@ -121,8 +675,47 @@ impl App {
                 votes: self.room.players.clone(),
                 deck: self.room.deck.clone(),
                 own_vote: self.vote.clone(),
+                topic: self.topic.clone(),
+                special_votes: count_special_votes(&self.room.players),
+                consensus: is_consensus(&self.room.players),
+                confidence: self.confidence.clone(),
+                log: self.round_log_start..self.log.len(),
             };
+            if entry.consensus && self.config.notify_consensus {
+                ring_bell();
+                if !self.config.disable_notifications {
+                    show_notification(&self.config, tr("Consensus!"));
+                }
+            }
+            let votes: Vec<String> = entry.votes.iter().map(|p| format!("{}={}", p.name, p.vote)).collect();
+            self.run_hook(HookEvent::Reveal, vec![
+                ("AVERAGE", format!("{:.1}", entry.average)),
+                ("VOTES", votes.join("; ")),
+                ("TOPIC", entry.topic.clone().unwrap_or_default()),
+            ]);
+            if let Some(path) = &self.config.journal_file {
+                if let Err(e) = append_journal(path, &self.room.name, &entry) {
+                    self.log_message(LogLevel::Error, tr_args("Failed to write the journal: {}", &[&e.to_string()]));
+                }
+            }
+            if let (Some(integration), Some(url), IntegrationSummary::Round) = (self.config.integration, &self.config.webhook_url, self.config.integration_summary) {
+                let message = integration::round_message(integration, &self.room.name, &entry, self.story.as_ref());
+                integration::post_in_background(url.clone(), message);
+            }
+            if let Some(story) = &self.story {
+                let message = tr_args("Post the estimate to #{} with :post or :post <card>.", &[&story.number.to_string()]);
+                self.log_message(LogLevel::Info, message);
+            }
+            record_round(&mut self.player_stats, &entry.votes);
+            self.metrics.record_round(entry.length);
             self.history.push(entry);
+
+            if self.config.auto_restart_delay > 0 && !self.client.is_read_only() {
+                self.auto_restart_at = Some(Instant::now() + Duration::from_secs(self.config.auto_restart_delay));
+            }
+        }
+        if self.config.screen_reader {
+            self.announcements.push(phase_announcement(&self.room, self.round_number));
         }
     }
 
@@ -130,61 +723,347 @@ impl App {
         debug!("room update: {:?}", update);
 
         let old = mem::replace(&mut self.room, update);
+        self.apply_custom_deck();
+        self.check_deck(&old);
+        self.track_activity(&old);
+        self.log_renames(&old);
+        let was_duplicate = old.players.iter().any(|p| !p.is_you && p.name == self.name);
+        if self.has_duplicate_name() && !was_duplicate {
+            self.log_message(LogLevel::Error, tr_args("Someone else in the room is called {} as well, press n to rename.", &[&self.name]));
+        }
+        if self.config.screen_reader {
+            self.announcements.extend(vote_announcements(&old, &self.room));
+        }
         if old.phase != self.room.phase {
             self.new_phase(&old);
         }
+        self.update_auto_reveal(&old);
+        if self.room.everyone_voted() && !old.everyone_voted() {
+            self.notify_all_voted();
+        }
 
         if self.is_my_vote_last_missing() {
             if !self.is_notified && self.notify_vote_at == None {
-                self.log_message(LogLevel::Info, "Your vote is the last one missing.".to_string());
+                self.log_message(LogLevel::Info, tr("Your vote is the last one missing.").to_string());
+                self.run_hook(HookEvent::MyVoteLastMissing, vec![]);
                 self.notify_vote_at = Some(Instant::now() + Duration::from_secs(15));
                 self.has_updates = true;
             }
         } else {
             self.notify_vote_at = None;
         }
+
+        self.publish_state();
+    }
+
+    /// Passes the room on to the state file and the overlay, if enabled.
+    fn publish_state(&self) {
+        if self.config.state_file || self.overlay.is_some() {
+            let my_vote = if self.hides_own_vote() {
+                self.vote.as_ref().map(|_| VoteData::Special("hidden".to_string()))
+            } else {
+                self.vote.clone()
+            };
+            let state = RoomState::new(&self.room, self.round_number, &my_vote);
+            if self.config.state_file {
+                state_file::write(&state);
+            }
+            if let Some(overlay) = &self.overlay {
+                overlay.publish(&state);
+            }
+        }
     }
 
     pub fn vote(&mut self, data: &str) -> AppResult<()> {
+        if !self.check_writable() {
+            return Ok(());
+        }
         let data = data.trim();
+        if self.estimation.is_active() {
+            match self.estimation.vote_command(data, &self.room.deck) {
+                Ok(command) => self.client.chat(command.as_str())?,
+                Err(message) => self.log_message(LogLevel::Error, message),
+            }
+            return Ok(());
+        }
         if data == "-" {
             self.vote = None;
             return Ok(());
         }
 
-        if self.deck_has_value(data) {
-            let numeric = data.parse::<u8>();
-            if numeric.is_ok() {
-                let vote = VoteData::Number(numeric.unwrap());
-                self.client.vote(Some(format!("{}", &vote).as_str()))?;
-                self.vote = Some(vote);
-            } else {
-                let vote = VoteData::Special(data.to_string());
-                self.client.vote(Some(data))?;
-                self.vote = Some(vote);
+        let (data, confidence) = match data.split_once(' ') {
+            Some((card, confidence)) => match parse_confidence(confidence) {
+                Some(confidence) => (card.trim(), Some(confidence)),
+                None => {
+                    self.log_message(LogLevel::Error, tr_args("Confidence must be between 1 and {}.", &[&MAX_CONFIDENCE.to_string()]));
+                    return Ok(());
+                }
+            },
+            None => (data, None),
+        };
+
+        let Some(card) = deck_card(data, &self.room.deck).map(str::to_string) else {
+            let message = match suggest_card(data, &self.room.deck) {
+                Some(card) => tr_args("Card is not in the deck: {}, did you mean {}?", &[data, card]),
+                None => tr_args("Card is not in the deck: {}", &[data]),
+            };
+            self.flash_message(&message);
+            self.log_message(LogLevel::Error, message);
+            return Ok(());
+        };
+        let vote = match card.parse::<u8>() {
+            Ok(number) => VoteData::Number(number),
+            Err(_) => VoteData::Special(card),
+        };
+        self.client.vote(Some(vote.to_string().as_str()))?;
+        self.vote = Some(vote);
+        if let Some(confidence) = confidence {
+            self.client.chat(format!("{}confidence {}", COMMAND_PREFIX, confidence).as_str())?;
+        }
+        Ok(())
+    }
+
+    /// Takes back the own vote of the current round.
+    pub fn retract_vote(&mut self) -> AppResult<()> {
+        if !self.check_writable() || self.room.phase != GamePhase::Playing {
+            return Ok(());
+        }
+        self.client.vote(None)?;
+        self.vote = None;
+        self.auto_reveal_at = None;
+        self.flash_message(tr("Vote retracted."));
+        Ok(())
+    }
+
+    /// Opens the most recent link posted in the log in the browser.
+    pub fn open_last_link(&mut self) {
+        // links hidden by the chat filter are skipped until they are revealed
+        let url = self.log.iter().rev().find_map(|entry| {
+            let text = self.chat_text(entry, &entry.message);
+            find_urls(&text).pop().map(|range| text[range].to_string())
+        });
+        match url {
+            Some(url) => match open_url(&url) {
+                Ok(()) => self.flash_message(&tr_args("Opened {}", &[&url])),
+                Err(e) => self.log_message(LogLevel::Error, tr_args("Failed to open {}: {}", &[&url, &e.to_string()])),
+            },
+            None => self.flash_message(tr("No link in the log")),
+        }
+    }
+
+    /// The text of a chat message as shown, masked by the chat filter unless it was revealed.
+    pub fn chat_text<'a>(&self, entry: &LogEntry, message: &'a str) -> Cow<'a, str> {
+        if entry.level != LogLevel::Chat || self.revealed_chat.contains(&entry.time) {
+            return Cow::Borrowed(message);
+        }
+        self.chat_filter.mask(message).map_or(Cow::Borrowed(message), Cow::Owned)
+    }
+
+    /// Shows the newest chat message the filter still masks as it was written.
+    pub fn reveal_chat(&mut self) {
+        let hidden = self.log.iter().rev()
+            .find(|entry| matches!(self.chat_text(entry, &entry.message), Cow::Owned(_)));
+        match hidden.map(|entry| entry.time) {
+            Some(time) => {
+                self.revealed_chat.insert(time);
+                self.has_updates = true;
             }
-        } else {
-            self.log_message(LogLevel::Error, format!("Card is not in the deck: {}", data));
+            None => self.flash_message(tr("No hidden chat message")),
+        }
+    }
+
+    /// Shows a short status message for a few seconds.
+    pub fn flash_message(&mut self, message: &str) {
+        self.flash = Some((message.to_string(), Instant::now()));
+        self.has_updates = true;
+    }
+
+    pub fn current_flash(&self) -> Option<&str> {
+        const FLASH_DURATION: Duration = Duration::from_secs(3);
+        self.flash.as_ref()
+            .filter(|(_, shown_at)| shown_at.elapsed() < FLASH_DURATION)
+            .map(|(message, _)| message.as_str())
+    }
+
+    pub fn set_technique(&mut self, input: &str) -> AppResult<()> {
+        if !self.check_writable() {
+            return Ok(());
+        }
+        match technique_command(input) {
+            Ok(command) => self.client.chat(command.as_str())?,
+            Err(message) => self.log_message(LogLevel::Error, message),
         }
         Ok(())
     }
 
+    /// Leaves the current room and joins another one, starting a fresh session.
+    pub fn join_room(&mut self, room: String) -> AppResult<()> {
+        if !self.check_writable() {
+            return Ok(());
+        }
+        let mut config = self.config.clone();
+        config.name = room_names::remembered_name(&room)
+            .filter(|_| !config.name_from_cli)
+            .unwrap_or_else(|| self.name.clone());
+        config.room = room;
+        let (client, room, log) = connect(&config)?;
+        self.name = config.name.clone();
+        self.metrics.connections += 1;
+
+        self.client = client;
+        self.room = room;
+        self.apply_custom_deck();
+        self.config = config;
+        self.vote = None;
+        self.log.clear();
+        self.history.clear();
+        self.round_log_start = 0;
+        self.log_round = None;
+        self.scheduled_reveal = None;
+        self.reveal_extension = 0;
+        self.reactions.clear();
+        self.player_stats.clear();
+        self.round_number = 1;
+        self.round_start = Instant::now();
+        self.player_activity.clear();
+        self.seen_names.clear();
+        self.remember_names();
+        self.estimation = EstimationState::default();
+        self.confidence.clear();
+        self.is_notified = false;
+        self.notify_vote_at = None;
+        self.timebox_alerted = false;
+        self.has_updates = true;
+        self.update_server_log(log);
+        info!("Joined room {}.", self.room.name);
+
+        Ok(())
+    }
+
+    pub fn player_role(&self, player: &Player) -> Role {
+        roles::player_role(&player.name, &self.config.roles)
+    }
+
+    pub fn player_group(&self, player: &Player) -> Option<String> {
+        groups::player_group(&player.name, &self.config.groups)
+    }
+
+    /// Averages of the revealed votes per role, empty unless the players have different roles.
+    pub fn role_averages(&self) -> Vec<(Role, f32)> {
+        roles::role_averages(&self.room.players, &self.config.roles)
+    }
+
+    /// Joins a new room with the given deck, `None` keeps the deck of the server, and copies the invite.
+    pub fn create_room(&mut self, room: String, deck: Option<String>) -> AppResult<()> {
+        if !self.check_writable() {
+            return Ok(());
+        }
+        let previous = mem::replace(&mut self.config.deck, deck);
+        let custom_deck = self.config.custom_deck();
+        let previous_custom_deck = mem::replace(&mut self.custom_deck, custom_deck);
+        if let Err(e) = self.join_room(room) {
+            self.config.deck = previous;
+            self.custom_deck = previous_custom_deck;
+            return Err(e);
+        }
+        self.copy_invite();
+        Ok(())
+    }
+
+    /// Copies the command line joining the current room to the clipboard and shows it in the log.
+    pub fn copy_invite(&mut self) {
+        let invite = invite::invite(&self.config);
+        if let Err(e) = invite::copy_to_clipboard(&invite) {
+            error!("Failed to copy the invite: {}", e);
+        }
+        self.log_message(LogLevel::Info, tr_args("Invite: {}", &[&invite]));
+        self.flash_message(tr("Invite copied to the clipboard."));
+    }
+
     pub fn rename(&mut self, data: String) -> AppResult<()> {
+        if !self.check_writable() {
+            return Ok(());
+        }
         self.name = data;
         self.client.change_name(self.name.as_str())?;
+        room_names::remember_name(&self.room.name, &self.name);
 
         Ok(())
     }
 
+    /// Revealing or restarting in a phase of a newer server version could do anything.
+    fn check_known_phase(&mut self) -> bool {
+        if self.room.phase == GamePhase::Unknown {
+            self.log_message(LogLevel::Error, tr(UNKNOWN_PHASE_WARNING).to_string());
+            return false;
+        }
+        true
+    }
+
     pub fn reveal(&mut self) -> AppResult<()> {
+        if !self.check_writable() || !self.check_known_phase() {
+            return Ok(());
+        }
         self.client.reveal()
     }
 
     pub fn chat(&mut self, message: String) -> AppResult<()> {
-        self.client.chat(message.as_str())
+        if !self.check_writable() {
+            return Ok(());
+        }
+        self.client.chat(message.as_str())?;
+        let queued = self.client.queued_messages();
+        if queued > 0 {
+            self.flash_message(&tr_args("Message queued, {} waiting to be sent", &[&queued.to_string()]));
+        }
+        Ok(())
+    }
+
+    /// Sends a message from the chat input, interpreting IRC-style slash commands.
+    pub fn chat_input(&mut self, input: String) -> AppResult<()> {
+        let input = input.trim();
+        if let Some(escaped) = input.strip_prefix("//") {
+            return self.chat(format!("/{}", escaped));
+        }
+        let Some(command) = input.strip_prefix('/') else {
+            return self.chat(input.to_string());
+        };
+        let (command, argument) = command.split_once(' ').map_or((command, ""), |(c, a)| (c, a.trim()));
+        match command {
+            "rename" if !argument.is_empty() => self.rename(argument.to_string()),
+            "reveal" => self.reveal(),
+            "reset" => self.restart(),
+            "spectate" => {
+                let spectating = self.room.players.iter().any(|p| p.is_you && p.user_type == UserType::Spectator);
+                self.change_user_type(if spectating { UserType::Player } else { UserType::Spectator })
+            }
+            "topic" => self.chat(format!("{}topic {}", COMMAND_PREFIX, argument)),
+            _ => self.chat(input.to_string()),
+        }
+    }
+
+    /// Posts the deck card closest to the average of the revealed round to the chat.
+    pub fn post_suggestion(&mut self) -> AppResult<()> {
+        if self.room.phase != GamePhase::Revealed {
+            return Ok(());
+        }
+        let average = self.average_votes();
+        let Some(card) = nearest_card(average, &self.room.deck) else {
+            self.log_message(LogLevel::Error, tr("There are no numeric votes to suggest a card from.").to_string());
+            return Ok(());
+        };
+        let mut message = format!("Suggested estimate: {} (average {:.1}", card, average);
+        if let Some(majority) = majority_vote(&self.room.players) {
+            message.push_str(&format!(", majority {}", majority));
+        }
+        message.push(')');
+        self.chat(message)
     }
 
     pub fn restart(&mut self) -> AppResult<()> {
+        if !self.check_writable() || !self.check_known_phase() {
+            return Ok(());
+        }
         self.vote = None;
         self.client.reset()
     }
@@ -192,27 +1071,233 @@ impl App {
     pub fn update(&mut self) -> AppResult<()> {
         let (room_updates, log_updates) = self.client.get_updates()?;
         // TODO: reconnect?
+        self.metrics.messages_received += room_updates.len() as u64;
+        for error in self.client.take_server_errors() {
+            self.metrics.server_errors += 1;
+            self.log_message(LogLevel::Error, error);
+        }
 
         for update in room_updates {
             self.merge_update(update);
         }
 
         self.update_server_log(log_updates);
+        self.check_update();
+        self.check_github();
+        self.write_status();
+        if self.metrics_written.is_none_or(|at| at.elapsed() >= METRICS_INTERVAL) {
+            self.write_metrics();
+        }
+
+        Ok(())
+    }
+
+    fn check_update(&mut self) {
+        let Some(receiver) = &self.update_check else {
+            return;
+        };
+        if let Ok(update) = receiver.try_recv() {
+            info!("Update to v{} available.", update.release.version);
+            self.available_update = Some(update);
+            self.update_check = None;
+        }
+    }
 
+    fn check_github(&mut self) {
+        let Some(github) = &self.github else {
+            return;
+        };
+        let repository = github.repository();
+        for result in github.results() {
+            match result {
+                GithubResult::Imported(issues) => {
+                    self.log_message(LogLevel::Info, tr_args("Imported {} issues from {}, :next starts the first.", &[&issues.len().to_string(), &repository]));
+                    self.stories = issues.into();
+                }
+                GithubResult::Posted(number) => self.log_message(LogLevel::Info, tr_args("Posted the estimate to #{}.", &[&number.to_string()])),
+                GithubResult::Failed(message) => self.log_message(LogLevel::Error, message),
+            }
+        }
+    }
+
+    /// Makes the next imported issue the topic of the round for everyone.
+    pub fn next_story(&mut self) -> AppResult<()> {
+        if !self.check_writable() {
+            return Ok(());
+        }
+        let Some(story) = self.stories.pop_front() else {
+            self.log_message(LogLevel::Error, tr("There are no more imported issues.").to_string());
+            return Ok(());
+        };
+        self.chat(format!("{}topic #{} {}", COMMAND_PREFIX, story.number, story.title))?;
+        self.log_message(LogLevel::Info, story.html_url.clone());
+        self.story = Some(story);
         Ok(())
     }
 
+    /// Comments the estimate on the issue of the round, the card closest to the average if none is given.
+    pub fn post_estimate(&mut self, card: Option<&str>) {
+        let (Some(github), Some(story)) = (&self.github, &self.story) else {
+            self.log_message(LogLevel::Error, tr("There is no imported issue to post the estimate to, start one with :next.").to_string());
+            return;
+        };
+        if self.room.phase != GamePhase::Revealed {
+            self.log_message(LogLevel::Error, tr("The estimate can only be posted after the reveal.").to_string());
+            return;
+        }
+        let average = self.average_votes();
+        let card = match card {
+            Some(card) => deck_card(card, &self.room.deck).unwrap_or(card).to_string(),
+            None => match nearest_card(average, &self.room.deck) {
+                Some(card) => card.to_string(),
+                None => {
+                    self.log_message(LogLevel::Error, tr("There are no numeric votes, post the estimate with :post <card>.").to_string());
+                    return;
+                }
+            },
+        };
+        let votes: Vec<String> = self.room.players.iter()
+            .filter(|p| p.user_type != UserType::Spectator)
+            .map(|p| format!("{}={}", p.name, p.vote))
+            .collect();
+        let body = format!("Estimated **{}** in planning poker (average {:.1}, votes: {}).", card, average, votes.join(", "));
+        github.post_comment(story.number, body);
+        let number = story.number;
+        self.log_message(LogLevel::Info, tr_args("Posting the estimate {} to #{}...", &[&card, &number.to_string()]));
+    }
+
+    /// Keeps the status file of the room current, rewriting it now and then so readers know the instance still runs.
+    fn write_status(&mut self) {
+        let status = Status::new(&self.room, self.is_own_vote_missing());
+        let unchanged = self.status.as_ref()
+            .is_some_and(|(last, at)| last.same_as(&status) && at.elapsed() < status::REFRESH_INTERVAL);
+        if !unchanged {
+            status::write(&status_file(&self.room.name), &status);
+            self.status = Some((status, Instant::now()));
+        }
+    }
+
+    /// Passes the metrics on to the metrics file and the overlay, if enabled.
+    pub fn write_metrics(&mut self) {
+        if self.config.metrics_file.is_none() && self.overlay.is_none() {
+            return;
+        }
+        let text = self.metrics.render(&self.room.name);
+        if let Some(path) = &self.config.metrics_file {
+            write_textfile(Path::new(path), &text);
+        }
+        if let Some(overlay) = &self.overlay {
+            overlay.publish_metrics(text);
+        }
+        self.metrics_written = Some(Instant::now());
+    }
+
+    pub fn remove_state_files(&self) {
+        if self.status.is_some() {
+            status::remove(&status_file(&self.room.name));
+        }
+        if self.config.state_file {
+            state_file::remove(&self.room.name);
+        }
+    }
+
     fn update_server_log(&mut self, log_updates: Vec<LogEntry>) {
         for log in log_updates {
             if self.log.iter().find(|l| l.server_index == log.server_index).is_none() {
+                if let Some(sender) = log.chat_sender(&self.room.players) {
+                    let key = self.room.players.iter()
+                        .find(|p| p.name == sender)
+                        .map_or(sender, |p| p.key())
+                        .to_string();
+                    self.player_activity.insert(key.clone(), Instant::now());
+                    let message = log.message[sender.len()..].trim_start_matches(':').trim_start();
+                    if let Some(topic) = command_argument(message, "topic") {
+                        self.topic = Some(topic.to_string()).filter(|t| !t.is_empty());
+                        self.has_updates = true;
+                    }
+                    let pending_reveal = self.auto_reveal_at.is_some() || self.scheduled_reveal.is_some();
+                    if let Some(seconds) = command_argument(message, "wait").filter(|_| pending_reveal) {
+                        let seconds = seconds.parse().unwrap_or(AUTO_REVEAL_EXTENSION)
+                            .min(MAX_REVEAL_EXTENSION - self.reveal_extension);
+                        self.reveal_extension += seconds;
+                        if let Some(at) = &mut self.auto_reveal_at {
+                            *at += Duration::from_secs(seconds);
+                            self.has_updates = true;
+                        }
+                        if let Some(scheduled) = &mut self.scheduled_reveal {
+                            scheduled.at += Duration::from_secs(seconds);
+                            self.has_updates = true;
+                        }
+                    }
+                    if let Some(reaction) = command_argument(message, "react").and_then(Reaction::parse) {
+                        self.reactions.retain(|(name, _)| name != sender);
+                        self.reactions.push((sender.to_string(), reaction));
+                        self.has_updates = true;
+                    }
+                    if let Some(argument) = command_argument(message, "reveal-in") {
+                        self.apply_reveal_in(sender, argument);
+                    }
+                    if let Some(confidence) = command_argument(message, "confidence").and_then(parse_confidence) {
+                        self.confidence.insert(key.clone(), confidence);
+                    }
+                    self.estimation.apply(sender, message);
+                    if !message.starts_with(COMMAND_PREFIX) && sender != self.name {
+                        self.notify_chat(sender, message);
+                        if mentions(message, &self.name) {
+                            self.run_hook(HookEvent::ChatMention, vec![("SENDER", sender.to_string()), ("MESSAGE", message.to_string())]);
+                        }
+                    }
+                }
                 self.log.push(log);
             }
         }
     }
 
+    /// Schedules or cancels the reveal of a `!pp reveal-in <seconds>|cancel` chat command.
+    fn apply_reveal_in(&mut self, sender: &str, argument: &str) {
+        let message = if argument == "cancel" {
+            if self.scheduled_reveal.take().is_none() {
+                return;
+            }
+            tr_args("{} cancelled the scheduled reveal.", &[sender])
+        } else {
+            let Ok(seconds) = argument.parse::<u64>() else {
+                return;
+            };
+            if self.room.phase != GamePhase::Playing {
+                return;
+            }
+            let seconds = seconds.clamp(1, MAX_REVEAL_DELAY);
+            self.scheduled_reveal = Some(ScheduledReveal {
+                at: Instant::now() + Duration::from_secs(seconds),
+                by: sender.to_string(),
+            });
+            tr_args("{} scheduled the reveal in {}s.", &[sender, &seconds.to_string()])
+        };
+        self.has_updates = true;
+        if self.config.screen_reader {
+            self.announcements.push(message.clone());
+        }
+        self.log_message(LogLevel::Info, message);
+    }
+
+    /// Log of the round at `index` of the history, up to now while no new round has started.
+    pub fn round_log(&self, index: usize) -> &[LogEntry] {
+        let Some(entry) = self.history.get(index) else {
+            return &[];
+        };
+        let end = if index + 1 == self.history.len() && entry.round_number == self.round_number {
+            self.log.len()
+        } else {
+            entry.log.end.min(self.log.len())
+        };
+        &self.log[entry.log.start.min(end)..end]
+    }
+
     pub fn log_message(&mut self, level: LogLevel, message: String) {
         self.log.push(LogEntry {
             timestamp: Instant::now(),
+            time: SystemTime::now(),
             level,
             message,
             source: LogSource::Client,
@@ -221,14 +1306,42 @@ impl App {
     }
 
     pub fn average_votes(&self) -> f32 {
-        let mut sum = 0f32;
-        let mut count = 0f32;
-        for player in &self.room.players {
-            if let Vote::Revealed(VoteData::Number(n)) = player.vote {
-                sum += n as f32;
-                count += 1f32;
-            }
+        self.room.average_votes()
+    }
+
+    /// Chat messages of this session, without the commands driving the shared state.
+    pub fn session_notes(&self) -> Vec<String> {
+        self.log.iter()
+            .filter(|l| l.level == LogLevel::Chat)
+            .filter(|l| {
+                let message = match l.chat_sender(&self.room.players) {
+                    Some(sender) => l.message[sender.len()..].trim_start_matches(':').trim_start(),
+                    None => l.message.as_str(),
+                };
+                !message.starts_with(COMMAND_PREFIX)
+            })
+            .map(|l| format!("{} {}", format_time_of_day(l.time), l.message))
+            .collect()
+    }
+
+    /// Posts the summary of all rounds to the integration, if it is configured to post one per session.
+    pub fn post_session_summary(&self) -> AppResult<bool> {
+        let (Some(integration), Some(url), IntegrationSummary::Session) = (self.config.integration, &self.config.webhook_url, self.config.integration_summary) else {
+            return Ok(false);
+        };
+        if self.history.is_empty() {
+            return Ok(false);
         }
-        sum / count
+        integration::post(url, &integration::session_message(integration, &self.room.name, &self.history))?;
+        Ok(true)
+    }
+
+    /// Writes the session summary into the configured directory, if there is one.
+    pub fn write_session_summary(&self) -> AppResult<Option<PathBuf>> {
+        let Some(dir) = &self.config.summary_dir else {
+            return Ok(None);
+        };
+        fs::create_dir_all(dir)?;
+        export_session_summary(&self.history, &self.room.name, &self.session_notes(), Some(dir)).map(Some)
     }
 }
\ No newline at end of file
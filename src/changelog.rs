@@ -0,0 +1,139 @@
+//! Structure of release notes as written by release-please: a heading per version, sections like `Features` and
+//! `Bug Fixes` with one item per change, read from the blocks of `ui::markdown`. Used by the update dialog and the
+//! update prompt on the terminal.
+
+use crate::ui::markdown::{parse_blocks, Block};
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChangelogVersion {
+    pub version: String,
+    pub date: Option<String>,
+    pub sections: Vec<ChangelogSection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChangelogSection {
+    pub heading: String,
+    pub items: Vec<String>,
+}
+
+impl ChangelogVersion {
+    fn count(&self, heading: &str) -> usize {
+        self.sections.iter()
+            .filter(|section| section.heading.eq_ignore_ascii_case(heading))
+            .map(|section| section.items.len())
+            .sum()
+    }
+
+    pub fn features(&self) -> usize {
+        self.count("Features")
+    }
+
+    pub fn fixes(&self) -> usize {
+        self.count("Bug Fixes")
+    }
+}
+
+/// Reads version headings (`## [0.3.2](url) (2024-06-13)` or `## v0.3.2`), section headings (`###`) and their top
+/// level list items. Text outside of a version is ignored.
+pub fn parse(text: &str) -> Vec<ChangelogVersion> {
+    let mut versions: Vec<ChangelogVersion> = vec![];
+    for block in parse_blocks(text) {
+        match block {
+            Block::Heading { level: 2, text } => versions.push(parse_version_heading(&text)),
+            Block::Heading { level: 3, text } => {
+                if let Some(version) = versions.last_mut() {
+                    version.sections.push(ChangelogSection { heading: text, items: vec![] });
+                }
+            }
+            Block::ListItem { level: 0, text } => {
+                if let Some(section) = versions.last_mut().and_then(|v| v.sections.last_mut()) {
+                    section.items.push(text.trim().to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    versions
+}
+
+fn parse_version_heading(heading: &str) -> ChangelogVersion {
+    let heading = heading.trim();
+    let (version, rest) = match heading.strip_prefix('[').and_then(|h| h.split_once(']')) {
+        Some((version, rest)) => (version, rest),
+        None => heading.split_once(' ').unwrap_or((heading, "")),
+    };
+    let date = rest.rsplit_once('(')
+        .and_then(|(_, date)| date.strip_suffix(')'))
+        .filter(|date| date.len() == 10 && date.chars().all(|c| c.is_ascii_digit() || c == '-'))
+        .map(str::to_string);
+    ChangelogVersion {
+        version: version.trim().trim_start_matches('v').to_string(),
+        date,
+        sections: vec![],
+    }
+}
+
+/// Short description like `3 features and 2 fixes`, `None` if the notes list neither.
+pub fn summary(versions: &[ChangelogVersion]) -> Option<String> {
+    let features: usize = versions.iter().map(ChangelogVersion::features).sum();
+    let fixes: usize = versions.iter().map(ChangelogVersion::fixes).sum();
+    let count = |count: usize, one: &str, many: &str| format!("{} {}", count, if count == 1 { one } else { many });
+    match (features, fixes) {
+        (0, 0) => None,
+        (features, 0) => Some(count(features, "feature", "features")),
+        (0, fixes) => Some(count(fixes, "fix", "fixes")),
+        (features, fixes) => Some(format!("{} and {}", count(features, "feature", "features"), count(fixes, "fix", "fixes"))),
+    }
+}
+
+/// The item without the trailing links to commits and pull requests, e.g. `fix layout ([abc1234](url))`.
+pub fn plain_item(item: &str) -> &str {
+    match item.find(" ([") {
+        Some(index) => &item[..index],
+        None => item,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTES: &str = "# Changelog
+
+## [0.3.2](https://github.com/ja-ko/ppoker/compare/v0.3.1...v0.3.2) (2024-06-13)
+
+
+### Features
+
+* **ui:** display player type in vote view ([2afeba7](https://github.com/ja-ko/ppoker/commit/2afeba7))
+
+## v0.3.1
+
+### Bug Fixes
+
+* fixes the failing build on macos ([54347d6](https://github.com/ja-ko/ppoker/commit/54347d6))
+* second fix
+";
+
+    #[test]
+    fn parses_versions_and_sections() {
+        let versions = parse(NOTES);
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].version, "0.3.2");
+        assert_eq!(versions[0].date.as_deref(), Some("2024-06-13"));
+        assert_eq!(versions[0].features(), 1);
+        assert_eq!(versions[1].version, "0.3.1");
+        assert_eq!(versions[1].date, None);
+        assert_eq!(versions[1].fixes(), 2);
+        assert_eq!(plain_item(&versions[1].sections[0].items[0]), "fixes the failing build on macos");
+    }
+
+    #[test]
+    fn summarizes_features_and_fixes() {
+        let versions = parse(NOTES);
+        assert_eq!(summary(&versions).as_deref(), Some("1 feature and 2 fixes"));
+        assert_eq!(summary(&versions[1..]).as_deref(), Some("2 fixes"));
+        assert_eq!(summary(&[]), None);
+    }
+}
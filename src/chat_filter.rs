@@ -0,0 +1,85 @@
+//! Masks configured words and suspicious links in the chat before it is shown, for screen shared terminals.
+//!
+//! Only the display is affected, the log keeps the original messages.
+
+use std::net::IpAddr;
+
+use regex::Regex;
+
+use crate::links::find_urls;
+
+/// Shown in place of a suspicious link.
+const HIDDEN_LINK: &str = "[link hidden]";
+
+pub struct ChatFilter {
+    words: Option<Regex>,
+    links: bool,
+}
+
+impl ChatFilter {
+    pub fn new(words: &[String], links: bool) -> Self {
+        let words: Vec<String> = words.iter()
+            .map(|w| w.trim())
+            .filter(|w| !w.is_empty())
+            .map(regex::escape)
+            .collect();
+        let words = (!words.is_empty())
+            .then(|| Regex::new(&format!(r"(?i)\b(?:{})\b", words.join("|"))).expect("invalid chat filter regex"));
+        Self { words, links }
+    }
+
+    /// The message with every filtered word replaced by stars and suspicious links hidden, `None` if nothing was masked.
+    pub fn mask(&self, message: &str) -> Option<String> {
+        let mut result = message.to_string();
+        if self.links {
+            for url in find_urls(message).into_iter().rev() {
+                if is_suspicious(&message[url.clone()]) {
+                    result.replace_range(url, HIDDEN_LINK);
+                }
+            }
+        }
+        if let Some(words) = &self.words {
+            result = words.replace_all(&result, |c: &regex::Captures| "*".repeat(c[0].chars().count())).into_owned();
+        }
+        (result != message).then_some(result)
+    }
+}
+
+/// Links that are unencrypted, hide their real host or point to a bare ip address.
+pub fn is_suspicious(url: &str) -> bool {
+    let Some(rest) = url.strip_prefix("https://") else {
+        return true;
+    };
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if authority.contains('@') {
+        return true;
+    }
+    let host = authority.rsplit_once(':').map_or(authority, |(host, _)| host);
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.parse::<IpAddr>().is_ok() || host.split('.').any(|label| label.to_ascii_lowercase().starts_with("xn--"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_words_and_suspicious_links() {
+        let filter = ChatFilter::new(&["darn".to_string(), " ".to_string()], true);
+        assert_eq!(filter.mask("Darn, see http://example.com and https://example.com").as_deref(),
+                   Some("****, see [link hidden] and https://example.com"));
+        assert_eq!(filter.mask("darnit is fine"), None);
+        assert_eq!(ChatFilter::new(&[], false).mask("http://example.com"), None);
+    }
+
+    #[test]
+    fn suspicious_links() {
+        assert!(!is_suspicious("https://github.com/ja-ko/ppoker"));
+        assert!(!is_suspicious("https://example.com:8443/path"));
+        assert!(is_suspicious("http://github.com"));
+        assert!(is_suspicious("https://github.com@evil.example/login"));
+        assert!(is_suspicious("https://192.168.0.1/admin"));
+        assert!(is_suspicious("https://[::1]:8080"));
+        assert!(is_suspicious("https://xn--pple-43d.com"));
+    }
+}
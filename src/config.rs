@@ -1,13 +1,228 @@
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use directories::ProjectDirs;
 use figment::Figment;
 use figment::providers::{Env, Format, Serialized, Toml};
-use log::{error, info};
+use log::{error, info, LevelFilter};
 use serde::{Deserialize, Serialize};
 
+use crate::github::GithubSource;
+use crate::models::EventCategory;
+use crate::roles::Role;
+use crate::trust;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    English,
+    German,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+#[value(rename_all = "snake_case")]
+pub enum AutoReveal {
+    /// Never reveal automatically.
+    Off,
+    /// Only the client whose vote completes the round counts down.
+    WhenLastVoter,
+    /// Every client counts down once everyone has voted, whoever voted last.
+    #[default]
+    WhenAllVoted,
+}
+
+/// Message format of the server, implemented in `web::protocol`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    /// The ppoker server.
+    #[default]
+    Ppoker,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum TimeFormat {
+    #[serde(rename = "24h")]
+    #[value(name = "24h")]
+    TwentyFourHour,
+    #[serde(rename = "12h")]
+    #[value(name = "12h")]
+    TwelveHour,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum IdleSpectate {
+    /// Keep me as a player.
+    #[default]
+    Off,
+    /// Ask whether to switch to spectator.
+    Ask,
+    /// Switch to spectator and back on the next key press.
+    Auto,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTimestamps {
+    /// No timestamps.
+    #[default]
+    Off,
+    /// How long ago, e.g. `2m`.
+    Relative,
+    /// Time of day, e.g. `14:05`.
+    Absolute,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum BigTextSize {
+    /// Full size block letters.
+    #[default]
+    Full,
+    /// Half as high.
+    Half,
+    /// Half as high and half as wide.
+    Quadrant,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum VoteColors {
+    /// Color the votes by comparing them with your own vote.
+    #[default]
+    Own,
+    /// Color the votes by comparing them with the average.
+    Average,
+    /// No colors.
+    Off,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum VoteArrows {
+    /// No arrows, only colors.
+    #[default]
+    Off,
+    /// Compare the votes with your own vote.
+    Own,
+    /// Compare the votes with the average.
+    Average,
+}
+
+/// Chat service the summaries are posted to through `webhook_url`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Integration {
+    Slack,
+    Discord,
+}
+
+/// When a summary is posted to the integration.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum IntegrationSummary {
+    /// After every reveal.
+    #[default]
+    Round,
+    /// Once when quitting, listing all rounds.
+    Session,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum LogVerbosity {
+    Off,
+    Error,
+    Warn,
+    Info,
+    #[default]
+    Debug,
+    Trace,
+}
+
+impl LogVerbosity {
+    pub fn filter(self) -> LevelFilter {
+        match self {
+            LogVerbosity::Off => LevelFilter::Off,
+            LogVerbosity::Error => LevelFilter::Error,
+            LogVerbosity::Warn => LevelFilter::Warn,
+            LogVerbosity::Info => LevelFilter::Info,
+            LogVerbosity::Debug => LevelFilter::Debug,
+            LogVerbosity::Trace => LevelFilter::Trace,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationBackend {
+    /// Desktop notifications, the terminal bell if they are not available.
+    #[default]
+    Auto,
+    /// Desktop notifications only.
+    Desktop,
+    /// The terminal bell only.
+    Bell,
+    /// Run `notification_command`.
+    Command,
+}
+
+/// How the log box shows a category of server events.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventDisplay {
+    #[default]
+    Show,
+    /// Replace consecutive events by a single line counting them.
+    Collapse,
+    Hide,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct ServerEvents {
+    pub join_leave: EventDisplay,
+    pub cards: EventDisplay,
+    pub renames: EventDisplay,
+}
+
+impl ServerEvents {
+    pub fn display(&self, category: EventCategory) -> EventDisplay {
+        match category {
+            EventCategory::JoinLeave => self.join_leave,
+            EventCategory::Card => self.cards,
+            EventCategory::Rename => self.renames,
+        }
+    }
+}
+
+/// Shell commands run on room events, with the details in `PPOKER_*` environment variables.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Hooks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reveal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub my_vote_last_missing: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chat_mention: Option<String>,
+}
+
 #[derive(Serialize, Parser)]
 #[command(version, about, long_about = None)]
 pub struct Cli {
@@ -21,6 +236,11 @@ pub struct Cli {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) server: Option<String>,
 
+    /// Team config to load from this URL, overridden by everything set locally.
+    #[arg(long, value_name = "URL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) config_url: Option<String>,
+
     /// Room to join.
     #[arg()]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -30,28 +250,632 @@ pub struct Cli {
     #[arg(short = 'S', long)]
     pub(crate) skip_update_check: bool,
 
+    /// Releases to update to: stable, beta to include pre-releases or a release tag like v0.4.0 to stay on.
+    #[arg(long, value_name = "CHANNEL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) update_channel: Option<String>,
+
+    /// Check for updates before joining the room instead of in the background.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) update_now: bool,
+
+    /// Hours between two update checks, 0 checks on every start.
+    #[arg(long, value_name = "HOURS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) update_check_interval_hours: Option<u64>,
+
+    /// Repository to update from as owner/name, for mirrors of the releases.
+    #[arg(long, value_name = "OWNER/NAME")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) update_repository: Option<String>,
+
+    /// Base URL of a GitHub compatible API serving the releases, e.g. of GitHub Enterprise.
+    #[arg(long, value_name = "URL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) update_api_url: Option<String>,
+
     /// Disable notifications
     #[arg(short = 'N', long)]
     pub(crate) disable_notifications: bool,
+
+    /// Don't show the room and whether your vote is missing in the terminal title.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) disable_terminal_title: bool,
+
+    /// Also rename the tmux window, if running inside tmux.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) tmux_window_name: bool,
+
+    /// How to notify you.
+    #[arg(long, value_enum, value_name = "BACKEND")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) notification_backend: Option<NotificationBackend>,
+
+    /// Command run for notifications with the command backend, gets summary and body as arguments.
+    #[arg(long, value_name = "COMMAND")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) notification_command: Option<String>,
+
+    /// Publish the room state on this IPC socket so other instances can follow it.
+    #[arg(long, value_name = "PATH")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) ipc_socket: Option<String>,
+
+    /// Serve the room as a web page for stream overlays on this port of localhost, the JSON state is at `/state.json`.
+    #[arg(long, value_name = "PORT")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) overlay_port: Option<u16>,
+
+    /// Stop on messages from the server that fail to parse instead of skipping them, for development.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) strict_protocol: bool,
+
+    /// Message format of the server.
+    #[arg(long, value_enum, value_name = "PROTOCOL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) protocol: Option<Protocol>,
+
+    /// Render a read-only mirror of the instance publishing on this IPC socket.
+    #[arg(long, value_name = "IPC_SOCKET")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) follow: Option<String>,
+
+    /// Color theme matching your terminal background.
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) theme: Option<Theme>,
+
+    /// Language of the interface, the messages and the notifications. Detected from LANG otherwise.
+    #[arg(long, value_enum)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) language: Option<Language>,
+
+    /// Clock of the times in the log and the session notes. Detected from the locale otherwise.
+    #[arg(long, value_enum, value_name = "CLOCK")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) time_format: Option<TimeFormat>,
+
+    /// Size of the big numbers showing your vote and the average.
+    #[arg(long, value_enum, value_name = "SIZE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) big_text_size: Option<BigTextSize>,
+
+    /// Only draw with ASCII characters, for terminals that garble unicode. Detected from TERM and the locale otherwise.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) ascii: bool,
+
+    /// Show the voting page as plain labelled text and announce new rounds, votes and results for screen readers.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) screen_reader: bool,
+
+    /// Show when log entries were written.
+    #[arg(long, value_enum, value_name = "MODE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) log_timestamps: Option<LogTimestamps>,
+
+    /// Mark revealed votes above or below a reference with ▲/▼.
+    #[arg(long, value_enum, value_name = "MODE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) vote_arrows: Option<VoteArrows>,
+
+    /// Color revealed votes as higher, lower or the same as a reference, the colors follow the theme.
+    #[arg(long, value_enum, value_name = "MODE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) vote_colors: Option<VoteColors>,
+
+    /// Minutes without voting or chatting after which a player is shown as away. 0 disables it.
+    #[arg(long, value_name = "MINUTES")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) away_after_minutes: Option<u64>,
+
+    /// Don't wait for away players when checking whether your vote is the last one missing.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) ignore_away_players: bool,
+
+    /// Ring the bell and show a notification when a round ends in consensus.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) notify_consensus: bool,
+
+    /// Show a notification for chat messages while the terminal is not focused.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) notify_chat: bool,
+
+    /// Hide chat links that are unencrypted, point to an ip address or hide their real host.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) hide_suspicious_links: bool,
+
+    /// Ring the bell and show a notification once everyone has voted, for whoever reveals the cards.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) notify_all_voted: bool,
+
+    /// Show your own vote as `#` until the cards are revealed, e.g. while sharing your screen.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) hide_own_vote: bool,
+
+    /// Pick the players with votes far from the average more likely to explain first.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) first_speaker_outliers: bool,
+
+    /// What to do when you are idle while your vote is missing.
+    #[arg(long, value_enum, value_name = "MODE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) idle_spectate: Option<IdleSpectate>,
+
+    /// Minutes without any key press during a round after which you are considered idle.
+    #[arg(long, value_name = "MINUTES")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) idle_spectate_minutes: Option<u64>,
+
+    /// Minutes each round may take before you get alerted. 0 disables the timebox.
+    #[arg(long, value_name = "MINUTES")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) round_timebox_minutes: Option<u64>,
+
+    /// Seconds after everyone has voted until the cards are revealed automatically. 0 disables it.
+    #[arg(long, value_name = "SECONDS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) auto_reveal_seconds: Option<u64>,
+
+    /// Which clients start the auto reveal countdown.
+    #[arg(long, value_enum, value_name = "POLICY")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) auto_reveal: Option<AutoReveal>,
+
+    /// Seconds after the reveal until a new round starts automatically. 0 disables it.
+    #[arg(long, value_name = "SECONDS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) auto_restart_delay: Option<u64>,
+
+    /// Directory to write a Markdown summary of the session to when quitting.
+    #[arg(long, value_name = "DIR")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) summary_dir: Option<String>,
+
+    /// Points the team can take on, e.g. the velocity of the sprint. The agreed estimates of the session are totaled
+    /// against it.
+    #[arg(long, value_name = "POINTS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) capacity: Option<u32>,
+
+    /// GitHub repository as owner/name to import the open issues of as stories to estimate.
+    #[arg(long, value_name = "OWNER/NAME")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) github: Option<String>,
+
+    /// Only import the GitHub issues with this label, e.g. needs-estimate.
+    #[arg(long, value_name = "LABEL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) github_label: Option<String>,
+
+    /// Post summaries to Slack or Discord through the webhook_url of the config file.
+    #[arg(long, value_enum, value_name = "SERVICE")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) integration: Option<Integration>,
+
+    /// Post a summary of every round or of the whole session when quitting.
+    #[arg(long, value_enum, value_name = "WHEN")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) integration_summary: Option<IntegrationSummary>,
+
+    /// Append every revealed round as a row to this CSV file, building a journal of all estimations.
+    #[arg(long, value_name = "PATH")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) journal_file: Option<String>,
+
+    /// Keep a JSON file with the state of the room in the data directory, for status bar scripts or overlays.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) state_file: bool,
+
+    /// Write Prometheus metrics of the session to this file, e.g. into the textfile directory of the node exporter.
+    #[arg(long, value_name = "PATH")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) metrics_file: Option<String>,
+
+    /// Most detailed messages written to the log file and shown on the log page.
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) log_level: Option<LogVerbosity>,
+
+    /// Directory for the log files instead of the one in the data directory.
+    #[arg(long, value_name = "DIR")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) log_dir: Option<String>,
+
+    /// Number of log files to keep, older ones are deleted on startup.
+    #[arg(long, value_name = "COUNT")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) max_log_files: Option<usize>,
+
+    /// Cards to vote with instead of the deck of the server: fibonacci, tshirt, powers, a preset from the config file or
+    /// a comma-separated list of cards.
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) deck: Option<String>,
+
+    #[command(subcommand)]
+    #[serde(skip)]
+    pub(crate) command: Option<Command>,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum Command {
+    /// Join a room as spectator and print a single-line status whenever it changes, e.g. for status bars.
+    Watch {
+        /// The value to print.
+        #[arg(short, long, value_enum, default_value_t = Metric::Progress)]
+        metric: Metric,
+
+        /// Room to watch.
+        #[arg(short, long)]
+        room: Option<String>,
+    },
+    /// Print the phase and the missing votes of a room once, e.g. for prompts and tmux status lines.
+    Status {
+        /// Room to check.
+        #[arg(short, long)]
+        room: Option<String>,
+
+        #[arg(short, long, value_enum, default_value_t = StatusFormat::Plain)]
+        format: StatusFormat,
+    },
+    /// Write a zip with the latest log, the config without secrets and system information for bug reports.
+    Diagnose,
+    /// Create a room and join it, the command line to invite others is copied to the clipboard.
+    Create {
+        /// Name of the room, a random one otherwise.
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Deck of the room, like the --deck option: fibonacci, tshirt, powers, a preset or a list of cards.
+        #[arg(short, long)]
+        deck: Option<String>,
+    },
+    /// Host rooms for the local network and join one, others connect to the printed address.
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Port to listen on, on all interfaces.
+        #[arg(short, long, default_value_t = 8080)]
+        port: u16,
+    },
+    /// Estimate with the peers in the local network, found over mDNS, without a server.
+    Lan {
+        /// Name of the room, the one of the peers already in the local network or a random one otherwise.
+        #[arg(short, long)]
+        room: Option<String>,
+    },
+    /// List the active rooms of a server with their participants, if the server offers a listing.
+    Rooms {
+        /// Websocket URL of the server, the configured server otherwise.
+        #[arg(short, long)]
+        server: Option<String>,
+    },
+    /// Check the connection to a server: handshake time, TLS and round trip times. Fails if the server can't be reached.
+    Ping {
+        /// Websocket URL to check, the configured server otherwise.
+        #[arg(short, long)]
+        server: Option<String>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum StatusFormat {
+    /// A short line like `playing, 2 missing, waiting on you`.
+    Plain,
+    /// A JSON object.
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Metric {
+    /// Number of participants that have voted, e.g. `3/5`.
+    Progress,
+    /// Current phase of the round.
+    Phase,
+    /// Average of the revealed votes.
+    Average,
+}
+
+/// Which releases the self update considers, parsed from the `update_channel` option.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UpdateChannel {
+    Stable,
+    /// Stable releases and pre-releases.
+    Beta,
+    /// Exactly this release tag, also if it is older than the current version.
+    Pinned(String),
+}
+
+/// Where the self update gets its releases from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UpdateSource {
+    pub owner: String,
+    pub repo: String,
+    /// GitHub API if not set.
+    pub api_url: Option<String>,
+    pub token: Option<String>,
 }
 
+const DEFAULT_UPDATE_REPOSITORY: &str = "ja-ko/ppoker";
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct Config {
     pub name: String,
+    /// The name was given with `--name`, it wins over the name remembered for a room.
+    #[serde(skip)]
+    pub name_from_cli: bool,
     pub room: String,
     pub server: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config_url: Option<String>,
+    /// Join the room with the peers in the local network instead of through the server, set by `ppoker lan`.
+    #[serde(skip)]
+    pub peer: bool,
     pub skip_update_check: bool,
+    pub update_channel: String,
+    pub update_now: bool,
+    pub update_check_interval_hours: u64,
+    pub update_repository: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_api_url: Option<String>,
+    /// Token for private mirrors, only read from the config file or the environment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_token: Option<String>,
     pub disable_notifications: bool,
+    pub disable_terminal_title: bool,
+    pub tmux_window_name: bool,
+    pub notification_backend: NotificationBackend,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipc_socket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overlay_port: Option<u16>,
+    pub strict_protocol: bool,
+    pub protocol: Protocol,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub follow: Option<String>,
+    pub theme: Theme,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<Language>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<TimeFormat>,
+    pub big_text_size: BigTextSize,
+    pub ascii: bool,
+    pub screen_reader: bool,
+    pub log_timestamps: LogTimestamps,
+    pub vote_arrows: VoteArrows,
+    pub vote_colors: VoteColors,
+    pub away_after_minutes: u64,
+    pub ignore_away_players: bool,
+    pub notify_consensus: bool,
+    pub notify_chat: bool,
+    pub notify_all_voted: bool,
+    pub hide_suspicious_links: bool,
+    pub hide_own_vote: bool,
+    pub first_speaker_outliers: bool,
+    pub idle_spectate: IdleSpectate,
+    pub idle_spectate_minutes: u64,
+    pub round_timebox_minutes: u64,
+    pub auto_reveal_seconds: u64,
+    pub auto_reveal: AutoReveal,
+    pub auto_restart_delay: u64,
+    /// Timebox per room name, overriding `round_timebox_minutes`.
+    pub room_timeboxes: HashMap<String, u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github_label: Option<String>,
+    /// Token to read private repositories and post estimates, only read from the config file or the environment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub github_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integration: Option<Integration>,
+    pub integration_summary: IntegrationSummary,
+    /// Incoming webhook of the integration, only read from the config file or the environment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    pub state_file: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics_file: Option<String>,
+    pub log_level: LogVerbosity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_dir: Option<String>,
+    pub max_log_files: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deck: Option<String>,
+    /// Named decks usable with `deck`, overriding the built-in presets.
+    pub deck_presets: HashMap<String, Vec<String>>,
+    /// Words masked with stars in the chat, only read from the config file.
+    pub chat_filter_words: Vec<String>,
+    /// Roles of the players by name, only read from the config file. Names can also be tagged like `Alice (QA)`.
+    pub roles: HashMap<String, Role>,
+    /// Groups of the players by name, only read from the config file. Names can also start with the group like
+    /// `@backend Alice`.
+    pub groups: HashMap<String, String>,
+    /// How noisy server events are shown in the log box.
+    pub server_events: ServerEvents,
+    pub hooks: Hooks,
+}
+
+/// Decks usable with `deck` without defining them in the config file.
+const DECK_PRESETS: [(&str, &[&str]); 3] = [
+    ("fibonacci", &["0", "1", "2", "3", "5", "8", "13", "21", "?"]),
+    ("tshirt", &["XS", "S", "M", "L", "XL", "XXL", "?"]),
+    ("powers", &["0", "1", "2", "4", "8", "16", "32", "64", "?"]),
+];
+
+impl Config {
+    pub fn log_dir(&self) -> PathBuf {
+        self.log_dir.as_ref().map_or_else(get_logdir, PathBuf::from)
+    }
+
+    pub fn update_check_interval(&self) -> Duration {
+        Duration::from_secs(self.update_check_interval_hours * 60 * 60)
+    }
+
+    pub fn update_source(&self) -> UpdateSource {
+        let repository = match self.update_repository.trim().split_once('/') {
+            Some((owner, repo)) if !owner.is_empty() && !repo.is_empty() && !repo.contains('/') => (owner, repo),
+            _ => {
+                error!("Invalid update repository {}, expected owner/name.", self.update_repository);
+                DEFAULT_UPDATE_REPOSITORY.split_once('/').expect("Invalid default update repository")
+            }
+        };
+        UpdateSource {
+            owner: repository.0.to_owned(),
+            repo: repository.1.to_owned(),
+            api_url: self.update_api_url.as_ref().map(|url| url.trim_end_matches('/').to_owned()),
+            token: self.update_token.clone().filter(|token| !token.is_empty()),
+        }
+    }
+
+    /// Repository to import stories from, `None` if none or an invalid one is configured.
+    pub fn github_source(&self) -> Option<GithubSource> {
+        let repository = self.github.as_deref()?.trim();
+        let Some((owner, repo)) = repository.split_once('/').filter(|(o, r)| !o.is_empty() && !r.is_empty() && !r.contains('/')) else {
+            error!("Invalid GitHub repository {}, expected owner/name.", repository);
+            return None;
+        };
+        Some(GithubSource {
+            owner: owner.to_owned(),
+            repo: repo.to_owned(),
+            label: self.github_label.clone().filter(|label| !label.is_empty()),
+            token: self.github_token.clone().filter(|token| !token.is_empty()),
+        })
+    }
+
+    pub fn update_channel(&self) -> UpdateChannel {
+        match self.update_channel.trim() {
+            channel if channel.eq_ignore_ascii_case("stable") || channel.is_empty() => UpdateChannel::Stable,
+            channel if channel.eq_ignore_ascii_case("beta") => UpdateChannel::Beta,
+            tag if tag.starts_with('v') => UpdateChannel::Pinned(tag.to_owned()),
+            tag => UpdateChannel::Pinned(format!("v{}", tag)),
+        }
+    }
+
+    /// Names of the built-in and configured deck presets, sorted.
+    pub fn deck_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = DECK_PRESETS.iter().map(|(name, _)| name.to_string())
+            .chain(self.deck_presets.keys().cloned())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Resolves the `deck` option to its cards, `None` if the deck of the server should be used.
+    /// Built-in presets can be abbreviated to their first three letters or more, like `fib`.
+    pub fn custom_deck(&self) -> Option<Vec<String>> {
+        let deck = self.deck.as_deref()?.trim();
+        if let Some(cards) = self.deck_presets.get(deck) {
+            return Some(cards.clone());
+        }
+        let abbreviates = |name: &str| deck.len() >= 3 && name.starts_with(&deck.to_ascii_lowercase());
+        if let Some((_, cards)) = DECK_PRESETS.iter().find(|(name, _)| name.eq_ignore_ascii_case(deck) || abbreviates(name)) {
+            return Some(cards.iter().map(|c| c.to_string()).collect());
+        }
+        if !deck.contains(',') {
+            error!("Unknown deck preset {}, using the deck of the server.", deck);
+            return None;
+        }
+        Some(deck.split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect())
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             name: whoami::username(),
+            name_from_cli: false,
             room: petname::petname(3, "").expect("Failed to generate random room name"),
             server: "wss://pp.discordia.network/".to_owned(),
+            config_url: None,
+            peer: false,
             skip_update_check: false,
+            update_channel: "stable".to_owned(),
+            update_now: false,
+            update_check_interval_hours: 0,
+            update_repository: DEFAULT_UPDATE_REPOSITORY.to_owned(),
+            update_api_url: None,
+            update_token: None,
             disable_notifications: false,
+            disable_terminal_title: false,
+            tmux_window_name: false,
+            notification_backend: NotificationBackend::Auto,
+            notification_command: None,
+            ipc_socket: None,
+            strict_protocol: false,
+            protocol: Protocol::Ppoker,
+            overlay_port: None,
+            follow: None,
+            theme: Theme::Dark,
+            language: None,
+            time_format: None,
+            big_text_size: BigTextSize::Full,
+            ascii: false,
+            screen_reader: false,
+            log_timestamps: LogTimestamps::Off,
+            vote_arrows: VoteArrows::Off,
+            vote_colors: VoteColors::Own,
+            away_after_minutes: 5,
+            ignore_away_players: false,
+            notify_consensus: false,
+            notify_chat: false,
+            notify_all_voted: false,
+            hide_suspicious_links: false,
+            hide_own_vote: false,
+            first_speaker_outliers: false,
+            idle_spectate: IdleSpectate::Off,
+            idle_spectate_minutes: 5,
+            round_timebox_minutes: 0,
+            auto_reveal_seconds: 0,
+            auto_reveal: AutoReveal::WhenAllVoted,
+            auto_restart_delay: 0,
+            room_timeboxes: HashMap::new(),
+            summary_dir: None,
+            capacity: None,
+            journal_file: None,
+            github: None,
+            github_label: None,
+            github_token: None,
+            integration: None,
+            integration_summary: IntegrationSummary::Round,
+            webhook_url: None,
+            state_file: false,
+            metrics_file: None,
+            log_level: LogVerbosity::Debug,
+            log_dir: None,
+            max_log_files: 20,
+            deck: None,
+            deck_presets: HashMap::new(),
+            chat_filter_words: vec![],
+            roles: HashMap::new(),
+            groups: HashMap::new(),
+            server_events: ServerEvents::default(),
+            hooks: Hooks::default(),
         }
     }
 }
@@ -77,17 +901,162 @@ pub fn get_logdir() -> PathBuf {
     return dir.to_owned();
 }
 
-pub fn get_config() -> Config {
-    let config_file = get_configdir().join("config.toml");
+pub fn get_datadir() -> PathBuf {
+    create_projdirs().data_dir().to_path_buf()
+}
+
+/// Crash and diagnose reports.
+pub fn get_reportdir() -> PathBuf {
+    create_projdirs().data_dir().join("reports")
+}
+
+/// Files a running instance keeps up to date for other processes.
+pub fn get_statedir() -> PathBuf {
+    create_projdirs().data_dir().join("state")
+}
+
+/// The config fetched from `config_url`, empty unless the user trusts it.
+fn shared_config(config_file: &Path, cli: &Cli) -> String {
+    let config_url = Figment::from(Toml::file(config_file))
+        .merge(Env::prefixed("PPOKER_"))
+        .merge(Serialized::defaults(cli))
+        .extract_inner::<String>("config_url");
+    config_url.ok().and_then(|url| trust::shared_config(&url)).unwrap_or_default()
+}
+
+pub fn get_config(cli: &Cli) -> Config {
+    load_config(&get_configdir().join("config.toml"), cli)
+}
+
+/// Flags that are not given on the command line are left out, so they don't replace the config file.
+fn load_config(config_file: &Path, cli: &Cli) -> Config {
     info!("Trying to load config from {}", config_file.to_string_lossy());
     let figment = Figment::from(Serialized::defaults(Config::default()))
-        .merge(Toml::file(config_file.as_path()))
+        .merge(Toml::string(&shared_config(config_file, cli)))
+        .merge(Toml::file(config_file))
         .merge(Env::prefixed("PPOKER_"))
-        .merge(Serialized::defaults(Cli::parse()));
+        .merge(Serialized::defaults(cli));
 
     let result = figment.extract();
     return result.unwrap_or_else(|e| {
         error!("Failed to load config: {}", e);
         Config::default()
     });
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_toml(toml: &str, args: &[&str]) -> Config {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config.toml");
+        fs::write(&file, toml).unwrap();
+        let cli = Cli::parse_from([&["ppoker"], args].concat());
+        load_config(&file, &cli)
+    }
+
+    #[test]
+    fn flags_left_out_keep_the_config_file() {
+        assert!(load_toml("hide_own_vote = true", &[]).hide_own_vote);
+        assert!(load_toml("", &["--hide-own-vote"]).hide_own_vote);
+        assert!(!load_toml("", &[]).hide_own_vote);
+    }
+
+    #[test]
+    fn state_file_can_be_enabled_in_the_config_file() {
+        assert!(load_toml("state_file = true", &[]).state_file);
+        assert!(load_toml("", &["--state-file"]).state_file);
+    }
+
+    #[test]
+    fn all_flags_can_be_enabled_in_the_config_file() {
+        let config = load_toml("update_now = true\ndisable_terminal_title = true\ntmux_window_name = true\n\
+            strict_protocol = true\nascii = true\nscreen_reader = true\nignore_away_players = true\n\
+            notify_consensus = true\nnotify_chat = true\nhide_suspicious_links = true\nnotify_all_voted = true\n\
+            first_speaker_outliers = true", &[]);
+        assert!(config.update_now && config.disable_terminal_title && config.tmux_window_name);
+        assert!(config.strict_protocol && config.ascii && config.screen_reader && config.ignore_away_players);
+        assert!(config.notify_consensus && config.notify_chat && config.hide_suspicious_links);
+        assert!(config.notify_all_voted && config.first_speaker_outliers);
+    }
+
+    #[test]
+    fn resolves_custom_decks() {
+        let mut config = Config::default();
+        assert_eq!(config.custom_deck(), None);
+
+        config.deck = Some("TShirt".to_string());
+        assert_eq!(config.custom_deck().unwrap()[0], "XS");
+
+        config.deck = Some("Fib".to_string());
+        assert_eq!(config.custom_deck().unwrap()[6], "13");
+        config.deck = Some("fi".to_string());
+        assert_eq!(config.custom_deck(), None);
+
+        config.deck_presets.insert("tshirt".to_string(), vec!["S".to_string(), "L".to_string()]);
+        config.deck = Some("tshirt".to_string());
+        assert_eq!(config.custom_deck(), Some(vec!["S".to_string(), "L".to_string()]));
+
+        config.deck = Some("1, 2,3,".to_string());
+        assert_eq!(config.custom_deck(), Some(vec!["1".to_string(), "2".to_string(), "3".to_string()]));
+
+        config.deck = Some("unknown".to_string());
+        assert_eq!(config.custom_deck(), None);
+    }
+
+    #[test]
+    fn parses_update_sources() {
+        let mut config = Config::default();
+        assert_eq!(config.update_source(), UpdateSource {
+            owner: "ja-ko".to_string(),
+            repo: "ppoker".to_string(),
+            api_url: None,
+            token: None,
+        });
+
+        config.update_repository = "tools/ppoker-mirror".to_string();
+        config.update_api_url = Some("https://git.example.com/api/v3/".to_string());
+        config.update_token = Some("secret".to_string());
+        let source = config.update_source();
+        assert_eq!((source.owner.as_str(), source.repo.as_str()), ("tools", "ppoker-mirror"));
+        assert_eq!(source.api_url.as_deref(), Some("https://git.example.com/api/v3"));
+        assert_eq!(source.token.as_deref(), Some("secret"));
+
+        config.update_repository = "no-slash".to_string();
+        assert_eq!(config.update_source().owner, "ja-ko");
+    }
+
+    #[test]
+    fn parses_github_sources() {
+        let mut config = Config::default();
+        assert_eq!(config.github_source(), None);
+
+        config.github = Some("ja-ko/ppoker".to_string());
+        config.github_label = Some("needs-estimate".to_string());
+        assert_eq!(config.github_source(), Some(GithubSource {
+            owner: "ja-ko".to_string(),
+            repo: "ppoker".to_string(),
+            label: Some("needs-estimate".to_string()),
+            token: None,
+        }));
+
+        config.github = Some("ppoker".to_string());
+        assert_eq!(config.github_source(), None);
+    }
+
+    #[test]
+    fn parses_update_channels() {
+        let mut config = Config::default();
+        assert_eq!(config.update_channel(), UpdateChannel::Stable);
+
+        config.update_channel = "Beta".to_string();
+        assert_eq!(config.update_channel(), UpdateChannel::Beta);
+
+        config.update_channel = "0.3.1".to_string();
+        assert_eq!(config.update_channel(), UpdateChannel::Pinned("v0.3.1".to_string()));
+
+        config.update_channel = "v0.4.0-rc.1".to_string();
+        assert_eq!(config.update_channel(), UpdateChannel::Pinned("v0.4.0-rc.1".to_string()));
+    }
+}
@@ -0,0 +1,320 @@
+//! Finds the peers of `ppoker lan` in the local network over mDNS. Every peer announces itself as an instance of the
+//! `_ppoker._udp.local` service, with the port it takes requests on in the SRV record and its room in the TXT record.
+
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4, UdpSocket};
+use std::time::{Duration, Instant};
+
+use log::{debug, warn};
+use socket2::{Domain, Protocol, Socket, Type};
+
+const MDNS_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MDNS_PORT: u16 = 5353;
+const SERVICE: &str = "_ppoker._udp.local";
+/// Seconds the others may keep an announcement.
+const TTL: u32 = 120;
+/// Asking again now and then finds the peers whose announcement got lost.
+const QUERY_INTERVAL: Duration = Duration::from_secs(10);
+/// How long `find_room` listens for answers.
+const FIND_TIMEOUT: Duration = Duration::from_secs(1);
+
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const TYPE_SRV: u16 = 33;
+const TYPE_ANY: u16 = 255;
+const CLASS_IN: u16 = 1;
+/// Class of the records only one peer answers for, telling the others to replace what they cached.
+const CLASS_IN_FLUSH: u16 = 0x8001;
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAGS_ANSWER: u16 = 0x8400;
+
+/// A peer announcing itself, or leaving when the TTL is zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Announcement {
+    pub peer: String,
+    pub room: String,
+    pub port: u16,
+    pub gone: bool,
+}
+
+/// What a packet of the mDNS group means to ppoker.
+#[derive(Debug, Default, PartialEq)]
+struct Packet {
+    /// Someone asks for the peers.
+    query: bool,
+    announcements: Vec<Announcement>,
+}
+
+fn header(flags: u16, questions: u16, answers: u16) -> Vec<u8> {
+    let mut packet = vec![0, 0];
+    for value in [flags, questions, answers, 0, 0] {
+        packet.extend_from_slice(&value.to_be_bytes());
+    }
+    packet
+}
+
+fn write_name(packet: &mut Vec<u8>, name: &str) {
+    for label in name.split('.') {
+        let label = &label.as_bytes()[..label.len().min(63)];
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label);
+    }
+    packet.push(0);
+}
+
+fn write_record(packet: &mut Vec<u8>, name: &str, record_type: u16, class: u16, ttl: u32, data: &[u8]) {
+    write_name(packet, name);
+    packet.extend_from_slice(&record_type.to_be_bytes());
+    packet.extend_from_slice(&class.to_be_bytes());
+    packet.extend_from_slice(&ttl.to_be_bytes());
+    packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    packet.extend_from_slice(data);
+}
+
+fn query() -> Vec<u8> {
+    let mut packet = header(0, 1, 0);
+    write_name(&mut packet, SERVICE);
+    packet.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    packet
+}
+
+/// The answer announcing a peer, with a TTL of zero as its goodbye.
+fn announcement(peer: &str, room: &str, port: u16, ttl: u32) -> Vec<u8> {
+    let instance = format!("{}.{}", peer, SERVICE);
+    let mut packet = header(FLAGS_ANSWER, 0, 3);
+
+    let mut data = vec![];
+    write_name(&mut data, &instance);
+    write_record(&mut packet, SERVICE, TYPE_PTR, CLASS_IN, ttl, &data);
+
+    // priority and weight are of no use here
+    let mut data = vec![0, 0, 0, 0];
+    data.extend_from_slice(&port.to_be_bytes());
+    write_name(&mut data, &format!("{}.local", peer));
+    write_record(&mut packet, &instance, TYPE_SRV, CLASS_IN_FLUSH, ttl, &data);
+
+    let entry = format!("room={}", room);
+    let entry = &entry.as_bytes()[..entry.len().min(255)];
+    let mut data = vec![entry.len() as u8];
+    data.extend_from_slice(entry);
+    write_record(&mut packet, &instance, TYPE_TXT, CLASS_IN_FLUSH, ttl, &data);
+    packet
+}
+
+fn read_u16(data: &[u8], position: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(data.get(position..position + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], position: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(position..position + 4)?.try_into().ok()?))
+}
+
+/// The name at `position` and the position after it, following the compression pointers of other responders.
+fn read_name(data: &[u8], mut position: usize) -> Option<(String, usize)> {
+    let mut labels = vec![];
+    let mut end = None;
+    for _ in 0..64 {
+        let length = *data.get(position)? as usize;
+        if length & 0xC0 == 0xC0 {
+            end.get_or_insert(position + 2);
+            position = (length & 0x3F) << 8 | *data.get(position + 1)? as usize;
+        } else if length == 0 {
+            return Some((labels.join("."), end.unwrap_or(position + 1)));
+        } else {
+            labels.push(String::from_utf8_lossy(data.get(position + 1..position + 1 + length)?).into_owned());
+            position += 1 + length;
+        }
+    }
+    None
+}
+
+/// The peer of an instance name like `<peer>._ppoker._udp.local`.
+fn instance_peer(name: &str) -> Option<&str> {
+    let split = name.len().checked_sub(SERVICE.len() + 1)?;
+    let suffix = name.get(split..)?;
+    (suffix.starts_with('.') && suffix[1..].eq_ignore_ascii_case(SERVICE)).then(|| &name[..split])
+}
+
+fn parse_packet(data: &[u8]) -> Option<Packet> {
+    let flags = read_u16(data, 2)?;
+    let questions = read_u16(data, 4)?;
+    let records = (6..12).step_by(2).map(|position| read_u16(data, position).map(u32::from)).sum::<Option<u32>>()?;
+    let mut packet = Packet::default();
+    let mut position = 12;
+    for _ in 0..questions {
+        let (name, next) = read_name(data, position)?;
+        let record_type = read_u16(data, next)?;
+        position = next + 4;
+        packet.query |= flags & FLAG_RESPONSE == 0 && matches!(record_type, TYPE_PTR | TYPE_ANY) && name.eq_ignore_ascii_case(SERVICE);
+    }
+    if flags & FLAG_RESPONSE == 0 {
+        return Some(packet);
+    }
+
+    let mut ports = vec![];
+    let mut rooms = vec![];
+    for _ in 0..records {
+        let (name, next) = read_name(data, position)?;
+        let record_type = read_u16(data, next)?;
+        let ttl = read_u32(data, next + 4)?;
+        let length = read_u16(data, next + 8)? as usize;
+        let record = data.get(next + 10..next + 10 + length)?;
+        position = next + 10 + length;
+        let Some(peer) = instance_peer(&name) else {
+            continue;
+        };
+        match record_type {
+            TYPE_SRV => ports.push((peer.to_string(), read_u16(record, 4)?, ttl)),
+            TYPE_TXT => {
+                let mut rest = record;
+                while let Some((&length, tail)) = rest.split_first() {
+                    let entry = tail.get(..length as usize)?;
+                    if let Some(room) = entry.strip_prefix(b"room=") {
+                        rooms.push((peer.to_string(), String::from_utf8_lossy(room).into_owned()));
+                    }
+                    rest = &tail[length as usize..];
+                }
+            }
+            _ => {}
+        }
+    }
+    packet.announcements = ports.into_iter()
+        .filter_map(|(peer, port, ttl)| {
+            let (_, room) = rooms.iter().find(|(p, _)| *p == peer)?;
+            Some(Announcement { room: room.clone(), peer, port, gone: ttl == 0 })
+        })
+        .collect();
+    Some(packet)
+}
+
+/// Socket in the mDNS group, sharing the port with the mDNS responder of the system and other instances.
+fn mdns_socket() -> io::Result<UdpSocket> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+    socket.set_reuse_address(true)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true)?;
+    socket.bind(&SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, MDNS_PORT).into())?;
+    let socket: UdpSocket = socket.into();
+    socket.join_multicast_v4(&MDNS_GROUP, &Ipv4Addr::UNSPECIFIED)?;
+    Ok(socket)
+}
+
+/// Asks the local network for the peers and returns the room of the first one answering, `None` if nobody did.
+pub fn find_room() -> io::Result<Option<String>> {
+    let socket = mdns_socket()?;
+    socket.send_to(&query(), (MDNS_GROUP, MDNS_PORT))?;
+
+    let start = Instant::now();
+    let mut buffer = [0; 9000];
+    while let Some(remaining) = FIND_TIMEOUT.checked_sub(start.elapsed()).filter(|r| !r.is_zero()) {
+        socket.set_read_timeout(Some(remaining))?;
+        let length = match socket.recv_from(&mut buffer) {
+            Ok((length, _)) => length,
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => break,
+            Err(e) => return Err(e),
+        };
+        let room = parse_packet(&buffer[..length])
+            .and_then(|packet| packet.announcements.into_iter().find(|a| !a.gone))
+            .map(|a| a.room);
+        if room.is_some() {
+            return Ok(room);
+        }
+    }
+    Ok(None)
+}
+
+/// Announces a peer of a room and answers the queries for it, as long as it lives.
+#[derive(Debug)]
+pub struct Discovery {
+    socket: UdpSocket,
+    peer: String,
+    room: String,
+    port: u16,
+    last_query: Instant,
+}
+
+impl Discovery {
+    /// Joins the mDNS group, asks for the other peers and announces this one with the port it takes requests on.
+    pub fn start(peer: &str, room: &str, port: u16) -> io::Result<Self> {
+        let socket = mdns_socket()?;
+        socket.set_nonblocking(true)?;
+        let result = Self { socket, peer: peer.to_string(), room: room.to_string(), port, last_query: Instant::now() };
+        result.send(&query())?;
+        result.announce(TTL)?;
+        Ok(result)
+    }
+
+    fn send(&self, packet: &[u8]) -> io::Result<()> {
+        self.socket.send_to(packet, (MDNS_GROUP, MDNS_PORT)).map(|_| ())
+    }
+
+    fn announce(&self, ttl: u32) -> io::Result<()> {
+        self.send(&announcement(&self.peer, &self.room, self.port, ttl))
+    }
+
+    /// The peers of the room that announced themselves or left since the last call, with the address of their
+    /// requests.
+    pub fn poll(&mut self) -> io::Result<Vec<(SocketAddr, Announcement)>> {
+        if self.last_query.elapsed() >= QUERY_INTERVAL {
+            self.last_query = Instant::now();
+            self.send(&query())?;
+        }
+        let mut result = vec![];
+        let mut buffer = [0; 9000];
+        loop {
+            let (length, from) = match self.socket.recv_from(&mut buffer) {
+                Ok(received) => received,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(result),
+                Err(e) => return Err(e),
+            };
+            let Some(packet) = parse_packet(&buffer[..length]) else {
+                debug!("Ignoring a malformed mDNS packet from {}.", from);
+                continue;
+            };
+            if packet.query {
+                self.announce(TTL)?;
+            }
+            result.extend(packet.announcements.into_iter()
+                .filter(|a| a.peer != self.peer && a.room == self.room)
+                .map(|a| (SocketAddr::new(from.ip(), a.port), a)));
+        }
+    }
+}
+
+impl Drop for Discovery {
+    fn drop(&mut self) {
+        if let Err(e) = self.announce(0) {
+            warn!("Failed to say goodbye to the peers: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn announcements_carry_port_and_room() {
+        let packet = parse_packet(&announcement("a1b2", "hallway chat", 40123, TTL)).unwrap();
+        assert_eq!(packet, Packet {
+            query: false,
+            announcements: vec![Announcement { peer: "a1b2".to_string(), room: "hallway chat".to_string(), port: 40123, gone: false }],
+        });
+        assert!(parse_packet(&announcement("a1b2", "hallway chat", 40123, 0)).unwrap().announcements[0].gone);
+        assert!(parse_packet(&query()).unwrap().query);
+        assert_eq!(parse_packet(&[0, 0, 0x84]), None);
+    }
+
+    #[test]
+    fn follows_compressed_names() {
+        let mut packet = header(FLAGS_ANSWER, 0, 0);
+        write_name(&mut packet, SERVICE);
+        // a pointer to the service name written at offset 12
+        packet.extend_from_slice(&[4, b'p', b'e', b'e', b'r', 0xC0, 12]);
+        assert_eq!(read_name(&packet, 12), Some((SERVICE.to_string(), 32)));
+        assert_eq!(read_name(&packet, 32), Some(("peer._ppoker._udp.local".to_string(), 39)));
+        assert_eq!(instance_peer("peer._PPOKER._udp.local"), Some("peer"));
+        assert_eq!(instance_peer(SERVICE), None);
+    }
+}
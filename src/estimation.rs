@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+use std::fmt::Formatter;
+
+use crate::i18n::{tr, tr_args};
+
+/// Prefix of chat messages that drive estimation techniques, so every client in the room shares the same state.
+pub const COMMAND_PREFIX: &str = "!pp ";
+
+/// The argument of a chat command like `!pp wait 5`, `None` for other messages and longer words like `!pp waiting`.
+pub fn command_argument<'a>(message: &'a str, command: &str) -> Option<&'a str> {
+    let rest = message.strip_prefix(COMMAND_PREFIX)?.strip_prefix(command)?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+const DOTS_PER_PLAYER: usize = 3;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Technique {
+    #[default]
+    Poker,
+    DotVoting,
+    BucketSort,
+    MagicEstimation,
+}
+
+impl Technique {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "poker" => Some(Technique::Poker),
+            "dots" => Some(Technique::DotVoting),
+            "buckets" => Some(Technique::BucketSort),
+            "magic" => Some(Technique::MagicEstimation),
+            _ => None,
+        }
+    }
+
+    pub fn command_name(&self) -> &'static str {
+        match self {
+            Technique::Poker => "poker",
+            Technique::DotVoting => "dots",
+            Technique::BucketSort => "buckets",
+            Technique::MagicEstimation => "magic",
+        }
+    }
+
+    /// Describes what a vote looks like for this technique.
+    pub fn input_hint(&self) -> &'static str {
+        match self {
+            Technique::Poker => tr("a card from the deck"),
+            Technique::DotVoting => tr("up to 3 item numbers, e.g. 1 1 3"),
+            Technique::BucketSort | Technique::MagicEstimation => tr("item number and card, e.g. 2 8"),
+        }
+    }
+}
+
+impl std::fmt::Display for Technique {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Technique::Poker => tr("Planning poker"),
+            Technique::DotVoting => tr("Dot voting"),
+            Technique::BucketSort => tr("Bucket sort"),
+            Technique::MagicEstimation => tr("Magic estimation"),
+        })
+    }
+}
+
+/// State of the technique currently used in the room, rebuilt from chat commands.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct EstimationState {
+    pub technique: Technique,
+    pub items: Vec<String>,
+    /// Dots given by each player, as item indices.
+    dots: HashMap<String, Vec<usize>>,
+    /// Bucket of each item, the last placement wins.
+    pub buckets: Vec<Option<String>>,
+}
+
+impl EstimationState {
+    /// Applies a chat message, returning whether it was a technique command.
+    pub fn apply(&mut self, sender: &str, message: &str) -> bool {
+        let Some(command) = message.strip_prefix(COMMAND_PREFIX) else {
+            return false;
+        };
+        let (name, args) = command.trim().split_once(' ').unwrap_or((command.trim(), ""));
+        match name {
+            "technique" => {
+                let (technique, items) = args.trim().split_once(' ').unwrap_or((args.trim(), ""));
+                if let Some(technique) = Technique::parse(technique) {
+                    self.technique = technique;
+                    self.items = items.split('|')
+                        .map(|i| i.trim().to_string())
+                        .filter(|i| !i.is_empty())
+                        .collect();
+                    self.dots.clear();
+                    self.buckets = vec![None; self.items.len()];
+                }
+            }
+            "dots" if self.technique == Technique::DotVoting => {
+                let dots: Vec<usize> = args.split_whitespace()
+                    .filter_map(|i| self.parse_item(i))
+                    .take(DOTS_PER_PLAYER)
+                    .collect();
+                self.dots.insert(sender.to_string(), dots);
+            }
+            "place" if matches!(self.technique, Technique::BucketSort | Technique::MagicEstimation) => {
+                let mut args = args.split_whitespace();
+                if let (Some(item), Some(bucket)) = (args.next().and_then(|i| self.parse_item(i)), args.next()) {
+                    self.buckets[item] = Some(bucket.to_string());
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn parse_item(&self, item: &str) -> Option<usize> {
+        item.parse::<usize>().ok()
+            .filter(|i| *i >= 1 && *i <= self.items.len())
+            .map(|i| i - 1)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.technique != Technique::Poker
+    }
+
+    pub fn dot_count(&self, item: usize) -> usize {
+        self.dots.values().flatten().filter(|i| **i == item).count()
+    }
+
+    pub fn players_with_dots(&self) -> usize {
+        self.dots.len()
+    }
+
+    /// Translates a vote typed by the user into the chat command for the current technique.
+    pub fn vote_command(&self, input: &str, deck: &[String]) -> Result<String, String> {
+        let args: Vec<&str> = input.split_whitespace().collect();
+        match self.technique {
+            Technique::Poker => Err(tr("No estimation technique is active.").to_string()),
+            Technique::DotVoting => {
+                if args.is_empty() || args.len() > DOTS_PER_PLAYER || args.iter().any(|a| self.parse_item(a).is_none()) {
+                    return Err(tr_args("Give up to {} dots as item numbers between 1 and {}.", &[
+                        &DOTS_PER_PLAYER.to_string(), &self.items.len().to_string(),
+                    ]));
+                }
+                Ok(format!("{}dots {}", COMMAND_PREFIX, args.join(" ")))
+            }
+            Technique::BucketSort | Technique::MagicEstimation => {
+                let [item, card] = args.as_slice() else {
+                    return Err(tr("Place an item by its number and a card, e.g. 2 8.").to_string());
+                };
+                if self.parse_item(item).is_none() {
+                    return Err(tr_args("Item numbers are between 1 and {}.", &[&self.items.len().to_string()]));
+                }
+                let Some(card) = deck.iter().find(|c| c.eq_ignore_ascii_case(card)) else {
+                    return Err(tr_args("Card is not in the deck: {}", &[card]));
+                };
+                Ok(format!("{}place {} {}", COMMAND_PREFIX, item, card))
+            }
+        }
+    }
+}
+
+/// Builds the command that switches the room to a technique, from user input like `dots Login | Search`.
+pub fn technique_command(input: &str) -> Result<String, String> {
+    let (name, items) = input.trim().split_once(' ').unwrap_or((input.trim(), ""));
+    let technique = Technique::parse(name)
+        .ok_or_else(|| tr_args("Unknown technique {}, use poker, dots, buckets or magic.", &[name]))?;
+    if technique != Technique::Poker && items.split('|').all(|i| i.trim().is_empty()) {
+        return Err(tr("List the items to estimate separated by |, e.g. dots Login | Search").to_string());
+    }
+    Ok(format!("{}technique {} {}", COMMAND_PREFIX, technique.command_name(), items.trim()).trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deck() -> Vec<String> {
+        vec!["1".to_string(), "2".to_string(), "3".to_string(), "5".to_string(), "8".to_string()]
+    }
+
+    #[test]
+    fn commands_end_at_a_word_boundary() {
+        assert_eq!(command_argument("!pp wait 10", "wait"), Some("10"));
+        assert_eq!(command_argument("!pp wait", "wait"), Some(""));
+        assert_eq!(command_argument("!pp waiting for bob", "wait"), None);
+        assert_eq!(command_argument("!pp reactor", "react"), None);
+        assert_eq!(command_argument("wait 10", "wait"), None);
+    }
+
+    #[test]
+    fn dot_voting() {
+        let mut state = EstimationState::default();
+        assert!(state.apply("alice", &technique_command("dots Login | Search | Export").unwrap()));
+        assert_eq!(state.technique, Technique::DotVoting);
+        assert_eq!(state.items, vec!["Login", "Search", "Export"]);
+
+        state.apply("alice", "!pp dots 1 1 3");
+        state.apply("bob", "!pp dots 2 7");
+        state.apply("alice", "!pp dots 1 3 3");
+        assert_eq!(state.dot_count(0), 1);
+        assert_eq!(state.dot_count(1), 1);
+        assert_eq!(state.dot_count(2), 2);
+        assert_eq!(state.players_with_dots(), 2);
+        assert!(!state.apply("bob", "just chatting"));
+    }
+
+    #[test]
+    fn bucket_sort() {
+        let mut state = EstimationState::default();
+        state.apply("alice", "!pp technique magic Login | Search");
+        let command = state.vote_command("2 8", &deck()).unwrap();
+        state.apply("alice", &command);
+        state.apply("bob", "!pp place 2 5");
+        state.apply("bob", "!pp place 3 5");
+        assert_eq!(state.buckets, vec![None, Some("5".to_string())]);
+
+        assert!(state.vote_command("3 8", &deck()).is_err());
+        assert!(state.vote_command("1 13", &deck()).is_err());
+    }
+
+    #[test]
+    fn back_to_poker() {
+        let mut state = EstimationState::default();
+        state.apply("alice", "!pp technique buckets Login");
+        state.apply("alice", &technique_command("poker").unwrap());
+        assert!(!state.is_active());
+        assert!(technique_command("dots").is_err());
+        assert!(technique_command("chess A | B").is_err());
+    }
+}
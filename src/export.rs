@@ -0,0 +1,265 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use time::{Date, OffsetDateTime};
+
+use crate::app::{AppResult, HistoryEntry};
+use crate::format::format_duration;
+use crate::models::UserType;
+
+fn default_file_name(room: &str, extension: &str) -> AppResult<String> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    Ok(format!("ppoker-{}-{}.{}", room, timestamp, extension))
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Votes of a round like `alice=5; bob=8 (confidence 2)`.
+fn format_votes(entry: &HistoryEntry) -> String {
+    let votes: Vec<String> = entry.votes.iter()
+        .map(|p| match entry.confidence.get(p.key()) {
+            Some(confidence) => format!("{}={} (confidence {})", p.name, p.vote, confidence),
+            None => format!("{}={}", p.name, p.vote),
+        })
+        .collect();
+    votes.join("; ")
+}
+
+pub fn history_csv(history: &[HistoryEntry]) -> String {
+    let mut result = String::from("round,topic,average,duration_seconds,votes\n");
+    for entry in history {
+        result.push_str(&format!(
+            "{},{},{:.1},{},{}\n",
+            entry.round_number,
+            csv_field(entry.topic.as_deref().unwrap_or("")),
+            entry.average,
+            entry.length.as_secs(),
+            csv_field(&format_votes(entry)),
+        ));
+    }
+    result
+}
+
+const JOURNAL_HEADER: &str = "timestamp,room,topic,participants,votes,average\n";
+
+/// Row of the journal for a revealed round, the timestamp in UTC like `2024-06-03T14:05:09Z`.
+fn journal_row(time: OffsetDateTime, room: &str, entry: &HistoryEntry) -> String {
+    let participants: Vec<&str> = entry.votes.iter()
+        .filter(|p| p.user_type != UserType::Spectator)
+        .map(|p| p.name.as_str())
+        .collect();
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z,{},{},{},{},{:.1}\n",
+        time.year(), time.month() as u8, time.day(), time.hour(), time.minute(), time.second(),
+        csv_field(room),
+        csv_field(entry.topic.as_deref().unwrap_or("")),
+        csv_field(&participants.join("; ")),
+        csv_field(&format_votes(entry)),
+        entry.average,
+    )
+}
+
+/// Appends the round to the journal file, starting it with a header if it is new.
+pub fn append_journal(path: &str, room: &str, entry: &HistoryEntry) -> AppResult<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut text = String::new();
+    if file.metadata()?.len() == 0 {
+        text.push_str(JOURNAL_HEADER);
+    }
+    text.push_str(&journal_row(OffsetDateTime::now_utc(), room, entry));
+    file.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+/// Writes the history as csv, to a generated file name in the working directory if no path is given.
+pub fn export_history_csv(history: &[HistoryEntry], room: &str, path: Option<&str>) -> AppResult<PathBuf> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(default_file_name(room, "csv")?),
+    };
+    fs::write(&path, history_csv(history))?;
+    Ok(path)
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+/// Markdown summary of a session, meant to be pasted into sprint planning documents.
+pub fn session_summary(room: &str, date: Date, history: &[HistoryEntry], notes: &[String]) -> String {
+    let mut result = format!("# Planning poker: {}\n\nDate: {}\n\n## Rounds\n\n", room, date);
+    if history.is_empty() {
+        result.push_str("No rounds were revealed.\n");
+    } else {
+        result.push_str("| Round | Topic | Average | Duration |\n|---|---|---|---|\n");
+        for entry in history {
+            result.push_str(&format!(
+                "| {} | {} | {:.1} | {} |\n",
+                entry.round_number,
+                markdown_cell(entry.topic.as_deref().unwrap_or("")),
+                entry.average,
+                format_duration(&entry.length),
+            ));
+        }
+    }
+    if !notes.is_empty() {
+        result.push_str("\n## Notes\n\n");
+        for note in notes {
+            result.push_str(&format!("- {}\n", note.replace(['\n', '\r'], " ")));
+        }
+    }
+    result
+}
+
+fn html_escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// The session summary as a standalone html page, for sharing with people outside of the terminal.
+pub fn session_summary_html(room: &str, date: Date, history: &[HistoryEntry], notes: &[String]) -> String {
+    let room = html_escape(room);
+    let mut result = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Planning poker: {room}</title>\n</head>\n<body>\n\
+        <h1>Planning poker: {room}</h1>\n<p>Date: {date}</p>\n<h2>Rounds</h2>\n"
+    );
+    if history.is_empty() {
+        result.push_str("<p>No rounds were revealed.</p>\n");
+    } else {
+        result.push_str("<table>\n<tr><th>Round</th><th>Topic</th><th>Average</th><th>Duration</th></tr>\n");
+        for entry in history {
+            result.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{:.1}</td><td>{}</td></tr>\n",
+                entry.round_number,
+                html_escape(entry.topic.as_deref().unwrap_or("")),
+                entry.average,
+                format_duration(&entry.length),
+            ));
+        }
+        result.push_str("</table>\n");
+    }
+    if !notes.is_empty() {
+        result.push_str("<h2>Notes</h2>\n<ul>\n");
+        for note in notes {
+            result.push_str(&format!("<li>{}</li>\n", html_escape(note)));
+        }
+        result.push_str("</ul>\n");
+    }
+    result.push_str("</body>\n</html>\n");
+    result
+}
+
+fn summary_path(room: &str, path: Option<&str>, extension: &str) -> AppResult<PathBuf> {
+    Ok(match path.map(Path::new) {
+        Some(path) if !path.is_dir() => path.to_path_buf(),
+        Some(dir) => dir.join(default_file_name(room, extension)?),
+        None => PathBuf::from(default_file_name(room, extension)?),
+    })
+}
+
+/// Writes the session summary into the given directory, or to the given file if the path is not a directory.
+pub fn export_session_summary(history: &[HistoryEntry], room: &str, notes: &[String], path: Option<&str>) -> AppResult<PathBuf> {
+    let path = summary_path(room, path, "md")?;
+    let date = time::OffsetDateTime::now_utc().date();
+    fs::write(&path, session_summary(room, date, history, notes))?;
+    Ok(path)
+}
+
+/// Like `export_session_summary`, as html.
+pub fn export_session_html(history: &[HistoryEntry], room: &str, notes: &[String], path: Option<&str>) -> AppResult<PathBuf> {
+    let path = summary_path(room, path, "html")?;
+    let date = time::OffsetDateTime::now_utc().date();
+    fs::write(&path, session_summary_html(room, date, history, notes))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use crate::models::{Player, Vote, VoteData};
+
+    use super::*;
+
+    #[test]
+    fn csv_escapes_votes() {
+        let entry = HistoryEntry {
+            round_number: 2,
+            average: 6.5,
+            length: Duration::from_secs(95),
+            votes: vec![
+                Player::new("Ralph, \"the\" Muller", Vote::Revealed(VoteData::Number(5))),
+                Player { is_you: true, ..Player::new("Johnnie", Vote::Revealed(VoteData::Number(8))) },
+            ],
+            own_vote: Some(VoteData::Number(8)),
+            topic: Some("Login, part 2".to_string()),
+            confidence: HashMap::from([("Johnnie".to_string(), 2)]),
+            ..HistoryEntry::default()
+        };
+
+        assert_eq!(history_csv(&[entry]), "round,topic,average,duration_seconds,votes\n\
+            2,\"Login, part 2\",6.5,95,\"Ralph, \"\"the\"\" Muller=5; Johnnie=8 (confidence 2)\"\n");
+    }
+
+    #[test]
+    fn summary_lists_rounds_and_notes() {
+        let entry = HistoryEntry {
+            round_number: 1,
+            average: 5.0,
+            length: Duration::from_secs(42),
+            topic: Some("Login | Logout".to_string()),
+            special_votes: 1,
+            ..HistoryEntry::default()
+        };
+        let date = Date::from_calendar_date(2024, time::Month::June, 3).unwrap();
+        let summary = session_summary("planning", date, &[entry], &["alice: split it".to_string()]);
+
+        assert_eq!(summary, "# Planning poker: planning\n\nDate: 2024-06-03\n\n## Rounds\n\n\
+            | Round | Topic | Average | Duration |\n|---|---|---|---|\n\
+            | 1 | Login \\| Logout | 5.0 | 42 seconds |\n\n## Notes\n\n- alice: split it\n");
+    }
+
+    #[test]
+    fn html_summary_escapes_topics() {
+        let entry = HistoryEntry {
+            round_number: 1,
+            average: 5.0,
+            length: Duration::from_secs(42),
+            topic: Some("<b>Login</b> & Logout".to_string()),
+            consensus: true,
+            ..HistoryEntry::default()
+        };
+        let date = Date::from_calendar_date(2024, time::Month::June, 3).unwrap();
+        let summary = session_summary_html("planning", date, &[entry], &[]);
+
+        assert!(summary.contains("<tr><td>1</td><td>&lt;b&gt;Login&lt;/b&gt; &amp; Logout</td><td>5.0</td><td>42 seconds</td></tr>"));
+        assert!(!summary.contains("<h2>Notes</h2>"));
+    }
+
+    #[test]
+    fn journal_rows_list_participants_and_votes() {
+        let entry = HistoryEntry {
+            round_number: 3,
+            average: 4.0,
+            length: Duration::from_secs(30),
+            votes: vec![
+                Player { is_you: true, ..Player::new("alice", Vote::Revealed(VoteData::Number(3))) },
+                Player::new("bob", Vote::Revealed(VoteData::Number(5))),
+                Player { user_type: UserType::Spectator, ..Player::new("carol", Vote::Missing) },
+            ],
+            own_vote: Some(VoteData::Number(3)),
+            ..HistoryEntry::default()
+        };
+        let time = OffsetDateTime::from_unix_timestamp(1717423509).unwrap();
+
+        assert_eq!(journal_row(time, "planning", &entry), "2024-06-03T14:05:09Z,planning,,alice; bob,alice=3; bob=5; carol=Missing,4.0\n");
+    }
+}
@@ -0,0 +1,172 @@
+//! Durations and clock times as shown to the user, in the language of the UI and the configured clock.
+
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use time::{OffsetDateTime, UtcOffset};
+
+use crate::config::{Language, TimeFormat};
+use crate::i18n;
+
+static LOCAL_OFFSET: OnceLock<UtcOffset> = OnceLock::new();
+static TWELVE_HOUR: AtomicBool = AtomicBool::new(false);
+
+/// Determines the offset of the local time zone. Must be called while the process is still single threaded,
+/// later on the offset can't be determined safely on unix and times are shown in UTC.
+pub fn init_local_offset() {
+    LOCAL_OFFSET.get_or_init(|| UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC));
+}
+
+/// Switches the clock of the time of day. Called once on startup.
+pub fn set_time_format(format: TimeFormat) {
+    TWELVE_HOUR.store(format == TimeFormat::TwelveHour, Ordering::Relaxed);
+}
+
+/// Guesses the clock from the locale, 12 hours in the countries mostly using it.
+pub fn detect_time_format() -> TimeFormat {
+    let locale = ["LC_ALL", "LC_TIME", "LANG"].iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty());
+    time_format_of_locale(locale.as_deref())
+}
+
+fn time_format_of_locale(locale: Option<&str>) -> TimeFormat {
+    let locale = locale.unwrap_or_default().to_lowercase();
+    if ["en_us", "en_ca", "en_au", "en_nz", "en_ph"].iter().any(|l| locale.starts_with(l)) {
+        TimeFormat::TwelveHour
+    } else {
+        TimeFormat::TwentyFourHour
+    }
+}
+
+/// Formats the time like `13:07` or `1:07 PM` in the local time zone.
+pub fn format_time_of_day(time: SystemTime) -> String {
+    let time = OffsetDateTime::from(time).to_offset(LOCAL_OFFSET.get().copied().unwrap_or(UtcOffset::UTC));
+    let format = if TWELVE_HOUR.load(Ordering::Relaxed) { TimeFormat::TwelveHour } else { TimeFormat::TwentyFourHour };
+    clock_time(time.hour(), time.minute(), format)
+}
+
+fn clock_time(hour: u8, minute: u8, format: TimeFormat) -> String {
+    match format {
+        TimeFormat::TwentyFourHour => format!("{:02}:{:02}", hour, minute),
+        TimeFormat::TwelveHour => {
+            let suffix = if hour < 12 { "AM" } else { "PM" };
+            let hour = match hour % 12 {
+                0 => 12,
+                hour => hour,
+            };
+            format!("{}:{:02} {}", hour, minute, suffix)
+        }
+    }
+}
+
+/// Length of a round like `42 seconds`, `1 minute 40 seconds` or `2 hours 5 minutes`, leaving out a zero remainder.
+pub fn format_duration(duration: &Duration) -> String {
+    duration_text(duration.as_secs(), i18n::language())
+}
+
+fn duration_text(secs: u64, language: Language) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    let (large, small) = if hours > 0 {
+        ((hours, Unit::Hour), (minutes, Unit::Minute))
+    } else if minutes > 0 {
+        ((minutes, Unit::Minute), (seconds, Unit::Second))
+    } else {
+        return unit_text(seconds, Unit::Second, language);
+    };
+    if small.0 == 0 {
+        unit_text(large.0, large.1, language)
+    } else {
+        format!("{} {}", unit_text(large.0, large.1, language), unit_text(small.0, small.1, language))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Unit {
+    Hour,
+    Minute,
+    Second,
+}
+
+fn unit_text(count: u64, unit: Unit, language: Language) -> String {
+    let (one, many) = match (language, unit) {
+        (Language::English, Unit::Hour) => ("hour", "hours"),
+        (Language::English, Unit::Minute) => ("minute", "minutes"),
+        (Language::English, Unit::Second) => ("second", "seconds"),
+        (Language::German, Unit::Hour) => ("Stunde", "Stunden"),
+        (Language::German, Unit::Minute) => ("Minute", "Minuten"),
+        (Language::German, Unit::Second) => ("Sekunde", "Sekunden"),
+    };
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+/// Countdown like `04:59`, with hours once it gets that long like `1:02:03`.
+pub fn format_clock(duration: &Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// Short age like `now`, `12m` or `3h`.
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        "now".to_string()
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h", secs / 3600)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_leave_out_zero_remainders() {
+        let english = |secs| duration_text(secs, Language::English);
+        assert_eq!(english(0), "0 seconds");
+        assert_eq!(english(1), "1 second");
+        assert_eq!(english(59), "59 seconds");
+        assert_eq!(english(60), "1 minute");
+        assert_eq!(english(100), "1 minute 40 seconds");
+        assert_eq!(english(130), "2 minutes 10 seconds");
+        assert_eq!(english(3599), "59 minutes 59 seconds");
+        assert_eq!(english(3600), "1 hour");
+        assert_eq!(english(3900), "1 hour 5 minutes");
+        assert_eq!(english(7260), "2 hours 1 minute");
+        assert_eq!(duration_text(61, Language::German), "1 Minute 1 Sekunde");
+        assert_eq!(duration_text(7200, Language::German), "2 Stunden");
+    }
+
+    #[test]
+    fn clocks_show_hours_when_needed() {
+        assert_eq!(format_clock(&Duration::from_secs(299)), "04:59");
+        assert_eq!(format_clock(&Duration::from_secs(3723)), "1:02:03");
+    }
+
+    #[test]
+    fn times_of_day_in_both_clocks() {
+        assert_eq!(clock_time(13, 7, TimeFormat::TwentyFourHour), "13:07");
+        assert_eq!(clock_time(0, 5, TimeFormat::TwelveHour), "12:05 AM");
+        assert_eq!(clock_time(12, 0, TimeFormat::TwelveHour), "12:00 PM");
+        assert_eq!(clock_time(23, 59, TimeFormat::TwelveHour), "11:59 PM");
+        assert_eq!(time_format_of_locale(Some("en_US.UTF-8")), TimeFormat::TwelveHour);
+        assert_eq!(time_format_of_locale(Some("en_GB.UTF-8")), TimeFormat::TwentyFourHour);
+        assert_eq!(time_format_of_locale(None), TimeFormat::TwentyFourHour);
+    }
+
+    #[test]
+    fn time_of_day_without_local_offset_is_utc() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(13 * 3600 + 7 * 60 + 59);
+        if LOCAL_OFFSET.get().is_none() && !TWELVE_HOUR.load(Ordering::Relaxed) {
+            assert_eq!(format_time_of_day(time), "13:07");
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Imports open GitHub issues as stories to estimate and posts the estimates back as comments through the REST API.
+//! Requests run in background threads, their results are collected by the app on every update.
+
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use log::debug;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header;
+use serde::Deserialize;
+
+use crate::app::AppResult;
+
+const API_URL: &str = "https://api.github.com";
+
+/// Repository the stories are imported from, see `Config::github_source`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GithubSource {
+    pub owner: String,
+    pub repo: String,
+    pub label: Option<String>,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    /// Set for pull requests, which the issues endpoint returns as well.
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug)]
+pub enum GithubResult {
+    Imported(Vec<Issue>),
+    Posted(u64),
+    Failed(String),
+}
+
+pub struct Github {
+    source: GithubSource,
+    sender: Sender<GithubResult>,
+    receiver: Receiver<GithubResult>,
+}
+
+impl Github {
+    pub fn new(source: GithubSource) -> Self {
+        let (sender, receiver) = channel();
+        Self { source, sender, receiver }
+    }
+
+    pub fn repository(&self) -> String {
+        format!("{}/{}", self.source.owner, self.source.repo)
+    }
+
+    /// Fetches the open issues, reported as `GithubResult::Imported`.
+    pub fn import_issues(&self) {
+        let source = self.source.clone();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = match fetch_issues(&source) {
+                Ok(issues) => GithubResult::Imported(issues),
+                Err(e) => GithubResult::Failed(format!("Failed to import the issues of {}/{}: {}", source.owner, source.repo, e)),
+            };
+            let _ = sender.send(result);
+        });
+    }
+
+    /// Comments on the issue, reported as `GithubResult::Posted`.
+    pub fn post_comment(&self, number: u64, body: String) {
+        let source = self.source.clone();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let result = match post_comment(&source, number, &body) {
+                Ok(()) => GithubResult::Posted(number),
+                Err(e) => GithubResult::Failed(format!("Failed to comment on #{}: {}", number, e)),
+            };
+            let _ = sender.send(result);
+        });
+    }
+
+    /// Results of the requests finished since the last call.
+    pub fn results(&self) -> Vec<GithubResult> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+fn request(builder: RequestBuilder, source: &GithubSource) -> RequestBuilder {
+    let builder = builder
+        .header(header::USER_AGENT, concat!("ppoker/", env!("CARGO_PKG_VERSION")))
+        .header(header::ACCEPT, "application/vnd.github+json");
+    match &source.token {
+        Some(token) => builder.bearer_auth(token),
+        None => builder,
+    }
+}
+
+fn fetch_issues(source: &GithubSource) -> AppResult<Vec<Issue>> {
+    let url = format!("{}/repos/{}/{}/issues", API_URL, source.owner, source.repo);
+    let mut query = vec![("state", "open"), ("per_page", "100")];
+    if let Some(label) = &source.label {
+        query.push(("labels", label.as_str()));
+    }
+    debug!("Fetching issues from {}", url);
+    let text = request(Client::new().get(&url), source).query(&query).send()?.error_for_status()?.text()?;
+    Ok(parse_issues(&text)?)
+}
+
+fn parse_issues(text: &str) -> serde_json::Result<Vec<Issue>> {
+    let issues: Vec<Issue> = serde_json::from_str(text)?;
+    Ok(issues.into_iter().filter(|issue| issue.pull_request.is_none()).collect())
+}
+
+fn post_comment(source: &GithubSource, number: u64, body: &str) -> AppResult<()> {
+    let url = format!("{}/repos/{}/{}/issues/{}/comments", API_URL, source.owner, source.repo, number);
+    let body = serde_json::json!({ "body": body }).to_string();
+    request(Client::new().post(&url), source)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()?
+        .error_for_status()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_pull_requests() {
+        let issues = parse_issues(r#"[
+            {"number": 12, "title": "Login", "html_url": "https://github.com/o/r/issues/12", "labels": []},
+            {"number": 13, "title": "Fix login", "html_url": "https://github.com/o/r/pull/13", "pull_request": {}}
+        ]"#).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].number, 12);
+        assert_eq!(issues[0].title, "Login");
+    }
+}
@@ -0,0 +1,72 @@
+//! Teams of the players for refinements with several teams, from the `groups` of the config file or a `@team` prefix
+//! in the name like `@backend Alice`.
+
+use std::collections::HashMap;
+
+use crate::models::{Player, UserType, Vote, VoteData};
+
+/// The configured group of the name, otherwise the one of its `@group` prefix.
+pub fn player_group(name: &str, groups: &HashMap<String, String>) -> Option<String> {
+    if let Some(group) = groups.get(name) {
+        return Some(group.clone());
+    }
+    let (prefix, rest) = name.trim_start().split_once(char::is_whitespace)?;
+    let group = prefix.strip_prefix('@')?;
+    (!group.is_empty() && !rest.trim().is_empty()).then(|| group.to_string())
+}
+
+/// Votes of the players of a group.
+#[derive(Debug, PartialEq)]
+pub struct GroupStats {
+    pub voted: usize,
+    pub total: usize,
+    /// Of the numeric revealed votes, NaN without any.
+    pub average: f32,
+}
+
+pub fn group_stats<'a>(players: impl IntoIterator<Item=&'a Player>) -> GroupStats {
+    let mut stats = GroupStats { voted: 0, total: 0, average: f32::NAN };
+    let mut numbers = vec![];
+    for player in players.into_iter().filter(|p| p.user_type != UserType::Spectator) {
+        stats.total += 1;
+        if player.vote != Vote::Missing {
+            stats.voted += 1;
+        }
+        if let Vote::Revealed(VoteData::Number(n)) = player.vote {
+            numbers.push(n as f32);
+        }
+    }
+    if !numbers.is_empty() {
+        stats.average = numbers.iter().sum::<f32>() / numbers.len() as f32;
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_come_from_the_config_or_the_name() {
+        let groups = HashMap::from([("Carol".to_string(), "frontend".to_string())]);
+        assert_eq!(player_group("@backend Alice", &groups).as_deref(), Some("backend"));
+        assert_eq!(player_group("Carol", &groups).as_deref(), Some("frontend"));
+        assert_eq!(player_group("@backend", &groups), None);
+        assert_eq!(player_group("@ Bob", &groups), None);
+        assert_eq!(player_group("Dan @backend", &groups), None);
+    }
+
+    #[test]
+    fn counts_votes_and_averages_per_group() {
+        let players = [
+            Player::new("a", Vote::Revealed(VoteData::Number(3))),
+            Player::new("b", Vote::Revealed(VoteData::Special("?".to_string()))),
+            Player::new("c", Vote::Missing),
+            Player { user_type: UserType::Spectator, ..Player::new("d", Vote::Missing) },
+            Player::new("e", Vote::Revealed(VoteData::Number(8))),
+        ];
+        let stats = group_stats(&players);
+        assert_eq!((stats.voted, stats.total, stats.average), (3, 4, 5.5));
+        assert!(group_stats(&players[1..4]).average.is_nan());
+    }
+}
@@ -0,0 +1,111 @@
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+
+use log::info;
+
+use crate::app::AppResult;
+use crate::config::{Config, Metric};
+use crate::models::{GamePhase, Room};
+use crate::web::client::{PokerClient, WebPokerClient};
+use crate::web::dto::UserType;
+
+/// Client that follows a room as spectator without any terminal user interface.
+pub struct HeadlessClient {
+    client: WebPokerClient,
+    pub room: Room,
+}
+
+impl HeadlessClient {
+    pub fn connect(config: &Config) -> AppResult<Self> {
+        let mut config = config.clone();
+        config.name = format!("{} (watching)", config.name);
+        config.ipc_socket = None;
+
+        let (client, room, _log) = WebPokerClient::connect(&config, UserType::Spectator)?;
+        Ok(Self { client, room })
+    }
+
+    /// Applies all pending room updates and returns whether there were any.
+    pub fn poll(&mut self) -> AppResult<bool> {
+        let (mut rooms, _log) = self.client.get_updates()?;
+        if let Some(room) = rooms.pop() {
+            self.room = room;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+pub fn format_metric(metric: Metric, room: &Room) -> String {
+    match metric {
+        Metric::Progress => {
+            let (voted, total) = room.vote_progress();
+            format!("{}/{}", voted, total)
+        }
+        Metric::Phase => room.phase.to_string(),
+        Metric::Average => {
+            let average = room.average_votes();
+            if room.phase == GamePhase::Revealed && !average.is_nan() {
+                format!("{:.1}", average)
+            } else {
+                "-".to_string()
+            }
+        }
+    }
+}
+
+pub fn watch(config: &Config, metric: Metric) -> AppResult<()> {
+    let mut client = HeadlessClient::connect(config)?;
+    info!("Watching metric {:?} of room {}.", metric, config.room);
+
+    let mut stdout = std::io::stdout();
+    let mut last_line = format_metric(metric, &client.room);
+    writeln!(stdout, "{}", last_line)?;
+    stdout.flush()?;
+
+    loop {
+        thread::sleep(Duration::from_millis(250));
+        if client.poll()? {
+            let line = format_metric(metric, &client.room);
+            if line != last_line {
+                writeln!(stdout, "{}", line)?;
+                stdout.flush()?;
+                last_line = line;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{Player, UserType, Vote, VoteData};
+
+    use super::*;
+
+    fn room_fixture(phase: GamePhase) -> Room {
+        Room {
+            name: "room".to_string(),
+            deck: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+            phase,
+            players: vec![
+                Player::new("a", Vote::Revealed(VoteData::Number(1))),
+                Player::new("b", Vote::Revealed(VoteData::Number(2))),
+                Player::new("c", Vote::Missing),
+                Player { user_type: UserType::Spectator, ..Player::new("d", Vote::Missing) },
+            ],
+        }
+    }
+
+    #[test]
+    fn progress_ignores_spectators() {
+        assert_eq!(format_metric(Metric::Progress, &room_fixture(GamePhase::Playing)), "2/3");
+    }
+
+    #[test]
+    fn average_only_after_reveal() {
+        assert_eq!(format_metric(Metric::Average, &room_fixture(GamePhase::Playing)), "-");
+        assert_eq!(format_metric(Metric::Average, &room_fixture(GamePhase::Revealed)), "1.5");
+    }
+}
@@ -0,0 +1,52 @@
+//! `ppoker ping`, checks whether a server can be reached and how fast it answers.
+
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::app::AppResult;
+use crate::config::Config;
+use crate::web::client::ClientError;
+use crate::web::dto::UserType;
+use crate::web::ws::{IncomingMessage, PokerSocket};
+
+/// Longest wait for the initial room update and for each pong.
+const TIMEOUT: Duration = Duration::from_secs(5);
+const PINGS: usize = 5;
+
+/// Connects as spectator to a room of its own, so nobody sees the check, and prints the timings.
+pub fn ping(config: &Config) -> AppResult<()> {
+    let mut config = config.clone();
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    config.room = format!("ppoker-ping-{}", timestamp);
+    config.name = format!("{} (ping)", config.name);
+    config.ipc_socket = None;
+    println!("Server: {}", config.server);
+
+    let start = Instant::now();
+    let mut socket = PokerSocket::connect(&config, UserType::Spectator)?;
+    println!("Handshake: {}ms", start.elapsed().as_millis());
+    println!("TLS: {}", if socket.is_tls() { "yes, the certificate was accepted" } else { "no" });
+
+    let start = Instant::now();
+    loop {
+        match socket.read()? {
+            Some(IncomingMessage::RoomUpdate(_)) => break,
+            Some(IncomingMessage::Close) => return Err(Box::new(ClientError::ServerClosedConnection)),
+            Some(IncomingMessage::Error(error)) => return Err(format!("The server reported an error: {}", error).into()),
+            _ if start.elapsed() > TIMEOUT => return Err(Box::new(ClientError::ServerUpdateMissing)),
+            _ => thread::sleep(Duration::from_millis(1)),
+        }
+    }
+    println!("Initial room update: {}ms", start.elapsed().as_millis());
+
+    for _ in 0..PINGS {
+        socket.round_trip(TIMEOUT)?;
+    }
+    if let Some(latency) = socket.latency() {
+        println!(
+            "Round trip: {}/{}/{}ms min/avg/max over {} pings",
+            latency.min.as_millis(), latency.avg.as_millis(), latency.max.as_millis(), PINGS,
+        );
+    }
+    Ok(())
+}
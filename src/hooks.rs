@@ -0,0 +1,88 @@
+//! Runs the commands of the `[hooks]` config section on room events. Details of the event are passed in environment
+//! variables like `PPOKER_ROOM`, so the commands don't need to parse any arguments.
+
+use std::process::{Command, Stdio};
+use std::thread;
+
+use log::{debug, error};
+
+use crate::config::Hooks;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HookEvent {
+    Reveal,
+    RoundStart,
+    MyVoteLastMissing,
+    ChatMention,
+}
+
+impl HookEvent {
+    /// Name of the event in the config file and in `PPOKER_EVENT`.
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::Reveal => "reveal",
+            HookEvent::RoundStart => "round_start",
+            HookEvent::MyVoteLastMissing => "my_vote_last_missing",
+            HookEvent::ChatMention => "chat_mention",
+        }
+    }
+
+    fn command(self, hooks: &Hooks) -> Option<&str> {
+        match self {
+            HookEvent::Reveal => hooks.reveal.as_deref(),
+            HookEvent::RoundStart => hooks.round_start.as_deref(),
+            HookEvent::MyVoteLastMissing => hooks.my_vote_last_missing.as_deref(),
+            HookEvent::ChatMention => hooks.chat_mention.as_deref(),
+        }
+        .filter(|command| !command.trim().is_empty())
+    }
+}
+
+/// Runs the command configured for the event in the background, `env` without the `PPOKER_` prefix.
+pub fn run(hooks: &Hooks, event: HookEvent, env: &[(&str, String)]) {
+    let Some(command) = event.command(hooks) else {
+        return;
+    };
+    debug!("Running {} hook: {}", event.name(), command);
+
+    #[cfg(unix)]
+    let mut process = {
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(command);
+        process
+    };
+    #[cfg(not(unix))]
+    let mut process = {
+        let mut process = Command::new("cmd");
+        process.arg("/C").arg(command);
+        process
+    };
+
+    let child = process
+        .env("PPOKER_EVENT", event.name())
+        .envs(env.iter().map(|(name, value)| (format!("PPOKER_{}", name), value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match child {
+        // reap the process in the background, so slow commands don't block the ui
+        Ok(mut child) => {
+            thread::spawn(move || child.wait());
+        }
+        Err(e) => error!("Failed to run {} hook {}: {}", event.name(), command, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_commands_are_not_run() {
+        let hooks = Hooks { reveal: Some("notify-send revealed".to_string()), round_start: Some(" ".to_string()), ..Hooks::default() };
+        assert_eq!(HookEvent::Reveal.command(&hooks), Some("notify-send revealed"));
+        assert_eq!(HookEvent::RoundStart.command(&hooks), None);
+        assert_eq!(HookEvent::ChatMention.command(&hooks), None);
+    }
+}
@@ -0,0 +1,450 @@
+//! Translations of the texts shown to the user, keyed by their English text. Texts without a translation are shown
+//! in English.
+
+use std::env;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::config::Language;
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(Language::English as u8);
+
+/// Switches the language of the UI. Called once on startup.
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        l if l == Language::German as u8 => Language::German,
+        _ => Language::English,
+    }
+}
+
+/// Guesses the language from the locale variables, English unless the locale is German.
+pub fn detect_language() -> Language {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty());
+    language_of_locale(locale.as_deref())
+}
+
+fn language_of_locale(locale: Option<&str>) -> Language {
+    match locale {
+        Some(locale) if locale.to_lowercase().starts_with("de") => Language::German,
+        _ => Language::English,
+    }
+}
+
+/// The text in the current language.
+pub fn tr(text: &'static str) -> &'static str {
+    translate(language(), text)
+}
+
+/// Like `tr` for the entries of the footers, which underline their first letter as the key.
+pub fn tr_footer(text: &'static str) -> &'static str {
+    match language() {
+        Language::English => text,
+        Language::German => german_footer(text).unwrap_or_else(|| translate(Language::German, text)),
+    }
+}
+
+/// Like `tr`, replacing each `{}` of the translation with the next argument.
+pub fn tr_args(text: &'static str, args: &[&str]) -> String {
+    let mut result = String::new();
+    let mut parts = tr(text).split("{}");
+    result.push_str(parts.next().unwrap_or_default());
+    for (part, arg) in parts.zip(args.iter().chain(std::iter::repeat(&""))) {
+        result.push_str(arg);
+        result.push_str(part);
+    }
+    result
+}
+
+fn translate(language: Language, text: &'static str) -> &'static str {
+    match language {
+        Language::English => text,
+        Language::German => german(text).unwrap_or(text),
+    }
+}
+
+/// Footer entries underline their first letter as the key, the translations start with the key where the word doesn't.
+fn german_footer(text: &str) -> Option<&'static str> {
+    Some(match text {
+        "Vote" => "v Abstimmen",
+        "Fast vote" => "f Schnell abstimmen",
+        "Reveal" => "r Aufdecken",
+        "History" => "h Verlauf",
+        "Quit" => "q Beenden",
+        "Restart" => "r Neue Runde",
+        "Suggest" => "s Vorschlagen",
+        "Pick speaker" => "p Sprecher wählen",
+        "Enter details" => "Enter Details",
+        "Voting" => "v Abstimmung",
+        "Enter join" => "Enter beitreten",
+        "Refresh" => "r Aktualisieren",
+        "Create" => "c Erstellen",
+        _ => return None,
+    })
+}
+
+fn german(text: &str) -> Option<&'static str> {
+    Some(match text {
+        // footer entries starting with their key in German too
+        "Technique" => "Technik",
+        "Name change" => "Name ändern",
+        "Web page" => "Webseite",
+        // confirmations
+        "Confirmation" => "Bestätigung",
+        "Yes" => "Ja",
+        "No" => "Nein",
+        "Not everyone has voted yet. Confirm you want to reveal the cards?" => "Noch haben nicht alle abgestimmt. Trotzdem aufdecken?",
+        "Confirm you want to start a new round?" => "Neue Runde starten?",
+        "You seem to be away. Switch to spectator?" => "Du scheinst weg zu sein. Als Zuschauer fortfahren?",
+        // notifications
+        "The timebox for this round has expired." => "Die Timebox dieser Runde ist abgelaufen.",
+        "Your vote is the last one missing." => "Nur deine Stimme fehlt noch.",
+        "Consensus!" => "Konsens!",
+        "All votes are in, reveal when ready." => "Alle haben abgestimmt, aufdecken wenn bereit.",
+        // update dialog
+        "Update to v{}" => "Update auf v{}",
+        "Current version: " => "Aktuelle Version: ",
+        " | New version: " => " | Neue Version: ",
+        " | Channel: " => " | Kanal: ",
+        " | Changes: " => " | Änderungen: ",
+        "No release notes." => "Keine Versionshinweise.",
+        "ENTER install | / search | ]/[ versions | PGUP/PGDN g/G scroll | l remind me later | ESC close" =>
+            "ENTER installieren | / suchen | ]/[ Versionen | PGUP/PGDN g/G blättern | l später erinnern | ESC schließen",
+        " | n/N {} matches" => " | n/N {} Treffer",
+        "Downloading... {} KiB" => "Lade herunter... {} KiB",
+        "Installing..." => "Installiere...",
+        "Update installed. ENTER restarts ppoker, ESC keeps the running version until you quit." =>
+            "Update installiert. ENTER startet ppoker neu, ESC behält die laufende Version bis zum Beenden.",
+        "Update failed: {} | ESC close" => "Update fehlgeschlagen: {} | ESC schließen",
+        // voting page
+        "Overview" => "Übersicht",
+        " | Room: " => " | Raum: ",
+        " | Mirror of: " => " | Spiegel von: ",
+        " | State: " => " | Status: ",
+        " | Round: " => " | Runde: ",
+        " | Topic: " => " | Thema: ",
+        "Bucket" => "Kategorie",
+        "{}/{} voted" => "{}/{} abgestimmt",
+        "{} left" => "noch {}",
+        "over by {}" => "{} überzogen",
+        "{} joins/leaves in the last minute, {} names seen" => "{} Beitritte/Abgänge in der letzten Minute, {} Namen gesehen",
+        "Has changes" => "Hat Änderungen",
+        "Update v{} available, press U" => "Update v{} verfügbar, U drücken",
+        "Playing" => "Läuft",
+        "Waiting" => "Wartet",
+        "Unknown" => "Unbekannt",
+        "Players" => "Spieler",
+        "Vote" => "Stimme",
+        "Type" => "Typ",
+        "Spectator" => "Zuschauer",
+        "Away" => "Abwesend",
+        "Player" => "Spieler",
+        "+{} more ({}/{} to scroll)" => "+{} weitere ({}/{} zum Blättern)",
+        "{} players ({}/{} to scroll)" => "{} Spieler ({}/{} zum Blättern)",
+        "Log" => "Protokoll",
+        "Log (chat only)" => "Protokoll (nur Chat)",
+        "Log (server events only)" => "Protokoll (nur Serverereignisse)",
+        "Log (errors only)" => "Protokoll (nur Fehler)",
+        "{} of round {}, ESC for all" => "{} der Runde {}, ESC für alle",
+        "{} players joined or left" => "{} Spieler kamen oder gingen",
+        "{} players changed their cards" => "{} Spieler änderten ihre Karte",
+        "{} players changed their names" => "{} Spieler änderten ihren Namen",
+        "Your vote" => "Deine Stimme",
+        "Vote distribution" => "Verteilung",
+        "Average vote" => "Durchschnitt",
+        "Average {}" => "Durchschnitt {}",
+        "Suggested: {}" => "Vorschlag: {}",
+        "majority: {}" => "Mehrheit: {}",
+        "abstained/unsure: {}" => "enthalten/unsicher: {}",
+        "Unknown state" => "Unbekannter Zustand",
+        "The room is in a state this version doesn't know, please update ppoker." =>
+            "Der Raum ist in einem Zustand, den diese Version nicht kennt, bitte aktualisiere ppoker.",
+        "Mention" => "Erwähnen",
+        "Item" => "Eintrag",
+        "Dots ({} voted)" => "Punkte ({} abgestimmt)",
+        "same as yours" => "wie deine",
+        "same as the average" => "wie der Durchschnitt",
+        "higher" => "höher",
+        "lower" => "niedriger",
+        " Vote colors are off" => " Die Farben der Stimmen sind aus",
+        // inputs and countdowns of the voting page
+        "   Enter {}" => "   Eingabe: {}",
+        "   Possible values:" => "   Mögliche Werte:",
+        " Fast vote: press the key below a card, ESC to cancel" => " Schnell abstimmen: Taste unter einer Karte drücken, ESC bricht ab",
+        "Auto reveal" => "Automatisch aufdecken",
+        "Everyone has voted, revealing in {}s." => "Alle haben abgestimmt, aufgedeckt wird in {}s.",
+        "wait {}s" => "{}s warten",
+        "reveal now" => "jetzt aufdecken",
+        "cancel" => "abbrechen",
+        "Auto restart" => "Automatisch neu starten",
+        "Next round starts in {}s." => "Die nächste Runde startet in {}s.",
+        "start now" => "jetzt starten",
+        "stay on the results" => "bei den Ergebnissen bleiben",
+        "Rename" => "Umbenennen",
+        "Technique: poker, dots, buckets or magic followed by items separated by |" =>
+            "Technik: poker, dots, buckets oder magic, gefolgt von Einträgen getrennt durch |",
+        "Reveal for everyone in seconds, at most {}" => "Für alle aufdecken in Sekunden, höchstens {}",
+        "Someone else is called {} too, n to rename " => "Jemand anderes heißt auch {}, n zum Umbenennen ",
+        "{} explains first " => "{} erklärt zuerst ",
+        "{} reveals in {}s, R to cancel " => "{} deckt in {}s auf, R bricht ab ",
+        // screen reader lines of the voting page
+        "Room: {}. State: {}. Round: {}." => "Raum: {}. Status: {}. Runde: {}.",
+        "Topic: {}." => "Thema: {}.",
+        "Your vote: {}." => "Deine Stimme: {}.",
+        "hidden" => "verborgen",
+        "none" => "keine",
+        "Votes: {} of {}." => "Stimmen: {} von {}.",
+        "Reactions: {}." => "Reaktionen: {}.",
+        "Players:" => "Spieler:",
+        "spectator" => "Zuschauer",
+        "no vote" => "keine Stimme",
+        "voted" => "abgestimmt",
+        "average" => "Durchschnitt",
+        "No group" => "Ohne Gruppe",
+        " (you)" => " (du)",
+        "Error: {}" => "Fehler: {}",
+        // rooms page
+        "Rooms" => "Räume",
+        "Room" => "Raum",
+        "Participants" => "Teilnehmer",
+        "Loading the rooms..." => "Lade die Räume...",
+        "There are no active rooms." => "Es gibt keine aktiven Räume.",
+        "This server doesn't list its rooms, use :room <room> instead." =>
+            "Dieser Server listet seine Räume nicht, nutze stattdessen :room <Raum>.",
+        "Failed to load the rooms: {}" => "Die Räume konnten nicht geladen werden: {}",
+        "New room" => "Neuer Raum",
+        "deck of the server" => "Deck des Servers",
+        " (Tab to change)" => " (Tab wechselt)",
+        "Enter creates the room and copies the invite" => "Enter erstellt den Raum und kopiert die Einladung",
+        "Failed to create room {}: {}" => "Raum {} konnte nicht erstellt werden: {}",
+        "Failed to join room {}: {}" => "Raum {} konnte nicht betreten werden: {}",
+        // flash and log messages
+        "Enter the number of seconds until the reveal." => "Gib die Sekunden bis zum Aufdecken ein.",
+        "The reveal can only be scheduled during a round." => "Das Aufdecken kann nur während einer Runde geplant werden.",
+        "The first speaker is picked after the reveal." => "Wer zuerst erklärt, wird nach dem Aufdecken gewählt.",
+        "Nobody voted." => "Niemand hat abgestimmt.",
+        "Reactions are for the revealed cards." => "Reaktionen gibt es erst für aufgedeckte Karten.",
+        "Welcome back, switching you back to player." => "Willkommen zurück, du spielst wieder mit.",
+        "You seem to be away, switching you to spectator until you press a key." =>
+            "Du scheinst weg zu sein, bis zum nächsten Tastendruck bist du Zuschauer.",
+        "Deck changed to: {}" => "Deck geändert auf: {}",
+        "The deck changed." => "Das Deck hat sich geändert.",
+        "Your vote {} is not in the deck anymore, please vote again." =>
+            "Deine Stimme {} ist nicht mehr im Deck, bitte stimme erneut ab.",
+        "This is a read-only mirror of another instance." => "Dies ist ein schreibgeschützter Spiegel einer anderen Instanz.",
+        "Failed to write the journal: {}" => "Das Journal konnte nicht geschrieben werden: {}",
+        "Someone else in the room is called {} as well, press n to rename." =>
+            "Jemand anderes im Raum heißt auch {}, drücke n zum Umbenennen.",
+        "Confidence must be between 1 and {}." => "Die Sicherheit muss zwischen 1 und {} liegen.",
+        "Vote retracted." => "Stimme zurückgezogen.",
+        "Opened {}" => "{} geöffnet",
+        "Failed to open {}: {}" => "{} konnte nicht geöffnet werden: {}",
+        "No link in the log" => "Kein Link im Protokoll",
+        "No hidden chat message" => "Keine verborgene Chatnachricht",
+        "Invite: {}" => "Einladung: {}",
+        "Invite copied to the clipboard." => "Einladung in die Zwischenablage kopiert.",
+        "Message queued, {} waiting to be sent" => "Nachricht vorgemerkt, {} warten auf den Versand",
+        "There are no numeric votes to suggest a card from." => "Es gibt keine numerischen Stimmen für einen Vorschlag.",
+        "Imported {} issues from {}, :next starts the first." => "{} Issues aus {} importiert, :next startet das erste.",
+        "Posted the estimate to #{}." => "Schätzung an #{} gesendet.",
+        "There are no more imported issues." => "Es gibt keine importierten Issues mehr.",
+        "There is no imported issue to post the estimate to, start one with :next." =>
+            "Es gibt kein importiertes Issue für die Schätzung, starte eines mit :next.",
+        "The estimate can only be posted after the reveal." => "Die Schätzung kann erst nach dem Aufdecken gesendet werden.",
+        "There are no numeric votes, post the estimate with :post <card>." =>
+            "Es gibt keine numerischen Stimmen, sende die Schätzung mit :post <Karte>.",
+        "Posting the estimate {} to #{}..." => "Sende die Schätzung {} an #{}...",
+        "Exported history to {}" => "Verlauf exportiert nach {}",
+        "Failed to export history: {}" => "Der Verlauf konnte nicht exportiert werden: {}",
+        "Exported session summary to {}" => "Zusammenfassung exportiert nach {}",
+        "Failed to export session summary: {}" => "Die Zusammenfassung konnte nicht exportiert werden: {}",
+        "Everyone has voted, revealing in {} seconds." => "Alle haben abgestimmt, aufgedeckt wird in {} Sekunden.",
+        "{} is now known as {}." => "{} heißt jetzt {}.",
+        "Post the estimate to #{} with :post or :post <card>." => "Sende die Schätzung an #{} mit :post oder :post <Karte>.",
+        "Card is not in the deck: {}, did you mean {}?" => "Die Karte ist nicht im Deck: {}, meintest du {}?",
+        "Card is not in the deck: {}" => "Die Karte ist nicht im Deck: {}",
+        "{} cancelled the scheduled reveal." => "{} hat das geplante Aufdecken abgebrochen.",
+        "{} scheduled the reveal in {}s." => "{} deckt in {}s auf.",
+        "Unknown theme: {}" => "Unbekanntes Farbschema: {}",
+        "Unknown page: {}" => "Unbekannte Seite: {}",
+        "Unknown command: {}" => "Unbekannter Befehl: {}",
+        // page tabs and the other pages
+        "Voting" => "Abstimmung",
+        "Stats" => "Statistik",
+        "Summary" => "Zusammenfassung",
+        "Terminal too small" => "Terminal zu klein",
+        "{}x{}, needs at least {}x{}" => "{}x{}, mindestens {}x{} nötig",
+        "{} of {} points committed" => "{} von {} Punkten zugesagt",
+        "Command" => "Befehl",
+        "Round" => "Runde",
+        "Rounds" => "Runden",
+        "Average" => "Mittel",
+        "Highest" => "Höchste",
+        "Lowest" => "Tiefste",
+        "Duration" => "Dauer",
+        "Topic" => "Thema",
+        "Consensus" => "Konsens",
+        "yes" => "ja",
+        "no" => "nein",
+        "Player stats" => "Spielerstatistik",
+        "{} rounds, {} ended in consensus" => "{} Runden, {} endeten im Konsens",
+        "{} room updates received, " => "{} Raum-Updates empfangen, ",
+        "{} server errors" => "{} Serverfehler",
+        ", ping {}/{}/{}ms min/avg/max, {}ms jitter" => ", Ping {}/{}/{}ms min/mittel/max, {}ms Jitter",
+        "Session summary" => "Zusammenfassung der Sitzung",
+        "{} rounds, {} consensus, {} in total" => "{} Runden, {} Konsens, {} insgesamt",
+        "Slowest discussions" => "Längste Diskussionen",
+        "Exported to {}" => "Exportiert nach {}",
+        "Failed to export: {}" => "Export fehlgeschlagen: {}",
+        // estimation techniques
+        "Planning poker" => "Planning Poker",
+        "Dot voting" => "Punktabstimmung",
+        "Bucket sort" => "Bucket Sort",
+        "Magic estimation" => "Magic Estimation",
+        "a card from the deck" => "eine Karte aus dem Deck",
+        "up to 3 item numbers, e.g. 1 1 3" => "bis zu 3 Eintragsnummern, z.B. 1 1 3",
+        "item number and card, e.g. 2 8" => "Eintragsnummer und Karte, z.B. 2 8",
+        "No estimation technique is active." => "Keine Schätztechnik ist aktiv.",
+        "Give up to {} dots as item numbers between 1 and {}." => "Vergib bis zu {} Punkte als Eintragsnummern zwischen 1 und {}.",
+        "Place an item by its number and a card, e.g. 2 8." => "Platziere einen Eintrag mit seiner Nummer und einer Karte, z.B. 2 8.",
+        "Item numbers are between 1 and {}." => "Eintragsnummern liegen zwischen 1 und {}.",
+        "Unknown technique {}, use poker, dots, buckets or magic." => "Unbekannte Technik {}, nutze poker, dots, buckets oder magic.",
+        "List the items to estimate separated by |, e.g. dots Login | Search" =>
+            "Liste die Einträge getrennt durch | auf, z.B. dots Login | Suche",
+        // server errors
+        "The server reported an error: {}" => "Der Server meldete einen Fehler: {}",
+        "The server sent a message this version does not understand." =>
+            "Der Server schickte eine Nachricht, die diese Version nicht versteht.",
+        "Skipped a message from the server that failed to parse: {}" =>
+            "Eine Nachricht des Servers konnte nicht gelesen werden und wurde übersprungen: {}",
+        // help
+        "Help - press any key to close" => "Hilfe - eine beliebige Taste schließt",
+        "This page" => "Diese Seite",
+        "Everywhere" => "Überall",
+        "Back to the menu" => "Zurück zum Menü",
+        "Back to voting" => "Zurück zur Abstimmung",
+        "Cancel" => "Abbrechen",
+        "Cancel the automatic reveal for this round" => "Das automatische Aufdecken dieser Runde abbrechen",
+        "Change your name" => "Deinen Namen ändern",
+        "Close" => "Schließen",
+        "Close the completion" => "Die Vervollständigung schließen",
+        "Close the details or quit" => "Die Details schließen oder beenden",
+        "Confirm" => "Bestätigen",
+        "Create a new room" => "Einen neuen Raum erstellen",
+        "Create and join the room, copying the invite" => "Den Raum erstellen, betreten und die Einladung kopieren",
+        "Cut the word after the cursor" => "Das Wort nach dem Cursor ausschneiden",
+        "Cut the word before the cursor" => "Das Wort vor dem Cursor ausschneiden",
+        "Cut to the end of the line" => "Bis zum Zeilenende ausschneiden",
+        "Cut to the start of the line" => "Bis zum Zeilenanfang ausschneiden",
+        "Cut up to the previous space" => "Bis zum vorigen Leerzeichen ausschneiden",
+        "Delete the character before the cursor" => "Das Zeichen vor dem Cursor löschen",
+        "Delete the character under the cursor" => "Das Zeichen unter dem Cursor löschen",
+        "Enter Page mode, scroll up/down" => "Blättermodus starten, hoch/runter blättern",
+        "Exit page mode" => "Blättermodus beenden",
+        "Export the summary as html" => "Die Zusammenfassung als HTML exportieren",
+        "Export the summary as markdown" => "Die Zusammenfassung als Markdown exportieren",
+        "Fast vote, a single key picks a card" => "Schnell abstimmen, eine Taste wählt eine Karte",
+        "Insert the selected name" => "Den gewählten Namen einfügen",
+        "Install the update, restart once it is installed" => "Das Update installieren, nach der Installation neu starten",
+        "Join the selected room" => "Den gewählten Raum betreten",
+        "Jump to the end of the line" => "Zum Zeilenende springen",
+        "Jump to the start of the line" => "Zum Zeilenanfang springen",
+        "Leave log view" => "Die Protokollansicht verlassen",
+        "Load the rooms again" => "Die Räume neu laden",
+        "Move the cursor" => "Den Cursor bewegen",
+        "Move the cursor by words" => "Den Cursor wortweise bewegen",
+        "Navigate" => "Navigieren",
+        "Next deck" => "Nächstes Deck",
+        "Next or previous match" => "Nächster oder vorheriger Treffer",
+        "Next page" => "Nächste Seite",
+        "Next or previous version" => "Nächste oder vorherige Version",
+        "Open the command line" => "Die Befehlszeile öffnen",
+        "Open the last link of the chat in the browser" => "Den letzten Link des Chats im Browser öffnen",
+        "Paste the last cut text" => "Den zuletzt ausgeschnittenen Text einfügen",
+        "Pick who explains their vote first after reveal" => "Wählen, wer die Stimme nach dem Aufdecken zuerst erklärt",
+        "Post the suggested card to the chat after reveal" => "Die vorgeschlagene Karte nach dem Aufdecken in den Chat schreiben",
+        "Previous deck" => "Vorheriges Deck",
+        "Previous page" => "Vorherige Seite",
+        "Quit" => "Beenden",
+        "Quit application" => "Die Anwendung beenden",
+        "React to the revealed cards with thumbs up, thumbs down, surprise or thinking" =>
+            "Auf die aufgedeckten Karten mit Daumen hoch, Daumen runter, Staunen oder Nachdenken reagieren",
+        "Recall previous inputs" => "Frühere Eingaben zurückholen",
+        "Reduce/increase level" => "Stufe verringern/erhöhen",
+        "Remind me later" => "Später erinnern",
+        "Retract your vote" => "Deine Stimme zurückziehen",
+        "Reveal for everyone in a number of seconds, or cancel the scheduled reveal" =>
+            "In einigen Sekunden für alle aufdecken oder das geplante Aufdecken abbrechen",
+        "Reveal now" => "Jetzt aufdecken",
+        "Reveal the cards or start a new round" => "Die Karten aufdecken oder eine neue Runde starten",
+        "Scroll the players" => "Durch die Spieler blättern",
+        "Scroll the players by a page" => "Seitenweise durch die Spieler blättern",
+        "Scroll the release notes" => "Durch die Versionshinweise blättern",
+        "Scroll the release notes by a page" => "Seitenweise durch die Versionshinweise blättern",
+        "Scroll to the first or last player" => "Zum ersten oder letzten Spieler blättern",
+        "Scroll to the top or the end" => "Zum Anfang oder Ende blättern",
+        "Search the release notes" => "Die Versionshinweise durchsuchen",
+        "Select the first or last round" => "Die erste oder letzte Runde wählen",
+        "Select the next name" => "Den nächsten Namen wählen",
+        "Select the next player" => "Den nächsten Spieler wählen",
+        "Select the next room" => "Den nächsten Raum wählen",
+        "Select the next round" => "Die nächste Runde wählen",
+        "Select the previous name" => "Den vorherigen Namen wählen",
+        "Select the previous player" => "Den vorherigen Spieler wählen",
+        "Select the previous room" => "Den vorherigen Raum wählen",
+        "Select the previous round" => "Die vorherige Runde wählen",
+        "Select the round a page up or down" => "Die Runde eine Seite weiter oben oder unten wählen",
+        "Show and install the available update" => "Das verfügbare Update anzeigen und installieren",
+        "Show only chat, server events, errors or everything in the log" =>
+            "Im Protokoll nur Chat, Serverereignisse, Fehler oder alles anzeigen",
+        "Show or hide the legend of the vote colors" => "Die Legende der Farben der Stimmen ein- oder ausblenden",
+        "Show the chat and log of the round" => "Chat und Protokoll der Runde anzeigen",
+        "Show the history" => "Den Verlauf anzeigen",
+        "Show the log" => "Das Protokoll anzeigen",
+        "Show the newest chat message hidden by the chat filter" =>
+            "Die neueste vom Chatfilter verborgene Nachricht anzeigen",
+        "Show the votes of the selected player" => "Die Stimmen des gewählten Spielers anzeigen",
+        "Show this help" => "Diese Hilfe anzeigen",
+        "Show your vote and the average as big or plain text" => "Deine Stimme und den Durchschnitt groß oder als einfachen Text anzeigen",
+        "Start a new line, Shift+ENTER where the terminal supports it" =>
+            "Eine neue Zeile beginnen, Shift+ENTER wo das Terminal es unterstützt",
+        "Start the next round now" => "Die nächste Runde jetzt starten",
+        "Start voting with a negative card" => "Mit einer negativen Karte abstimmen",
+        "Start voting with this card" => "Mit dieser Karte abstimmen",
+        "Stay on the results" => "Bei den Ergebnissen bleiben",
+        "Swap the pasted text for an older cut" => "Den eingefügten Text gegen einen älteren Ausschnitt tauschen",
+        "Switch the estimation technique" => "Die Schätztechnik wechseln",
+        "Toggle focus" => "Fokus wechseln",
+        "Toggle hiding disabled targets" => "Deaktivierte Ziele ein- oder ausblenden",
+        "Toggle target selector" => "Zielauswahl ein- oder ausblenden",
+        "Vote for the card with this hint" => "Für die Karte mit diesem Hinweis abstimmen",
+        "Wait a few more seconds, announced in the chat" => "Noch ein paar Sekunden warten, im Chat angekündigt",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_with_english_fallback() {
+        assert_eq!(translate(Language::German, "Quit"), "Beenden");
+        assert_eq!(german_footer("Quit"), Some("q Beenden"));
+        assert_eq!(translate(Language::German, "Chat"), "Chat");
+        assert_eq!(translate(Language::English, "Quit"), "Quit");
+        assert_eq!(language_of_locale(Some("de_DE.UTF-8")), Language::German);
+        assert_eq!(language_of_locale(Some("en_US.UTF-8")), Language::English);
+        assert_eq!(language_of_locale(None), Language::English);
+    }
+
+    #[test]
+    fn fills_in_arguments() {
+        assert_eq!(tr_args("Update to v{}", &["1.2.0"]), "Update to v1.2.0");
+        assert_eq!(tr_args("Consensus!", &["unused"]), "Consensus!");
+    }
+}
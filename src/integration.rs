@@ -0,0 +1,189 @@
+//! Posts summaries of the rounds or the whole session to a Slack or Discord channel through an incoming webhook, with
+//! the formatting of the service. Players with the lowest and highest votes are listed, so they can explain them.
+//! Names and topics come from other players, so they are escaped and never ping anyone.
+
+use std::thread;
+
+use log::{debug, error};
+use reqwest::blocking::Client;
+use reqwest::header;
+use serde_json::{json, Value};
+
+use crate::app::{AppResult, HistoryEntry};
+use crate::config::Integration;
+use crate::github::Issue;
+use crate::models::{nearest_card, UserType, Vote, VoteData};
+
+fn bold(integration: Integration, text: &str) -> String {
+    match integration {
+        Integration::Slack => format!("*{}*", text),
+        Integration::Discord => format!("**{}**", text),
+    }
+}
+
+/// Escapes the characters Slack uses for links and mentions like `<!channel>`.
+fn escape_mrkdwn(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Players with the lowest or the highest numeric vote, unless it is the estimate.
+fn outliers<'a>(entry: &'a HistoryEntry, estimate: Option<&str>) -> Vec<(&'a str, u8)> {
+    let votes: Vec<(&str, u8)> = entry.votes.iter()
+        .filter(|p| p.user_type != UserType::Spectator)
+        .filter_map(|p| match p.vote {
+            Vote::Revealed(VoteData::Number(n)) => Some((p.name.as_str(), n)),
+            _ => None,
+        })
+        .collect();
+    let (Some(min), Some(max)) = (votes.iter().map(|v| v.1).min(), votes.iter().map(|v| v.1).max()) else {
+        return vec![];
+    };
+    votes.into_iter()
+        .filter(|(_, vote)| (*vote == min || *vote == max) && estimate != Some(vote.to_string().as_str()))
+        .collect()
+}
+
+fn round_lines(integration: Integration, entry: &HistoryEntry) -> Vec<String> {
+    let mut lines = vec![];
+    let estimate = nearest_card(entry.average, &entry.deck);
+    match estimate {
+        Some(card) => lines.push(format!("Estimate {}, average {:.1}", bold(integration, card), entry.average)),
+        None => lines.push("No numeric votes".to_string()),
+    }
+    if entry.consensus {
+        lines.push("Consensus!".to_string());
+    }
+    let votes: Vec<String> = entry.votes.iter()
+        .filter(|p| p.user_type != UserType::Spectator)
+        .map(|p| format!("{} {}", p.name, p.vote))
+        .collect();
+    lines.push(format!("Votes: {}", votes.join(", ")));
+    let outliers: Vec<String> = outliers(entry, estimate).into_iter().map(|(name, vote)| format!("{} ({})", name, vote)).collect();
+    if !outliers.is_empty() {
+        lines.push(format!("Outliers: {}", outliers.join(", ")));
+    }
+    lines
+}
+
+fn message(integration: Integration, title: &str, url: Option<&str>, lines: &[String]) -> Value {
+    match integration {
+        Integration::Slack => {
+            let title = escape_mrkdwn(title);
+            let heading = match url {
+                Some(url) => format!("<{}|{}>", url, title),
+                None => title.clone(),
+            };
+            let lines: Vec<String> = lines.iter().map(|line| escape_mrkdwn(line)).collect();
+            json!({
+                "text": title,
+                "blocks": [{
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": format!("{}\n{}", bold(integration, &heading), lines.join("\n")) },
+                }],
+            })
+        }
+        Integration::Discord => {
+            let mut embed = json!({ "title": title, "description": lines.join("\n") });
+            if let Some(url) = url {
+                embed["url"] = json!(url);
+            }
+            json!({ "embeds": [embed], "allowed_mentions": { "parse": [] } })
+        }
+    }
+}
+
+/// Summary of a revealed round, linking the story it estimated.
+pub fn round_message(integration: Integration, room: &str, entry: &HistoryEntry, story: Option<&Issue>) -> Value {
+    let title = match (story, &entry.topic) {
+        (Some(story), _) => format!("#{} {}", story.number, story.title),
+        (None, Some(topic)) => topic.clone(),
+        (None, None) => format!("Round {} in {}", entry.round_number, room),
+    };
+    message(integration, &title, story.map(|s| s.html_url.as_str()), &round_lines(integration, entry))
+}
+
+/// Summary of all rounds of the session, one line each.
+pub fn session_message(integration: Integration, room: &str, history: &[HistoryEntry]) -> Value {
+    let lines: Vec<String> = history.iter().map(|entry| {
+        let topic = entry.topic.as_deref().map(|topic| format!(" {}", topic)).unwrap_or_default();
+        let estimate = nearest_card(entry.average, &entry.deck).map_or("-".to_string(), |card| bold(integration, card));
+        format!("Round {}{}: {} (average {:.1})", entry.round_number, topic, estimate, entry.average)
+    }).collect();
+    message(integration, &format!("Planning poker in {}: {} rounds", room, history.len()), None, &lines)
+}
+
+pub fn post(url: &str, message: &Value) -> AppResult<()> {
+    debug!("Posting summary to the webhook");
+    Client::new().post(url)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(message.to_string())
+        .send()?
+        .error_for_status()?;
+    Ok(())
+}
+
+pub fn post_in_background(url: String, message: Value) {
+    thread::spawn(move || {
+        if let Err(e) = post(&url, &message) {
+            error!("Failed to post the summary: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::models::Player;
+
+    use super::*;
+
+    fn entry() -> HistoryEntry {
+        HistoryEntry {
+            round_number: 2,
+            average: 7.0,
+            length: Duration::from_secs(60),
+            votes: vec![
+                Player::new("alice", Vote::Revealed(VoteData::Number(5))),
+                Player::new("bob", Vote::Revealed(VoteData::Number(8))),
+                Player::new("carol", Vote::Revealed(VoteData::Number(8))),
+            ],
+            deck: ["3", "5", "8", "13"].iter().map(|c| c.to_string()).collect(),
+            topic: Some("Login".to_string()),
+            ..HistoryEntry::default()
+        }
+    }
+
+    #[test]
+    fn formats_rounds_for_slack() {
+        let message = round_message(Integration::Slack, "planning", &entry(), None);
+        assert_eq!(message["text"], "Login");
+        assert_eq!(message["blocks"][0]["text"]["text"], "*Login*\nEstimate *8*, average 7.0\n\
+            Votes: alice 5, bob 8, carol 8\nOutliers: alice (5)");
+    }
+
+    #[test]
+    fn names_never_ping_the_channel() {
+        let mut entry = entry();
+        entry.votes[0].name = "<!channel>".to_string();
+        entry.topic = Some("@everyone & co".to_string());
+
+        let slack = round_message(Integration::Slack, "planning", &entry, None);
+        assert_eq!(slack["text"], "@everyone &amp; co");
+        assert!(slack["blocks"][0]["text"]["text"].as_str().unwrap().contains("Outliers: &lt;!channel&gt; (5)"));
+
+        let discord = round_message(Integration::Discord, "planning", &entry, None);
+        assert_eq!(discord["allowed_mentions"], json!({ "parse": [] }));
+    }
+
+    #[test]
+    fn formats_rounds_for_discord_with_the_story() {
+        let story: Issue = serde_json::from_value(json!({
+            "number": 12, "title": "Login", "html_url": "https://github.com/o/r/issues/12",
+        })).unwrap();
+        let message = round_message(Integration::Discord, "planning", &entry(), Some(&story));
+        assert_eq!(message["embeds"][0]["title"], "#12 Login");
+        assert_eq!(message["embeds"][0]["url"], "https://github.com/o/r/issues/12");
+        assert!(message["embeds"][0]["description"].as_str().unwrap().starts_with("Estimate **8**"));
+    }
+}
@@ -0,0 +1,85 @@
+//! Invites to a room: the command line joining it, copied to the clipboard of the terminal.
+
+use std::io;
+use std::io::Write;
+
+use crate::config::Config;
+
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Random name for a new room, like `wildly-happy-otter`.
+pub fn generate_room_name() -> String {
+    petname::petname(3, "-").expect("Failed to generate random room name")
+}
+
+/// The command line others run to join the room of the config, with its deck if one is set.
+pub fn invite(config: &Config) -> String {
+    let mut args = vec!["ppoker", "--server", &config.server, "--room", &config.room];
+    if let Some(deck) = &config.deck {
+        args.extend(["--deck", deck]);
+    }
+    args.into_iter().map(quote).collect::<Vec<_>>().join(" ")
+}
+
+/// Quotes an argument for POSIX shells if it contains anything besides a few safe characters.
+fn quote(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./:@%+=,".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    let mut result = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(BASE64_ALPHABET[((bits >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+/// Sets the clipboard through an OSC 52 sequence, which also works over ssh. Terminals without support ignore it,
+/// tmux passes it on with `set-clipboard on`.
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    // the ui is drawn on stderr, so that is the terminal
+    let mut stderr = io::stderr();
+    write!(stderr, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stderr.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_base64_with_padding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64("ppoker ä".as_bytes()), "cHBva2VyIMOk");
+    }
+
+    #[test]
+    fn invite_quotes_for_the_shell() {
+        let mut config = Config {
+            server: "wss://poker.example.com/api".to_string(),
+            room: "sprint-42".to_string(),
+            ..Config::default()
+        };
+        assert_eq!(invite(&config), "ppoker --server wss://poker.example.com/api --room sprint-42");
+
+        config.room = "Bob's room".to_string();
+        config.deck = Some("fibonacci".to_string());
+        assert_eq!(invite(&config), r"ppoker --server wss://poker.example.com/api --room 'Bob'\''s room' --deck fibonacci");
+    }
+}
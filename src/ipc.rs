@@ -0,0 +1,275 @@
+use std::fs;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info, warn};
+use snafu::Snafu;
+
+use crate::app::AppResult;
+use crate::models::{LogEntry, Room, UserType};
+use crate::web::client::{convert_initial_room, convert_room_updates, PokerClient};
+use crate::web::dto;
+
+#[derive(Debug, Snafu)]
+pub enum IpcError {
+    #[snafu(display("The followed instance did not send a room state in time."))]
+    InitialStateMissing,
+    #[snafu(display("The followed instance closed the connection."))]
+    LeaderClosedConnection,
+    #[snafu(display("Another instance is publishing on the IPC socket {:?}.", path))]
+    SocketInUse { path: PathBuf },
+    #[snafu(display("{:?} is not an IPC socket, not replacing it.", path))]
+    NotASocket { path: PathBuf },
+}
+
+/// Removes the socket left behind by an instance that didn't exit cleanly, but nothing else at the path.
+fn remove_stale_socket(path: &Path) -> AppResult<()> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(Box::new(IpcError::NotASocket { path: path.to_path_buf() }));
+    }
+    if UnixStream::connect(path).is_ok() {
+        return Err(Box::new(IpcError::SocketInUse { path: path.to_path_buf() }));
+    }
+    warn!("Removing stale IPC socket {:?}.", path);
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+/// Publishes every room state received from the server to all connected followers, one JSON document per line.
+#[derive(Debug)]
+pub struct IpcServer {
+    path: PathBuf,
+    inode: u64,
+    followers: Arc<Mutex<Vec<UnixStream>>>,
+    last_state: Arc<Mutex<Option<String>>>,
+}
+
+impl IpcServer {
+    pub fn bind(path: &str) -> AppResult<Self> {
+        let path = PathBuf::from(path);
+        remove_stale_socket(&path)?;
+        let listener = UnixListener::bind(&path)?;
+        let inode = fs::metadata(&path)?.ino();
+        info!("Publishing room state on IPC socket {:?}.", path);
+
+        let followers: Arc<Mutex<Vec<UnixStream>>> = Arc::new(Mutex::new(vec![]));
+        let last_state: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+        {
+            let followers = followers.clone();
+            let last_state = last_state.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(mut stream) => {
+                            info!("Follower connected to IPC socket.");
+                            if let Err(e) = stream.set_write_timeout(Some(Duration::from_millis(100))) {
+                                warn!("Failed to set write timeout for follower: {}", e);
+                            }
+                            let mut followers = followers.lock().expect("IPC follower list poisoned");
+                            if let Some(state) = last_state.lock().expect("IPC state poisoned").as_ref() {
+                                if let Err(e) = writeln!(stream, "{}", state) {
+                                    warn!("Failed to send initial state to follower: {}", e);
+                                    continue;
+                                }
+                            }
+                            followers.push(stream);
+                        }
+                        Err(e) => error!("Failed to accept IPC connection: {}", e),
+                    }
+                }
+            });
+        }
+
+        Ok(Self { path, inode, followers, last_state })
+    }
+
+    pub fn publish(&self, room: &dto::Room) -> AppResult<()> {
+        let state = serde_json::to_string(room)?;
+        let mut followers = self.followers.lock().expect("IPC follower list poisoned");
+        followers.retain_mut(|stream| {
+            let result = writeln!(stream, "{}", state);
+            if result.is_err() {
+                info!("Follower disconnected from IPC socket.");
+            }
+            result.is_ok()
+        });
+        *self.last_state.lock().expect("IPC state poisoned") = Some(state);
+        Ok(())
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        // a new server may have taken over the path in the meantime
+        if fs::metadata(&self.path).map(|m| m.ino()).ok() != Some(self.inode) {
+            return;
+        }
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("Failed to remove IPC socket {:?}: {}", self.path, e);
+        }
+    }
+}
+
+/// Read-only client mirroring the room of another instance through its IPC socket.
+#[derive(Debug)]
+pub struct FollowerClient {
+    reader: BufReader<UnixStream>,
+    buffer: Vec<u8>,
+}
+
+impl FollowerClient {
+    pub fn new(path: &str) -> AppResult<(Self, Room, Vec<LogEntry>)> {
+        let stream = UnixStream::connect(path)?;
+        stream.set_nonblocking(true)?;
+        info!("Following instance on IPC socket {}.", path);
+
+        let mut result = Self {
+            reader: BufReader::new(stream),
+            buffer: vec![],
+        };
+        for i in 0..20 {
+            if let Some(room) = result.read_all()?.pop() {
+                info!("Got initial room state with delay {}ms.", i * 20);
+                let (room, log) = convert_initial_room(&room);
+                return Ok((result, room, log));
+            } else {
+                thread::sleep(Duration::from_millis(20));
+            }
+        }
+
+        error!("Followed instance did not send initial room state.");
+        Err(Box::new(IpcError::InitialStateMissing))
+    }
+
+    fn read_all(&mut self) -> AppResult<Vec<dto::Room>> {
+        let mut result = vec![];
+        loop {
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0) => {
+                    info!("Followed instance closed the IPC connection. Terminating.");
+                    return Err(Box::new(IpcError::LeaderClosedConnection));
+                }
+                Ok(_) => {
+                    if self.buffer.ends_with(b"\n") {
+                        // a broken line is skipped, the next one starts with an empty buffer
+                        match serde_json::from_slice(&self.buffer) {
+                            Ok(room) => result.push(room),
+                            Err(e) => warn!("Skipping malformed room state from the followed instance: {}", e),
+                        }
+                        self.buffer.clear();
+                    }
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(result),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl PokerClient for FollowerClient {
+    fn get_updates(&mut self) -> AppResult<(Vec<Room>, Vec<LogEntry>)> {
+        let rooms = self.read_all()?;
+        Ok(convert_room_updates(&rooms))
+    }
+
+    fn vote(&mut self, _card_value: Option<&str>) -> AppResult<()> {
+        Ok(())
+    }
+
+    fn change_name(&mut self, _name: &str) -> AppResult<()> {
+        Ok(())
+    }
+
+    fn chat(&mut self, _message: &str) -> AppResult<()> {
+        Ok(())
+    }
+
+    fn change_user_type(&mut self, _user_type: UserType) -> AppResult<()> {
+        Ok(())
+    }
+
+    fn reveal(&mut self) -> AppResult<()> {
+        Ok(())
+    }
+
+    fn reset(&mut self) -> AppResult<()> {
+        Ok(())
+    }
+
+    fn is_read_only(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn room() -> dto::Room {
+        serde_json::from_value(json!({
+            "roomId": "planning",
+            "deck": ["1", "2", "3"],
+            "gamePhase": "PLAYING",
+            "users": [{ "username": "alice", "userType": "PARTICIPANT", "yourUser": true, "cardValue": "" }],
+            "average": "0",
+            "log": [],
+        })).unwrap()
+    }
+
+    #[test]
+    fn followers_get_the_published_room() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ppoker.sock");
+        let server = IpcServer::bind(path.to_str().unwrap()).unwrap();
+        server.publish(&room()).unwrap();
+
+        let (mut client, initial, _) = FollowerClient::new(path.to_str().unwrap()).unwrap();
+        assert_eq!(initial.name, "planning");
+        assert_eq!(initial.players[0].name, "alice");
+
+        server.publish(&room()).unwrap();
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(client.get_updates().unwrap().0.len(), 1);
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let (mut leader, follower) = UnixStream::pair().unwrap();
+        follower.set_nonblocking(true).unwrap();
+        let mut client = FollowerClient { reader: BufReader::new(follower), buffer: vec![] };
+
+        writeln!(leader, "{{\"roomId\": broken").unwrap();
+        assert!(client.read_all().unwrap().is_empty());
+        writeln!(leader, "{}", serde_json::to_string(&room()).unwrap()).unwrap();
+        assert_eq!(client.read_all().unwrap(), vec![room()]);
+    }
+
+    #[test]
+    fn only_stale_sockets_are_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ppoker.sock");
+
+        drop(UnixListener::bind(&path).unwrap());
+        let server = IpcServer::bind(path.to_str().unwrap()).unwrap();
+        assert!(IpcServer::bind(path.to_str().unwrap()).is_err());
+        drop(server);
+        assert!(!path.exists());
+
+        let file = dir.path().join("notes.txt");
+        fs::write(&file, "keep me").unwrap();
+        assert!(IpcServer::bind(file.to_str().unwrap()).is_err());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "keep me");
+    }
+}
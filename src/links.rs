@@ -0,0 +1,92 @@
+use std::env;
+use std::io;
+use std::ops::Range;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+fn url_regex() -> &'static Regex {
+    static URL: OnceLock<Regex> = OnceLock::new();
+    URL.get_or_init(|| Regex::new(r#"https?://[^\s<>"']+"#).expect("invalid url regex"))
+}
+
+/// Byte ranges of the http(s) links in the text. Punctuation at the end belongs to the sentence, not the link.
+pub fn find_urls(text: &str) -> Vec<Range<usize>> {
+    url_regex().find_iter(text)
+        .map(|m| {
+            let url = m.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']']);
+            m.start()..m.start() + url.len()
+        })
+        .collect()
+}
+
+/// Guesses from the environment whether the terminal opens OSC 8 hyperlinks on click. Others may print the sequences,
+/// so their links stay only underlined. Multiplexers don't always pass them on.
+pub fn detect_hyperlinks() -> bool {
+    supports_hyperlinks(|var| env::var(var).ok().filter(|value| !value.is_empty()))
+}
+
+fn supports_hyperlinks(var: impl Fn(&str) -> Option<String>) -> bool {
+    if var("TMUX").is_some() || var("STY").is_some() {
+        return false;
+    }
+    if ["WT_SESSION", "KITTY_WINDOW_ID", "WEZTERM_EXECUTABLE", "KONSOLE_VERSION"].iter().any(|v| var(v).is_some()) {
+        return true;
+    }
+    if var("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()).is_some_and(|v| v >= 5000) {
+        return true;
+    }
+    matches!(var("TERM_PROGRAM").as_deref(), Some("iTerm.app" | "WezTerm" | "vscode" | "ghostty"))
+        || matches!(var("TERM").as_deref(), Some("xterm-kitty" | "alacritty" | "foot" | "xterm-ghostty"))
+}
+
+/// The text as an OSC 8 hyperlink to the url.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Opens the link in the default browser without waiting for it.
+pub fn open_url(url: &str) -> io::Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        // without a shell, links from the chat could run commands through characters like `&` with `cmd /C start`
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = Command::new("xdg-open");
+
+    command.arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_urls_without_trailing_punctuation() {
+        let text = "see https://example.com/a?b=1. and (http://foo.bar/baz)";
+        let urls: Vec<&str> = find_urls(text).into_iter().map(|r| &text[r]).collect();
+        assert_eq!(urls, vec!["https://example.com/a?b=1", "http://foo.bar/baz"]);
+        assert!(find_urls("no links, just ftp://here").is_empty());
+    }
+
+    #[test]
+    fn detects_hyperlink_terminals() {
+        let env = |vars: &'static [(&str, &str)]| move |var: &str| vars.iter().find(|(k, _)| *k == var).map(|(_, v)| v.to_string());
+        assert!(supports_hyperlinks(env(&[("TERM_PROGRAM", "iTerm.app")])));
+        assert!(supports_hyperlinks(env(&[("VTE_VERSION", "7200"), ("TERM", "xterm-256color")])));
+        assert!(!supports_hyperlinks(env(&[("VTE_VERSION", "4800")])));
+        assert!(!supports_hyperlinks(env(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux-1000/default")])));
+        assert!(!supports_hyperlinks(env(&[("TERM", "linux")])));
+    }
+}
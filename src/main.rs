@@ -4,15 +4,17 @@ use std::path::PathBuf;
 
 use filetime::FileTime;
 use glob::glob;
+use clap::Parser;
 use log::{debug, error, info, LevelFilter, warn};
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use regex::Regex;
 
 use crate::app::{App, AppResult};
-use crate::config::{get_config, get_logdir};
+use crate::config::{Cli, Command, Config, get_config};
 use crate::events::EventHandler;
 use crate::tui::Tui;
+use crate::ui::symbols;
 use crate::update::{self_update, UpdateError, UpdateResult};
 
 mod app;
@@ -21,14 +23,47 @@ mod ui;
 mod events;
 mod models;
 mod config;
+mod trust;
 mod web;
 mod update;
 mod notification;
+mod headless;
+mod persist;
+mod estimation;
+mod export;
+mod stats;
+mod links;
+mod announce;
+mod status;
+mod state_file;
+mod overlay;
+mod metrics;
+mod diagnose;
+mod changelog;
+mod hooks;
+mod github;
+mod integration;
+mod i18n;
+mod format;
+mod room_names;
+mod chat_filter;
+mod health;
+mod rooms;
+mod invite;
+mod discovery;
+mod roles;
+mod groups;
+#[cfg(feature = "serve")]
+mod serve;
+#[cfg(unix)]
+mod ipc;
+
+fn setup_logging(config: &Config) -> AppResult<()> {
+    log::set_max_level(config.log_level.filter());
+    tui_logger::set_default_level(config.log_level.filter());
 
-fn setup_logging() -> AppResult<()> {
-    const MAX_LOGFILES: usize = 20;
     let filename_regex = Regex::new(r"main-(?P<index>\d+)\.log")?;
-    let log_dir = get_logdir();
+    let log_dir = config.log_dir();
     if !log_dir.exists() {
         fs::create_dir_all(&log_dir)?;
     }
@@ -39,7 +74,8 @@ fn setup_logging() -> AppResult<()> {
         return FileTime::from_creation_time(&metadata);
     });
 
-    let delete_files = existing_files.len().checked_sub(MAX_LOGFILES).unwrap_or(0);
+    // the new log file counts as well
+    let delete_files = (existing_files.len() + 1).saturating_sub(config.max_log_files.max(1));
     if delete_files > 0 {
         let drain = existing_files.drain(..delete_files);
 
@@ -72,22 +108,15 @@ fn run(app: &mut App, tui: &mut Tui<CrosstermBackend<Stderr>>) -> AppResult<()>
     Ok(())
 }
 
-fn setup() -> AppResult<Option<(App, Tui<CrosstermBackend<Stderr>>)>> {
-    tui_logger::init_logger(LevelFilter::Debug).expect("Unable to setup logging capture");
-    tui_logger::set_default_level(LevelFilter::Debug);
-
-    setup_logging().unwrap_or_else(|err| error!("Failed to setup logging: {:?}", err));
-
-    let config = get_config();
-
-    if !config.skip_update_check {
-        let res = self_update();
+fn setup(config: Config) -> AppResult<Option<(App, Tui<CrosstermBackend<Stderr>>)>> {
+    if !config.skip_update_check && config.update_now {
+        let res = self_update(&config.update_source(), &config.update_channel(), config.update_check_interval());
         match res {
             Ok(UpdateResult::Updated) => {
                 println!("Please restart the application.");
                 return Ok(None);
             }
-            Ok(UpdateResult::UpToDate) => {}
+            Ok(UpdateResult::UpToDate) | Ok(UpdateResult::Snoozed) => {}
             Err(e) => {
                 if matches!(e, UpdateError::NoCompatibleAssetFound) || matches!(e, UpdateError::UserCanceled) {
                     warn!("Current release has no asset for current target.");
@@ -100,6 +129,9 @@ fn setup() -> AppResult<Option<(App, Tui<CrosstermBackend<Stderr>>)>> {
         }
     }
     
+    symbols::set_ascii(config.ascii || config.screen_reader || symbols::detect_ascii());
+    i18n::set_language(config.language.unwrap_or_else(i18n::detect_language));
+    format::set_time_format(config.time_format.unwrap_or_else(format::detect_time_format));
     let app = App::new(config)?;
 
     let backend = CrosstermBackend::new(io::stderr());
@@ -112,11 +144,112 @@ fn setup() -> AppResult<Option<(App, Tui<CrosstermBackend<Stderr>>)>> {
 }
 
 fn execute() -> AppResult<()> {
-    if let Some((mut app, mut tui)) = setup()? {
+    // everything is captured until the configured level is known
+    tui_logger::init_logger(LevelFilter::Trace).expect("Unable to setup logging capture");
+    tui_logger::set_default_level(LevelFilter::Debug);
+
+    let cli = Cli::parse();
+    let mut config = get_config(&cli);
+    setup_logging(&config).unwrap_or_else(|err| error!("Failed to setup logging: {:?}", err));
+    diagnose::init(&config);
+
+    let mut created = false;
+    match cli.command {
+        Some(Command::Watch { metric, room }) => {
+            if let Some(room) = room {
+                config.room = room;
+            }
+            return headless::watch(&config, metric);
+        }
+        Some(Command::Status { room, format }) => {
+            if let Some(room) = room {
+                config.room = room;
+            }
+            return status::print(&config, format);
+        }
+        Some(Command::Create { name, deck }) => {
+            config.room = name.unwrap_or_else(invite::generate_room_name);
+            if deck.is_some() {
+                config.deck = deck;
+            }
+            created = true;
+        }
+        #[cfg(feature = "serve")]
+        Some(Command::Serve { port }) => {
+            let url = serve::start(port, config.custom_deck())?;
+            println!("Serving rooms on {}", url);
+            config.server = format!("ws://localhost:{}", port);
+        }
+        Some(Command::Lan { room }) => {
+            match room {
+                Some(room) => config.room = room,
+                None => match discovery::find_room()? {
+                    Some(room) => {
+                        println!("Joining room {} in the local network", room);
+                        config.room = room;
+                    }
+                    None => println!("Starting room {} in the local network", config.room),
+                },
+            }
+            config.peer = true;
+        }
+        Some(Command::Rooms { server }) => {
+            return rooms::print(server.as_deref().unwrap_or(&config.server));
+        }
+        Some(Command::Ping { server }) => {
+            if let Some(server) = server {
+                config.server = server;
+            }
+            return health::ping(&config);
+        }
+        Some(Command::Diagnose) => {
+            tui_logger::move_events();
+            let path = diagnose::write_report(None)?;
+            println!("Report written to {}", path.display());
+            return Ok(());
+        }
+        None => {}
+    }
+
+    trust::confirm_commands(&mut config)?;
+
+    // a name given on the command line wins over the one remembered for the room
+    config.name_from_cli = cli.name.is_some();
+    if !config.name_from_cli {
+        if let Some(name) = room_names::remembered_name(&config.room) {
+            config.name = name;
+        }
+    }
+
+    if let Some((mut app, mut tui)) = setup(config)? {
+        if created {
+            app.copy_invite();
+        }
         let result = run(&mut app, &mut tui);
         if let Err(e) = tui.exit() {
             error!("Failed to stop tui: {:?}", e)
         }
+        app.remove_state_files();
+        app.write_metrics();
+        match app.write_session_summary() {
+            Ok(Some(path)) => println!("Session summary written to {}", path.display()),
+            Ok(None) => {}
+            Err(e) => error!("Failed to write session summary: {}", e),
+        }
+        match app.post_session_summary() {
+            Ok(true) => println!("Session summary posted to {:?}", app.config.integration.unwrap()),
+            Ok(false) => {}
+            Err(e) => {
+                error!("Failed to post session summary: {}", e);
+                println!("Failed to post the session summary: {}", e);
+            }
+        }
+        if app.restart_after_update {
+            if let Err(e) = update::restart() {
+                error!("Failed to restart the application. {}", e);
+                println!("Please restart the application.");
+            }
+        }
         result
     } else {
         Ok(())
@@ -124,6 +257,7 @@ fn execute() -> AppResult<()> {
 }
 
 fn main() -> AppResult<()> {
+    format::init_local_offset();
     let result = execute();
     tui_logger::move_events();
     result
@@ -0,0 +1,69 @@
+//! Usage and connection metrics of the session in the Prometheus text format.
+
+use std::path::Path;
+use std::time::Duration;
+
+use log::warn;
+
+use crate::persist;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Metrics {
+    pub rounds_completed: u64,
+    /// Websocket connections opened, more than one means the room was switched or joined again.
+    pub connections: u64,
+    pub messages_received: u64,
+    /// Errors and unknown messages from the server.
+    pub server_errors: u64,
+    pub round_seconds: f64,
+}
+
+impl Metrics {
+    pub fn record_round(&mut self, length: Duration) {
+        self.rounds_completed += 1;
+        self.round_seconds += length.as_secs_f64();
+    }
+
+    pub fn render(&self, room: &str) -> String {
+        let labels = format!("{{room=\"{}\"}}", escape_label(room));
+        let mut text = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            text.push_str(&format!("# HELP {} {}\n# TYPE {} {}\n{}{} {}\n", name, help, name, kind, name, labels, value));
+        };
+        metric("ppoker_rounds_completed_total", "counter", "Rounds that were revealed.", self.rounds_completed.to_string());
+        metric("ppoker_connections_total", "counter", "Websocket connections opened to the server.", self.connections.to_string());
+        metric("ppoker_ws_messages_received_total", "counter", "Room updates received from the server.", self.messages_received.to_string());
+        metric("ppoker_ws_server_errors_total", "counter", "Errors and unknown messages received from the server.", self.server_errors.to_string());
+        metric("ppoker_round_duration_seconds_sum", "counter", "Total duration of the completed rounds.", format!("{:.3}", self.round_seconds));
+        metric("ppoker_round_duration_seconds_count", "counter", "Number of rounds in the duration sum.", self.rounds_completed.to_string());
+        text
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Writes the metrics for the textfile collector of the node exporter, which must never see a partial file.
+pub fn write_textfile(path: &Path, content: &str) {
+    if let Err(e) = persist::write_atomic(path, content.as_bytes()) {
+        warn!("Failed to write metrics to {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_prometheus_text_format() {
+        let mut metrics = Metrics { connections: 1, messages_received: 7, ..Metrics::default() };
+        metrics.record_round(Duration::from_millis(1500));
+        metrics.record_round(Duration::from_secs(3));
+
+        let text = metrics.render("team \"a\"");
+        assert!(text.contains("# TYPE ppoker_rounds_completed_total counter\nppoker_rounds_completed_total{room=\"team \\\"a\\\"\"} 2\n"));
+        assert!(text.contains("ppoker_round_duration_seconds_sum{room=\"team \\\"a\\\"\"} 4.500\n"));
+        assert!(text.contains("ppoker_ws_messages_received_total{room=\"team \\\"a\\\"\"} 7\n"));
+    }
+}
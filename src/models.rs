@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 use std::fmt::Formatter;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum VoteData {
@@ -42,23 +42,54 @@ pub enum UserType {
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Player {
+    /// Server side id of the user, which stays the same across renames. Older servers don't send one.
+    pub id: Option<String>,
     pub name: String,
     pub vote: Vote,
     pub is_you: bool,
     pub user_type: UserType,
 }
 
+impl Player {
+    /// Identifies the player for stats and activity tracking, the id if known, else the name.
+    pub fn key(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Whether both refer to the same user, comparing names only if an id is missing.
+    pub fn is_same(&self, other: &Player) -> bool {
+        match (&self.id, &other.id) {
+            (Some(id), Some(other_id)) => id == other_id,
+            _ => self.name == other.name,
+        }
+    }
+}
+
+#[cfg(test)]
+impl Player {
+    /// A player who isn't you, for the tests.
+    pub fn new(name: &str, vote: Vote) -> Self {
+        Player { id: None, name: name.to_string(), vote, is_you: false, user_type: UserType::Player }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GamePhase {
     Playing,
     Revealed,
+    /// A phase of a newer server version, nothing can be done until it changes to a known one.
+    Unknown,
 }
 
+/// Shown while the room is in a phase of a newer server version.
+pub const UNKNOWN_PHASE_WARNING: &str = "The room is in a state this version doesn't know, please update ppoker.";
+
 impl std::fmt::Display for GamePhase {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             GamePhase::Playing => { write!(f, "Playing") }
             GamePhase::Revealed => { write!(f, "Waiting") }
+            GamePhase::Unknown => { write!(f, "Unknown") }
         }
     }
 }
@@ -80,12 +111,56 @@ pub enum LogSource {
 #[derive(Debug, PartialEq, Clone)]
 pub struct LogEntry {
     pub timestamp: Instant,
+    /// Wall clock time of the entry, `timestamp` is only good for measuring how long ago it was.
+    pub time: SystemTime,
     pub level: LogLevel,
     pub message: String,
     pub source: LogSource,
     pub server_index: Option<u32>,
 }
 
+/// Kinds of server events that can flood the log in big rooms.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EventCategory {
+    JoinLeave,
+    Card,
+    Rename,
+}
+
+impl LogEntry {
+    /// Guesses the category of a server event from its message, phase changes and chat have none.
+    pub fn event_category(&self) -> Option<EventCategory> {
+        if self.level != LogLevel::Info {
+            return None;
+        }
+        let message = self.message.to_lowercase();
+        if message.contains("reveal") || message.contains("round") {
+            None
+        } else if message.contains("joined") || message.contains(" left") {
+            Some(EventCategory::JoinLeave)
+        } else if message.contains("name") || message.contains("known as") {
+            Some(EventCategory::Rename)
+        } else if message.contains("card") || message.contains("vote") {
+            Some(EventCategory::Card)
+        } else {
+            None
+        }
+    }
+
+    /// Finds the author of a chat message, which the server prefixes with the sender's name.
+    pub fn chat_sender(&self, players: &[Player]) -> Option<&str> {
+        if self.level != LogLevel::Chat {
+            return None;
+        }
+        let known = players.iter()
+            .map(|p| p.name.as_str())
+            .filter(|name| self.message.strip_prefix(name).is_some_and(|rest| rest.starts_with(':')))
+            .max_by_key(|name| name.len());
+        let len = known.map(|name| name.len()).or_else(|| self.message.find(": "))?;
+        Some(&self.message[..len])
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Room {
     pub name: String,
@@ -94,6 +169,232 @@ pub struct Room {
     pub players: Vec<Player>,
 }
 
+/// Counts the joins and leaves of the minute before `now`, the log being in chronological order.
+pub fn join_leave_rate(log: &[LogEntry], now: Instant) -> usize {
+    log.iter().rev()
+        .take_while(|entry| now.saturating_duration_since(entry.timestamp) < Duration::from_secs(60))
+        .filter(|entry| entry.event_category() == Some(EventCategory::JoinLeave))
+        .count()
+}
+
+/// First of `name2`, `name3`, … nobody in the room is called yet.
+pub fn unique_name(name: &str, players: &[Player]) -> String {
+    (2..).map(|n| format!("{}{}", name, n))
+        .find(|candidate| players.iter().all(|p| p.name != *candidate))
+        .unwrap_or_default()
+}
+
+/// Counts revealed votes that are not numbers, like `?` or coffee, which the average ignores.
+pub fn count_special_votes(players: &[Player]) -> usize {
+    players.iter()
+        .filter(|p| matches!(p.vote, Vote::Revealed(VoteData::Special(_))))
+        .count()
+}
+
+pub const MAX_CONFIDENCE: u8 = 3;
+
+/// Parses how sure a player is about the estimate, from 1 to `MAX_CONFIDENCE`.
+pub fn parse_confidence(value: &str) -> Option<u8> {
+    value.trim().parse::<u8>().ok().filter(|c| (1..=MAX_CONFIDENCE).contains(c))
+}
+
+/// Renders a confidence as filled and empty dots, e.g. `●●○`.
+pub fn format_confidence(confidence: u8, filled_dot: &str, empty_dot: &str) -> String {
+    let filled = confidence.min(MAX_CONFIDENCE) as usize;
+    format!("{}{}", filled_dot.repeat(filled), empty_dot.repeat(MAX_CONFIDENCE as usize - filled))
+}
+
+/// Whether at least two players revealed a number and all numbers are the same.
+pub fn is_consensus(players: &[Player]) -> bool {
+    let mut numbers = players.iter().filter_map(|p| match p.vote {
+        Vote::Revealed(VoteData::Number(n)) => Some(n),
+        _ => None,
+    });
+    let Some(first) = numbers.next() else {
+        return false;
+    };
+    let mut count = 1;
+    for n in numbers {
+        if n != first {
+            return false;
+        }
+        count += 1;
+    }
+    count > 1
+}
+
+/// Numeric deck card closest to the average, rounding up on ties.
+pub fn nearest_card(average: f32, deck: &[String]) -> Option<&str> {
+    if average.is_nan() {
+        return None;
+    }
+    deck.iter()
+        .filter_map(|card| card.parse::<f32>().ok().map(|value| (card.as_str(), (value - average).abs())))
+        .fold(None, |best, (card, distance)| match best {
+            Some((_, best_distance)) if best_distance < distance => best,
+            _ => Some((card, distance)),
+        })
+        .map(|(card, _)| card)
+}
+
+/// Whether a chat message mentions the name with `@name`, ignoring case.
+pub fn mentions(message: &str, name: &str) -> bool {
+    let name = name.trim();
+    if name.is_empty() {
+        return false;
+    }
+    let message = message.to_lowercase();
+    let mention = format!("@{}", name.to_lowercase());
+    message.match_indices(&mention).any(|(start, _)| {
+        !matches!(message[start + mention.len()..].chars().next(), Some(c) if c.is_alphanumeric() || c == '_' || c == '-')
+    })
+}
+
+/// Card of the deck as written there for a typed vote, ignoring case and number formats like `08` or `8.0`.
+pub fn deck_card<'a>(input: &str, deck: &'a [String]) -> Option<&'a str> {
+    let input = input.trim();
+    let number = input.parse::<f32>().ok();
+    deck.iter()
+        .find(|card| card.eq_ignore_ascii_case(input))
+        .or_else(|| deck.iter().find(|card| number.is_some() && card.parse::<f32>().ok() == number))
+        .map(String::as_str)
+}
+
+/// Card the user probably meant with a vote that is not in the deck: the closest number for numbers, otherwise a
+/// card differing by at most two characters.
+pub fn suggest_card<'a>(input: &str, deck: &'a [String]) -> Option<&'a str> {
+    let input = input.trim().to_lowercase();
+    if let Ok(number) = input.parse::<f32>() {
+        return nearest_card(number, deck);
+    }
+    deck.iter()
+        .map(|card| (card.as_str(), edit_distance(&input, &card.to_lowercase())))
+        .filter(|(_, distance)| *distance <= 2)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(card, _)| card)
+}
+
+/// Levenshtein distance in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Picks who explains their vote first after the reveal, `random` choosing among the players with a revealed vote.
+/// Weighted by outliers, a numeric vote further from the average is more likely to be picked.
+pub fn pick_first_speaker(players: &[Player], weighted: bool, random: u64) -> Option<&Player> {
+    let candidates: Vec<&Player> = players.iter()
+        .filter(|p| p.user_type != UserType::Spectator && matches!(p.vote, Vote::Revealed(_)))
+        .collect();
+    let numbers: Vec<f32> = candidates.iter()
+        .filter_map(|p| match p.vote {
+            Vote::Revealed(VoteData::Number(n)) => Some(n as f32),
+            _ => None,
+        })
+        .collect();
+    let average = numbers.iter().sum::<f32>() / numbers.len() as f32;
+    let weight = |player: &Player| match player.vote {
+        Vote::Revealed(VoteData::Number(n)) if weighted => 1 + (n as f32 - average).abs().round() as u64,
+        _ => 1,
+    };
+    let total: u64 = candidates.iter().map(|p| weight(p)).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut remaining = random % total;
+    for player in candidates {
+        let weight = weight(player);
+        if remaining < weight {
+            return Some(player);
+        }
+        remaining -= weight;
+    }
+    None
+}
+
+/// Quick reaction to the revealed cards, sent as `!pp react <name>` and drawn with `Symbols::reactions`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Reaction {
+    ThumbsUp,
+    ThumbsDown,
+    Surprised,
+    Thinking,
+}
+
+impl Reaction {
+    pub const ALL: [Reaction; 4] = [Reaction::ThumbsUp, Reaction::ThumbsDown, Reaction::Surprised, Reaction::Thinking];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Reaction::ThumbsUp => "thumbsup",
+            Reaction::ThumbsDown => "thumbsdown",
+            Reaction::Surprised => "surprised",
+            Reaction::Thinking => "thinking",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Reaction> {
+        Reaction::ALL.into_iter().find(|r| r.name() == name.trim())
+    }
+}
+
+/// Most common revealed vote, if no other vote was given as often.
+pub fn majority_vote(players: &[Player]) -> Option<&VoteData> {
+    let mut counts: Vec<(&VoteData, usize)> = vec![];
+    for player in players {
+        if let Vote::Revealed(vote) = &player.vote {
+            match counts.iter_mut().find(|(v, _)| *v == vote) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((vote, 1)),
+            }
+        }
+    }
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    match counts.as_slice() {
+        [(_, first), (_, second), ..] if first == second => None,
+        [(vote, _), ..] => Some(vote),
+        [] => None,
+    }
+}
+
+impl Room {
+    pub fn average_votes(&self) -> f32 {
+        let mut sum = 0f32;
+        let mut count = 0f32;
+        for player in &self.players {
+            if let Vote::Revealed(VoteData::Number(n)) = player.vote {
+                sum += n as f32;
+                count += 1f32;
+            }
+        }
+        sum / count
+    }
+
+    /// Returns how many participants have voted and how many are expected to vote.
+    pub fn vote_progress(&self) -> (usize, usize) {
+        let participants = self.players.iter().filter(|p| p.user_type != UserType::Spectator);
+        let (voted, total) = participants.fold((0, 0), |(voted, total), p| {
+            (if p.vote == Vote::Missing { voted } else { voted + 1 }, total + 1)
+        });
+        (voted, total)
+    }
+
+    /// Whether the round is running and every participant has voted.
+    pub fn everyone_voted(&self) -> bool {
+        let (voted, total) = self.vote_progress();
+        self.phase == GamePhase::Playing && total > 0 && voted == total
+    }
+}
+
 fn vote_rank(vote: &Vote) -> i32 {
     match vote {
         Vote::Missing => { 9999 }
@@ -130,4 +431,124 @@ impl Ord for Player {
     fn cmp(&self, other: &Self) -> Ordering {
         self.partial_cmp(other).expect("Unable to compare players")
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_player_by_id_or_name() {
+        let named = |id: Option<&str>, name: &str| Player { id: id.map(str::to_string), ..Player::new(name, Vote::Missing) };
+        assert!(named(Some("1"), "alice").is_same(&named(Some("1"), "bob")));
+        assert!(!named(Some("1"), "alice").is_same(&named(Some("2"), "alice")));
+        assert!(named(None, "alice").is_same(&named(Some("2"), "alice")));
+        assert_eq!(named(None, "alice").key(), "alice");
+        assert_eq!(named(Some("1"), "alice").key(), "1");
+    }
+
+    #[test]
+    fn join_leave_rate_counts_the_last_minute() {
+        let now = Instant::now() + Duration::from_secs(120);
+        let entry = |secs_ago, message: &str| LogEntry {
+            timestamp: now - Duration::from_secs(secs_ago),
+            time: SystemTime::now(),
+            level: LogLevel::Info,
+            message: message.to_string(),
+            source: LogSource::Server,
+            server_index: None,
+        };
+        let log = [
+            entry(90, "alice joined the room."),
+            entry(50, "bob joined the room."),
+            entry(40, "bob changed the name to carol."),
+            entry(10, "carol left the room."),
+        ];
+        assert_eq!(join_leave_rate(&log, now), 2);
+    }
+
+    #[test]
+    fn unique_name_adds_a_free_suffix() {
+        let named = |name: &str| Player::new(name, Vote::Missing);
+        let players = [named("alice"), named("alice"), named("alice2")];
+        assert_eq!(unique_name("alice", &players), "alice3");
+        assert_eq!(unique_name("bob", &players), "bob2");
+    }
+
+    #[test]
+    fn confidence() {
+        assert_eq!(parse_confidence(" 2"), Some(2));
+        assert_eq!(parse_confidence("0"), None);
+        assert_eq!(parse_confidence("4"), None);
+        assert_eq!(format_confidence(1, "●", "○"), "●○○");
+    }
+
+    #[test]
+    fn nearest_card_rounds_up_on_ties() {
+        let deck: Vec<String> = ["1", "2", "3", "5", "8", "?"].iter().map(|c| c.to_string()).collect();
+        assert_eq!(nearest_card(6.4, &deck), Some("5"));
+        assert_eq!(nearest_card(6.5, &deck), Some("8"));
+        assert_eq!(nearest_card(f32::NAN, &deck), None);
+    }
+
+    #[test]
+    fn consensus_ignores_special_votes() {
+        let five = Vote::Revealed(VoteData::Number(5));
+        let coffee = Vote::Revealed(VoteData::Special("☕".to_string()));
+        assert!(is_consensus(&[Player::new("", five.clone()), Player::new("", coffee), Player::new("", five.clone())]));
+        assert!(!is_consensus(&[Player::new("", five.clone()), Player::new("", Vote::Missing)]));
+        assert!(!is_consensus(&[Player::new("", five), Player::new("", Vote::Revealed(VoteData::Number(8)))]));
+    }
+
+    #[test]
+    fn majority_needs_a_single_winner() {
+        let five = Vote::Revealed(VoteData::Number(5));
+        let eight = Vote::Revealed(VoteData::Number(8));
+        let players = vec![Player::new("", five.clone()), Player::new("", eight.clone()), Player::new("", five.clone()), Player::new("", Vote::Missing)];
+        assert_eq!(majority_vote(&players), Some(&VoteData::Number(5)));
+
+        let players = vec![Player::new("", five), Player::new("", eight)];
+        assert_eq!(majority_vote(&players), None);
+    }
+
+    #[test]
+    fn matches_and_suggests_deck_cards() {
+        let deck: Vec<String> = ["1", "2", "3", "5", "8", "13", "?", "Coffee"].iter().map(|c| c.to_string()).collect();
+        assert_eq!(deck_card("coffee", &deck), Some("Coffee"));
+        assert_eq!(deck_card("08", &deck), Some("8"));
+        assert_eq!(deck_card("13.0", &deck), Some("13"));
+        assert_eq!(deck_card("12", &deck), None);
+        assert_eq!(suggest_card("12", &deck), Some("13"));
+        assert_eq!(suggest_card("cofee", &deck), Some("Coffee"));
+        assert_eq!(suggest_card("tea", &deck), None);
+    }
+
+    #[test]
+    fn picks_the_first_speaker_among_voters() {
+        let players = vec![
+            Player::new("alice", Vote::Revealed(VoteData::Number(3))),
+            Player::new("bob", Vote::Revealed(VoteData::Number(3))),
+            Player::new("carol", Vote::Revealed(VoteData::Number(13))),
+            Player::new("dave", Vote::Missing),
+        ];
+        let picked = |weighted, random| pick_first_speaker(&players, weighted, random).map(|p| p.name.as_str());
+
+        assert_eq!((0..3).map(|r| picked(false, r)).collect::<Vec<_>>(), vec![Some("alice"), Some("bob"), Some("carol")]);
+        assert_eq!(picked(false, 3), Some("alice"));
+        // the average is 6.3, carol's 13 weighs 8 against 4 for each 3
+        assert_eq!(picked(true, 7), Some("bob"));
+        assert_eq!(picked(true, 8), Some("carol"));
+        assert_eq!(picked(true, 15), Some("carol"));
+        assert_eq!(pick_first_speaker(&players[3..], false, 0), None);
+    }
+
+    #[test]
+    fn finds_mentions_of_a_name() {
+        assert!(mentions("ping @Alice, please vote", "alice"));
+        assert!(mentions("@alice", "alice"));
+        assert!(!mentions("@alicia", "alice"));
+        assert!(!mentions("@alice_2", "alice"));
+        assert!(!mentions("alice", "alice"));
+        assert!(!mentions("@", " "));
+    }
+}
@@ -1,29 +1,105 @@
-use log::error;
+use std::io;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use log::{error, info};
 #[cfg(target_os = "linux")]
 use notify_rust::{Hint, Urgency};
 use notify_rust::{Notification, Timeout};
 
+use crate::config::{Config, NotificationBackend};
+
+const SUMMARY: &str = "Planning Poker";
+
+/// Notifies the user through the configured backend. `auto` falls back to the bell without desktop notifications.
+pub fn show_notification(config: &Config, body: &str) {
+    match config.notification_backend {
+        NotificationBackend::Auto => {
+            if !show_desktop_notification(body) {
+                info!("Falling back to the terminal bell.");
+                ring_bell();
+            }
+        }
+        NotificationBackend::Desktop => {
+            show_desktop_notification(body);
+        }
+        NotificationBackend::Bell => ring_bell(),
+        NotificationBackend::Command => match &config.notification_command {
+            Some(command) => run_command(command, body),
+            None => error!("Notification backend is command, but no notification_command is configured."),
+        },
+    }
+}
+
 #[cfg(any(target_os = "windows", target_os = "macos"))]
-pub fn show_notification() {
+fn show_desktop_notification(body: &str) -> bool {
     if let Err(e) = Notification::new()
-        .summary("Planning Poker")
-        .body("Your vote is the last one missing.")
+        .summary(SUMMARY)
+        .body(body)
         .timeout(Timeout::Milliseconds(10000))
         .show() {
         error!("Failed to send notification: {}", e);
+        return false;
     }
+    true
 }
 
 #[cfg(target_os = "linux")]
-pub fn show_notification() {
+fn show_desktop_notification(body: &str) -> bool {
     if let Err(e) = Notification::new()
-        .summary("Planning Poker")
-        .body("Your vote is the last one missing.")
+        .summary(SUMMARY)
+        .body(body)
         .timeout(Timeout::Milliseconds(10000))
         .urgency(Urgency::Critical)
         .hint(Hint::SoundName("message-new-instant".to_string()))
         .show() {
         error!("Failed to send notification: {}", e);
+        return false;
     }
+    true
 }
 
+/// Runs the user's command with summary and body appended as arguments, e.g. `notify-send`.
+fn run_command(command: &str, body: &str) {
+    #[cfg(unix)]
+    let mut process = {
+        let mut process = Command::new("sh");
+        // "$@" passes the arguments through without the shell interpreting them
+        process.arg("-c").arg(format!("{} \"$@\"", command)).arg("ppoker");
+        process
+    };
+    // cmd has no way to pass arguments through uninterpreted, so the program is started directly with the words of
+    // the command as its arguments
+    #[cfg(not(unix))]
+    let mut process = {
+        let mut words = command.split_whitespace();
+        let Some(program) = words.next() else {
+            error!("The notification command is empty.");
+            return;
+        };
+        let mut process = Command::new(program);
+        process.args(words);
+        process
+    };
+
+    let child = process.arg(SUMMARY).arg(body)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    match child {
+        // reap the process in the background, so slow commands don't block the ui
+        Ok(mut child) => {
+            thread::spawn(move || child.wait());
+        }
+        Err(e) => error!("Failed to run notification command {}: {}", command, e),
+    }
+}
+
+pub fn ring_bell() {
+    let mut stderr = io::stderr();
+    if let Err(e) = stderr.write_all(b"\x07").and_then(|_| stderr.flush()) {
+        error!("Failed to ring terminal bell: {}", e);
+    }
+}
@@ -0,0 +1,174 @@
+//! Small HTTP server showing the room on a web page, e.g. as browser source for an overlay in OBS.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use log::{error, info, warn};
+
+use crate::app::AppResult;
+use crate::state_file::RoomState;
+
+/// Seconds after which the page reloads itself.
+const REFRESH_SECONDS: u32 = 2;
+
+#[derive(Debug, Default, Clone)]
+struct Pages {
+    html: String,
+    json: String,
+    metrics: String,
+}
+
+/// Serves the last published state as `/` (an auto-refreshing page) and `/state.json` on localhost, and the metrics
+/// as `/metrics`.
+#[derive(Debug)]
+pub struct OverlayServer {
+    pages: Arc<Mutex<Pages>>,
+}
+
+impl OverlayServer {
+    pub fn bind(port: u16) -> AppResult<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        info!("Serving the overlay on http://localhost:{}/.", port);
+
+        let pages = Arc::new(Mutex::new(Pages::default()));
+        {
+            let pages = pages.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    match stream {
+                        Ok(stream) => {
+                            let pages = pages.lock().expect("Overlay pages poisoned").clone();
+                            if let Err(e) = serve(stream, &pages) {
+                                warn!("Failed to answer overlay request: {}", e);
+                            }
+                        }
+                        Err(e) => error!("Failed to accept overlay connection: {}", e),
+                    }
+                }
+            });
+        }
+        Ok(Self { pages })
+    }
+
+    pub fn publish(&self, state: &RoomState) {
+        let mut pages = self.pages.lock().expect("Overlay pages poisoned");
+        pages.html = render_html(state);
+        pages.json = serde_json::to_string(state).expect("Failed to serialize room state");
+    }
+
+    pub fn publish_metrics(&self, metrics: String) {
+        self.pages.lock().expect("Overlay pages poisoned").metrics = metrics;
+    }
+}
+
+fn serve(stream: TcpStream, pages: &Pages) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(1)))?;
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+
+    let (status, content_type, body) = route(path, pages);
+    let mut stream = reader.into_inner();
+    write!(stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\n\
+        Connection: close\r\n\r\n{}",
+        status, content_type, body.len(), body)?;
+    stream.flush()
+}
+
+fn route<'a>(path: &str, pages: &'a Pages) -> (&'static str, &'static str, &'a str) {
+    match path.split('?').next().unwrap_or(path) {
+        "/" => ("200 OK", "text/html; charset=utf-8", &pages.html),
+        "/state.json" => ("200 OK", "application/json", &pages.json),
+        "/metrics" => ("200 OK", "text/plain; version=0.0.4", &pages.metrics),
+        _ => ("404 Not Found", "text/plain", "not found"),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn render_html(state: &RoomState) -> String {
+    let participants: Vec<_> = state.players.iter().filter(|p| !p.spectator).collect();
+    let voted = participants.iter().filter(|p| p.voted).count();
+    let mut summary = format!("Round {} &middot; {} of {} voted", state.round_number, voted, participants.len());
+    if let Some(average) = state.average {
+        summary.push_str(&format!(" &middot; average {:.1}", average));
+    }
+
+    let rows: String = participants.iter().map(|player| {
+        let vote = match (&player.vote, player.voted) {
+            (Some(vote), _) => escape_html(vote),
+            (None, true) => "&#10003;".to_string(),
+            (None, false) => "&hellip;".to_string(),
+        };
+        format!("<tr><td>{}</td><td class=\"vote\">{}</td></tr>", escape_html(&player.name), vote)
+    }).collect();
+
+    format!(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta http-equiv="refresh" content="{refresh}">
+<title>ppoker - {room}</title>
+<style>
+body {{ background: transparent; color: white; font-family: sans-serif; font-size: 24px; text-shadow: 0 0 4px black; }}
+td {{ padding: 0 1em 0 0; }}
+.vote {{ font-weight: bold; }}
+</style>
+</head>
+<body class="{phase}">
+<h1>{room}</h1>
+<p>{summary}</p>
+<table>{rows}</table>
+</body>
+</html>
+"#, refresh = REFRESH_SECONDS, room = escape_html(&state.room), phase = state.phase, summary = summary, rows = rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{GamePhase, Player, Room, Vote, VoteData};
+
+    use super::*;
+
+    fn state() -> RoomState {
+        let room = Room {
+            name: "room".to_string(),
+            deck: vec![],
+            phase: GamePhase::Revealed,
+            players: vec![
+                Player::new("<script>", Vote::Revealed(VoteData::Number(3))),
+                Player::new("bob", Vote::Revealed(VoteData::Number(5))),
+            ],
+        };
+        RoomState::new(&room, 2, &None)
+    }
+
+    #[test]
+    fn renders_escaped_names_and_the_average() {
+        let html = render_html(&state());
+        assert!(html.contains("<td>&lt;script&gt;</td><td class=\"vote\">3</td>"));
+        assert!(html.contains("Round 2 &middot; 2 of 2 voted &middot; average 4.0"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn routes_page_and_json() {
+        let pages = Pages { html: "html".to_string(), json: "json".to_string(), metrics: "metrics".to_string() };
+        assert_eq!(route("/", &pages).2, "html");
+        assert_eq!(route("/state.json?t=1", &pages).2, "json");
+        assert_eq!(route("/metrics", &pages).2, "metrics");
+        assert_eq!(route("/other", &pages).0, "404 Not Found");
+    }
+}
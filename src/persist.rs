@@ -0,0 +1,144 @@
+//! Helpers for files that several running instances may write at the same time.
+
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use log::warn;
+
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// Exclusive lock on a persisted file, released when dropped.
+///
+/// The lock is a `<file>.lock` sibling created with `create_new`, which is atomic on every platform.
+#[derive(Debug)]
+pub struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    pub fn acquire(target: &Path) -> io::Result<Self> {
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut path = target.as_os_str().to_owned();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        warn!("Removing stale lock file {:?}.", path);
+                        // another instance may have removed it already, the next attempt will tell
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if Instant::now() > deadline {
+                        return Err(io::Error::new(ErrorKind::TimedOut, format!("Timed out waiting for lock {:?}", path)));
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("Failed to remove lock file {:?}: {}", self.path, e);
+        }
+    }
+}
+
+fn is_stale(lock: &Path) -> bool {
+    fs::metadata(lock)
+        .and_then(|m| m.modified())
+        .map(|modified| SystemTime::now().duration_since(modified).unwrap_or_default() > STALE_LOCK_AGE)
+        .unwrap_or(false)
+}
+
+/// Replaces the file by writing a temporary file next to it and renaming it, so readers never see partial content.
+fn replace(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(contents)?;
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let _lock = FileLock::acquire(path)?;
+    replace(path, contents)
+}
+
+/// Reads the file, returning `None` if it doesn't exist yet. Atomic writes make a lock unnecessary here.
+pub fn read(path: &Path) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Read-modify-write cycle holding the lock, so concurrent updates from other instances aren't lost.
+pub fn update<F>(path: &Path, f: F) -> io::Result<()>
+where
+    F: FnOnce(Option<String>) -> String,
+{
+    let _lock = FileLock::acquire(path)?;
+    let content = f(read(path)?);
+    replace(path, content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn write_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state").join("file.json");
+        assert_eq!(read(&path).unwrap(), None);
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(read(&path).unwrap(), Some("second".to_string()));
+        assert!(!dir.path().join("state").join("file.json.lock").exists());
+    }
+
+    #[test]
+    fn concurrent_updates_are_not_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = Arc::new(dir.path().join("counter"));
+
+        let handles: Vec<_> = (0..8).map(|_| {
+            let path = path.clone();
+            thread::spawn(move || {
+                for _ in 0..10 {
+                    update(&path, |content| {
+                        let count = content.map_or(0, |c| c.parse::<u32>().unwrap());
+                        (count + 1).to_string()
+                    }).unwrap();
+                }
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(read(&path).unwrap(), Some("80".to_string()));
+    }
+}
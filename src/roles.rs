@@ -0,0 +1,103 @@
+//! Roles of the players, from the `roles` of the config file or a tag at the end of the name like `Alice (QA)`, so
+//! the revealed votes can be averaged per role.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::{Player, UserType, Vote, VoteData};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Dev,
+    Qa,
+    Other,
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Role::Dev => "Dev",
+            Role::Qa => "QA",
+            Role::Other => "Other",
+        })
+    }
+}
+
+/// Role tagged at the end of a name in parentheses or brackets, like `Bob [dev]`.
+fn role_tag(name: &str) -> Option<Role> {
+    let name = name.trim_end();
+    let tag = name.strip_suffix(')').and_then(|rest| rest.rsplit_once('('))
+        .or_else(|| name.strip_suffix(']').and_then(|rest| rest.rsplit_once('[')))?
+        .1.trim().to_lowercase();
+    match tag.as_str() {
+        "dev" | "developer" => Some(Role::Dev),
+        "qa" | "test" | "tester" => Some(Role::Qa),
+        _ => None,
+    }
+}
+
+/// The configured role of the name, otherwise the tagged one.
+pub fn player_role(name: &str, roles: &HashMap<String, Role>) -> Role {
+    roles.get(name).copied()
+        .or_else(|| role_tag(name))
+        .unwrap_or(Role::Other)
+}
+
+/// Average of the numeric votes per role, only if the players have different roles.
+pub fn role_averages(players: &[Player], roles: &HashMap<String, Role>) -> Vec<(Role, f32)> {
+    let mut votes: HashMap<Role, Vec<f32>> = HashMap::new();
+    for player in players.iter().filter(|p| p.user_type != UserType::Spectator) {
+        if let Vote::Revealed(VoteData::Number(n)) = player.vote {
+            votes.entry(player_role(&player.name, roles)).or_default().push(n as f32);
+        }
+    }
+    if votes.len() < 2 {
+        return vec![];
+    }
+    let mut averages: Vec<(Role, f32)> = votes.into_iter()
+        .map(|(role, votes)| (role, votes.iter().sum::<f32>() / votes.len() as f32))
+        .collect();
+    averages.sort_by_key(|(role, _)| *role);
+    averages
+}
+
+/// Averages like `Dev 5.3 QA 8.0`.
+pub fn format_role_averages(averages: &[(Role, f32)]) -> String {
+    averages.iter()
+        .map(|(role, average)| format!("{} {:.1}", role, average))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(name: &str, vote: u8) -> Player {
+        Player::new(name, Vote::Revealed(VoteData::Number(vote)))
+    }
+
+    #[test]
+    fn roles_come_from_the_config_or_the_name() {
+        let roles = HashMap::from([("Carol".to_string(), Role::Qa), ("Dan (dev)".to_string(), Role::Other)]);
+        assert_eq!(player_role("Alice (QA)", &roles), Role::Qa);
+        assert_eq!(player_role("Bob [Developer]", &roles), Role::Dev);
+        assert_eq!(player_role("Carol", &roles), Role::Qa);
+        assert_eq!(player_role("Dan (dev)", &roles), Role::Other);
+        assert_eq!(player_role("Eve (team a)", &roles), Role::Other);
+    }
+
+    #[test]
+    fn averages_per_role_once_roles_differ() {
+        let roles = HashMap::new();
+        let players = vec![player("a (dev)", 3), player("b (dev)", 5), player("c (qa)", 8), player("d", 13)];
+        let averages = role_averages(&players, &roles);
+        assert_eq!(averages, vec![(Role::Dev, 4.0), (Role::Qa, 8.0), (Role::Other, 13.0)]);
+        assert_eq!(format_role_averages(&averages), "Dev 4.0 QA 8.0 Other 13.0");
+
+        assert_eq!(role_averages(&players[..2], &roles), vec![]);
+    }
+}
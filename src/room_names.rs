@@ -0,0 +1,54 @@
+//! The name last used in each room, preferred over the configured name when joining that room again.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use log::warn;
+
+use crate::config::get_datadir;
+use crate::persist;
+
+fn names_file() -> PathBuf {
+    get_datadir().join("names.json")
+}
+
+fn parse_names(content: Option<String>) -> HashMap<String, String> {
+    content.and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default()
+}
+
+/// The name you last renamed yourself to in the room, if any.
+pub fn remembered_name(room: &str) -> Option<String> {
+    let path = names_file();
+    match persist::read(&path) {
+        Ok(content) => parse_names(content).remove(room),
+        Err(e) => {
+            warn!("Failed to read the names of the rooms {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Stores the name for the next time you join the room.
+pub fn remember_name(room: &str, name: &str) {
+    let path = names_file();
+    let result = persist::update(&path, |content| {
+        let mut names = parse_names(content);
+        names.insert(room.to_string(), name.to_string());
+        serde_json::to_string(&names).expect("Failed to serialize the names of the rooms")
+    });
+    if let Err(e) = result {
+        warn!("Failed to write the names of the rooms {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn broken_files_are_ignored() {
+        assert_eq!(parse_names(Some("{\"planning\":\"ali\"}".to_string())).get("planning").map(String::as_str), Some("ali"));
+        assert!(parse_names(Some("not json".to_string())).is_empty());
+        assert!(parse_names(None).is_empty());
+    }
+}
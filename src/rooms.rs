@@ -0,0 +1,103 @@
+//! Lists the active rooms of a server, for servers that offer a listing next to the websocket endpoint of the rooms.
+
+use std::time::Duration;
+
+use log::debug;
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use serde_json::Value;
+
+use crate::app::AppResult;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoomInfo {
+    pub name: String,
+    /// `None` if the server doesn't tell.
+    pub participants: Option<usize>,
+}
+
+/// The http url of the listing, `<server>/rooms` with the websocket scheme replaced.
+fn listing_url(server: &str) -> Option<String> {
+    let base = if let Some(rest) = server.strip_prefix("wss://") {
+        format!("https://{}", rest)
+    } else if let Some(rest) = server.strip_prefix("ws://") {
+        format!("http://{}", rest)
+    } else {
+        return None;
+    };
+    Some(format!("{}/rooms", base.trim_end_matches('/')))
+}
+
+/// Parses a JSON array of rooms, `None` if the response isn't a listing.
+fn parse_rooms(text: &str) -> Option<Vec<RoomInfo>> {
+    let Value::Array(items) = serde_json::from_str(text).ok()? else {
+        return None;
+    };
+    let mut rooms: Vec<RoomInfo> = items.iter().map(|item| {
+        let name = item.get("roomId").or_else(|| item.get("name"))?.as_str()?.to_string();
+        let participants = ["userCount", "participants"].iter()
+            .find_map(|key| item.get(key)?.as_u64())
+            .map(|count| count as usize)
+            .or_else(|| item.get("users")?.as_array().map(Vec::len));
+        Some(RoomInfo { name, participants })
+    }).collect::<Option<_>>()?;
+    rooms.sort_by(|a, b| b.participants.cmp(&a.participants).then_with(|| a.name.cmp(&b.name)));
+    Some(rooms)
+}
+
+/// Fetches the rooms of the server, `Ok(None)` if it has no listing.
+pub fn fetch_rooms(server: &str) -> AppResult<Option<Vec<RoomInfo>>> {
+    let Some(url) = listing_url(server) else {
+        return Ok(None);
+    };
+    debug!("Fetching rooms from {}", url);
+    let response = Client::builder().timeout(TIMEOUT).build()?.get(&url).send()?;
+    if matches!(response.status(), StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED) {
+        return Ok(None);
+    }
+    let text = response.error_for_status()?.text()?;
+    Ok(parse_rooms(&text))
+}
+
+/// `ppoker rooms`, prints the rooms with their participants.
+pub fn print(server: &str) -> AppResult<()> {
+    match fetch_rooms(server)? {
+        Some(rooms) if rooms.is_empty() => println!("No active rooms on {}.", server),
+        Some(rooms) => {
+            for room in rooms {
+                match room.participants {
+                    Some(count) => println!("{}\t{}", room.name, count),
+                    None => println!("{}", room.name),
+                }
+            }
+        }
+        None => println!("The server at {} doesn't list its rooms.", server),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_next_to_the_websocket() {
+        assert_eq!(listing_url("wss://poker.example.com/api/").as_deref(), Some("https://poker.example.com/api/rooms"));
+        assert_eq!(listing_url("ws://localhost:8080").as_deref(), Some("http://localhost:8080/rooms"));
+        assert_eq!(listing_url("localhost:8080"), None);
+    }
+
+    #[test]
+    fn parses_rooms_with_or_without_counts() {
+        let rooms = parse_rooms(r#"[{"roomId":"b","users":[{},{}]},{"roomId":"a","userCount":2},{"name":"c"}]"#).unwrap();
+        assert_eq!(rooms, vec![
+            RoomInfo { name: "a".to_string(), participants: Some(2) },
+            RoomInfo { name: "b".to_string(), participants: Some(2) },
+            RoomInfo { name: "c".to_string(), participants: None },
+        ]);
+        assert_eq!(parse_rooms(r#"{"roomId":"a"}"#), None);
+        assert_eq!(parse_rooms("<html>"), None);
+    }
+}
@@ -0,0 +1,302 @@
+//! `ppoker serve`, a minimal server speaking the protocol of the ppoker server, for estimating over the local network
+//! when the internet connection is bad. It keeps the rooms in memory and forgets a room once everyone left.
+
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{Ipv4Addr, TcpListener, TcpStream, UdpSocket};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use log::{debug, error, info, warn};
+use tungstenite::handshake::server::{Request as Handshake, Response};
+use tungstenite::{Message, WebSocket};
+
+use crate::app::AppResult;
+use crate::web::dto::{GamePhase, LogEntry, LogLevel, OwnedUserRequest as Request, Room, User, UserType, DEFAULT_DECK};
+
+/// Delay between two polls of a connection for requests and room changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+static NEXT_USER_ID: AtomicU64 = AtomicU64::new(1);
+
+type Rooms = Arc<Mutex<HashMap<String, ServerRoom>>>;
+
+#[derive(Debug)]
+struct ServerUser {
+    id: u64,
+    name: String,
+    user_type: UserType,
+    card: Option<String>,
+}
+
+#[derive(Debug)]
+struct ServerRoom {
+    deck: Vec<String>,
+    phase: GamePhase,
+    users: Vec<ServerUser>,
+    log: Vec<LogEntry>,
+    /// Counts the changes, connections send the room again when it moved on.
+    version: u64,
+}
+
+impl ServerRoom {
+    fn new(deck: Vec<String>) -> Self {
+        Self { deck, phase: GamePhase::Playing, users: vec![], log: vec![], version: 0 }
+    }
+
+    fn info(&mut self, message: String) {
+        self.log.push(LogEntry { level: LogLevel::Info, message });
+    }
+
+    fn apply(&mut self, user_id: u64, request: Request) {
+        let Some(user) = self.users.iter_mut().find(|u| u.id == user_id) else {
+            return;
+        };
+        let name = user.name.clone();
+        match request {
+            Request::PlayCard { card_value } => user.card = card_value.filter(|_| user.user_type == UserType::Participant),
+            Request::ChangeName { name: new_name } => {
+                user.name = new_name.clone();
+                self.info(format!("{} is now known as {}.", name, new_name));
+            }
+            Request::ChatMessage { message } => {
+                self.log.push(LogEntry { level: LogLevel::Chat, message: format!("{}: {}", name, message) });
+            }
+            Request::ChangeUserType { user_type } => {
+                user.user_type = user_type;
+                if user_type == UserType::Spectator {
+                    user.card = None;
+                }
+            }
+            Request::RevealCards => {
+                self.phase = GamePhase::CardsRevealed;
+                self.info(format!("{} revealed the cards.", name));
+            }
+            Request::StartNewRound => {
+                self.phase = GamePhase::Playing;
+                self.users.iter_mut().for_each(|u| u.card = None);
+                self.info(format!("{} started a new round.", name));
+            }
+        }
+        self.version += 1;
+    }
+
+    /// The room as the given user sees it: the cards of the others stay hidden until the reveal.
+    fn view(&self, room_id: &str, user_id: u64) -> Room {
+        let revealed = self.phase == GamePhase::CardsRevealed;
+        let users = self.users.iter().map(|user| {
+            let you = user.id == user_id;
+            let card_value = match (&user.card, user.user_type) {
+                (_, UserType::Spectator) => String::new(),
+                (Some(card), _) if revealed || you => card.clone(),
+                (Some(_), _) => "✅".to_string(),
+                (None, _) if you => String::new(),
+                (None, _) => "❌".to_string(),
+            };
+            User {
+                user_id: Some(user.id.to_string()),
+                username: user.name.clone(),
+                user_type: user.user_type,
+                your_user: you,
+                card_value,
+            }
+        }).collect();
+        let numbers: Vec<f32> = self.users.iter()
+            .filter_map(|user| user.card.as_ref()?.parse().ok())
+            .collect();
+        let average = if revealed && !numbers.is_empty() {
+            format!("{:.1}", numbers.iter().sum::<f32>() / numbers.len() as f32)
+        } else {
+            String::new()
+        };
+        Room {
+            room_id: room_id.to_string(),
+            deck: self.deck.clone(),
+            game_phase: self.phase,
+            users,
+            average,
+            log: self.log.iter().map(|entry| LogEntry { level: entry.level, message: entry.message.clone() }).collect(),
+        }
+    }
+}
+
+/// Room, name and user type from a request path like `/rooms/<room>?user=<name>&userType=PARTICIPANT`.
+fn parse_target(target: &str) -> Option<(String, String, UserType)> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let room = path.trim_start_matches('/').strip_prefix("rooms/")?;
+    let room = urlencoding::decode(room).ok()?.into_owned();
+    let mut name = None;
+    let mut user_type = UserType::Participant;
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "user" => name = Some(urlencoding::decode(value).ok()?.into_owned()),
+            "userType" if value == "SPECTATOR" => user_type = UserType::Spectator,
+            _ => {}
+        }
+    }
+    Some((room, name.filter(|n| !n.is_empty())?, user_type)).filter(|(room, _, _)| !room.is_empty())
+}
+
+/// Address of this machine in the local network, the one others connect to.
+fn lan_address() -> Option<String> {
+    // connecting a udp socket sends nothing, it only picks the interface of the default route
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9)).ok()?;
+    Some(socket.local_addr().ok()?.ip().to_string())
+}
+
+/// Starts the server on all interfaces in the background and returns the url others join with.
+pub fn start(port: u16, deck: Option<Vec<String>>) -> AppResult<String> {
+    let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))?;
+    let url = format!("ws://{}:{}", lan_address().unwrap_or_else(|| "localhost".to_string()), port);
+    info!("Serving rooms on {}, others join with --server {}.", url, url);
+
+    let deck = deck.unwrap_or_else(|| DEFAULT_DECK.iter().map(|c| c.to_string()).collect());
+    let rooms: Rooms = Arc::default();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let rooms = rooms.clone();
+                    let deck = deck.clone();
+                    thread::spawn(move || {
+                        if let Err(e) = handle(stream, rooms, deck) {
+                            warn!("Connection to the local server failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => error!("Failed to accept connection: {}", e),
+            }
+        }
+    });
+    Ok(url)
+}
+
+fn handle(stream: TcpStream, rooms: Rooms, deck: Vec<String>) -> AppResult<()> {
+    let mut target = String::new();
+    // the error response type is given by tungstenite
+    #[allow(clippy::result_large_err)]
+    let mut socket = tungstenite::accept_hdr(stream, |request: &Handshake, response: Response| {
+        target = request.uri().to_string();
+        Ok(response)
+    }).map_err(|e| format!("Websocket handshake failed: {}", e))?;
+    let Some((room_id, name, user_type)) = parse_target(&target) else {
+        socket.send(Message::Text(r#"{"error":"Expected /rooms/<room>?user=<name>"}"#.to_string()))?;
+        socket.close(None)?;
+        return Ok(());
+    };
+    socket.get_mut().set_nonblocking(true)?;
+
+    let user_id = NEXT_USER_ID.fetch_add(1, Ordering::Relaxed);
+    {
+        let mut rooms = rooms.lock().expect("Rooms poisoned");
+        let room = rooms.entry(room_id.clone()).or_insert_with(|| ServerRoom::new(deck));
+        room.users.push(ServerUser { id: user_id, name: name.clone(), user_type, card: None });
+        room.info(format!("{} joined the room.", name));
+        room.version += 1;
+    }
+    debug!("{} joined room {} on the local server.", name, room_id);
+
+    let result = run(&mut socket, &rooms, &room_id, user_id);
+
+    let mut rooms = rooms.lock().expect("Rooms poisoned");
+    if let Some(room) = rooms.get_mut(&room_id) {
+        if let Some(position) = room.users.iter().position(|u| u.id == user_id) {
+            let user = room.users.remove(position);
+            room.info(format!("{} left the room.", user.name));
+            room.version += 1;
+        }
+        if room.users.is_empty() {
+            rooms.remove(&room_id);
+        }
+    }
+    result
+}
+
+/// Applies the requests of the connection and sends the room whenever it changed, until the client leaves.
+fn run(socket: &mut WebSocket<TcpStream>, rooms: &Rooms, room_id: &str, user_id: u64) -> AppResult<()> {
+    let mut sent_version = None;
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => match serde_json::from_str::<Request>(&text) {
+                Ok(request) => {
+                    if let Some(room) = rooms.lock().expect("Rooms poisoned").get_mut(room_id) {
+                        room.apply(user_id, request);
+                    }
+                }
+                Err(e) => debug!("Ignoring invalid request ({}): {}", e, text),
+            },
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => {
+                let view = rooms.lock().expect("Rooms poisoned").get(room_id)
+                    .filter(|room| Some(room.version) != sent_version)
+                    .map(|room| (room.version, room.view(room_id, user_id)));
+                if let Some((version, view)) = view {
+                    match socket.send(Message::Text(serde_json::to_string(&view)?)) {
+                        Err(tungstenite::Error::Io(e)) if e.kind() == ErrorKind::WouldBlock => {}
+                        result => result?,
+                    }
+                    sent_version = Some(version);
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::web::dto::UserRequest;
+
+    use super::*;
+
+    #[test]
+    fn parses_the_room_target() {
+        assert_eq!(
+            parse_target("//rooms/sprint%2042?user=Zo%C3%AB&userType=SPECTATOR"),
+            Some(("sprint 42".to_string(), "Zoë".to_string(), UserType::Spectator)),
+        );
+        assert_eq!(
+            parse_target("/rooms/a?user=b&userType=PARTICIPANT"),
+            Some(("a".to_string(), "b".to_string(), UserType::Participant)),
+        );
+        assert_eq!(parse_target("/rooms/a"), None);
+        assert_eq!(parse_target("/other?user=b"), None);
+    }
+
+    #[test]
+    fn understands_the_requests_of_the_client() {
+        let parse = |request: UserRequest| serde_json::from_str::<Request>(&serde_json::to_string(&request).unwrap()).unwrap();
+        assert_eq!(parse(UserRequest::PlayCard { card_value: Some("13") }), Request::PlayCard { card_value: Some("13".to_string()) });
+        assert_eq!(parse(UserRequest::ChatMessage { message: "say \"hi\"" }), Request::ChatMessage { message: "say \"hi\"".to_string() });
+        assert_eq!(parse(UserRequest::ChangeUserType { user_type: UserType::Spectator }), Request::ChangeUserType { user_type: UserType::Spectator });
+        assert_eq!(parse(UserRequest::StartNewRound), Request::StartNewRound);
+    }
+
+    #[test]
+    fn hides_the_cards_of_others_until_the_reveal() {
+        let mut room = ServerRoom::new(vec!["1".to_string(), "3".to_string()]);
+        room.users.push(ServerUser { id: 1, name: "a".to_string(), user_type: UserType::Participant, card: None });
+        room.users.push(ServerUser { id: 2, name: "b".to_string(), user_type: UserType::Participant, card: None });
+        room.apply(1, Request::PlayCard { card_value: Some("3".to_string()) });
+
+        let cards = |room: &ServerRoom, user_id| room.view("r", user_id).users.into_iter().map(|u| u.card_value).collect::<Vec<_>>();
+        assert_eq!(cards(&room, 1), vec!["3", "❌"]);
+        assert_eq!(cards(&room, 2), vec!["✅", ""]);
+
+        room.apply(2, Request::PlayCard { card_value: Some("1".to_string()) });
+        room.apply(2, Request::RevealCards);
+        assert_eq!(cards(&room, 2), vec!["3", "1"]);
+        assert_eq!(room.view("r", 1).average, "2.0");
+        assert_eq!(room.log.last().unwrap().message, "b revealed the cards.");
+
+        room.apply(1, Request::StartNewRound);
+        assert_eq!(room.phase, GamePhase::Playing);
+        assert_eq!(cards(&room, 1), vec!["", "❌"]);
+    }
+}
@@ -0,0 +1,124 @@
+//! JSON file with the state of the room for external tools like status bar scripts or stream overlays.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::warn;
+use serde::Serialize;
+
+use crate::config::get_statedir;
+use crate::models::{GamePhase, Player, Room, UserType, Vote, VoteData};
+use crate::persist;
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct RoomState {
+    pub room: String,
+    /// `playing` or `revealed`.
+    pub phase: &'static str,
+    pub round_number: u32,
+    pub my_vote: Option<String>,
+    pub players: Vec<PlayerState>,
+    /// Average of the numeric votes, only after the reveal.
+    pub average: Option<f32>,
+    /// Seconds since the unix epoch.
+    pub updated: u64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct PlayerState {
+    pub name: String,
+    pub spectator: bool,
+    pub voted: bool,
+    /// Only known after the reveal.
+    pub vote: Option<String>,
+    pub you: bool,
+}
+
+impl From<&Player> for PlayerState {
+    fn from(player: &Player) -> Self {
+        Self {
+            name: player.name.clone(),
+            spectator: player.user_type == UserType::Spectator,
+            voted: player.vote != Vote::Missing,
+            vote: match &player.vote {
+                Vote::Revealed(vote) => Some(vote.to_string()),
+                _ => None,
+            },
+            you: player.is_you,
+        }
+    }
+}
+
+impl RoomState {
+    pub fn new(room: &Room, round_number: u32, my_vote: &Option<VoteData>) -> Self {
+        Self {
+            room: room.name.clone(),
+            phase: match room.phase {
+                GamePhase::Playing => "playing",
+                GamePhase::Revealed => "revealed",
+                GamePhase::Unknown => "unknown",
+            },
+            round_number,
+            my_vote: my_vote.as_ref().map(|vote| vote.to_string()),
+            players: room.players.iter().map(PlayerState::from).collect(),
+            average: Some(room.average_votes()).filter(|a| room.phase == GamePhase::Revealed && a.is_finite()),
+            updated: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        }
+    }
+}
+
+pub fn state_file(room: &str) -> PathBuf {
+    get_statedir().join(format!("room-{}.json", urlencoding::encode(room)))
+}
+
+pub fn write(state: &RoomState) {
+    let path = state_file(&state.room);
+    let content = serde_json::to_string_pretty(state).expect("Failed to serialize room state");
+    if let Err(e) = persist::write_atomic(&path, content.as_bytes()) {
+        warn!("Failed to write state file {:?}: {}", path, e);
+    }
+}
+
+pub fn remove(room: &str) {
+    let path = state_file(room);
+    if let Err(e) = fs::remove_file(&path) {
+        warn!("Failed to remove state file {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serializes_players_and_votes() {
+        let player = |name: &str, vote, is_you| Player { is_you, ..Player::new(name, vote) };
+        let room = Room {
+            name: "room".to_string(),
+            deck: vec![],
+            phase: GamePhase::Revealed,
+            players: vec![
+                player("me", Vote::Revealed(VoteData::Number(5)), true),
+                player("other", Vote::Missing, false),
+            ],
+        };
+        let mut state = serde_json::to_value(RoomState::new(&room, 3, &Some(VoteData::Number(5)))).unwrap();
+        state["updated"] = json!(0);
+
+        assert_eq!(state, json!({
+            "room": "room",
+            "phase": "revealed",
+            "round_number": 3,
+            "my_vote": "5",
+            "players": [
+                {"name": "me", "spectator": false, "voted": true, "vote": "5", "you": true},
+                {"name": "other", "spectator": false, "voted": false, "vote": null, "you": false},
+            ],
+            "average": 5.0,
+            "updated": 0,
+        }));
+    }
+}
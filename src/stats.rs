@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::app::HistoryEntry;
+use crate::models::{nearest_card, Player, Vote, VoteData};
+
+/// Votes of a single player across all revealed rounds of the session.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct PlayerStats {
+    /// `Player::key` of the player, so stats survive renames.
+    pub key: String,
+    /// Most recent name of the player.
+    pub name: String,
+    /// Revealed rounds the player took part in.
+    pub rounds: u32,
+    pub numeric_votes: u32,
+    sum: u32,
+    /// Rounds where the player gave the highest vote while others voted lower.
+    pub highest: u32,
+    /// Rounds where the player gave the lowest vote while others voted higher.
+    pub lowest: u32,
+}
+
+impl PlayerStats {
+    pub fn average(&self) -> Option<f32> {
+        if self.numeric_votes == 0 {
+            None
+        } else {
+            Some(self.sum as f32 / self.numeric_votes as f32)
+        }
+    }
+}
+
+/// Adds the votes of a revealed round to the stats of each player.
+pub fn record_round(stats: &mut HashMap<String, PlayerStats>, players: &[Player]) {
+    let numbers: Vec<u8> = players.iter().filter_map(|p| numeric_vote(&p.vote)).collect();
+    let max = numbers.iter().max().copied();
+    let min = numbers.iter().min().copied();
+    let spread = max != min;
+
+    for player in players {
+        if !matches!(player.vote, Vote::Revealed(_)) {
+            continue;
+        }
+        let entry = stats.entry(player.key().to_string()).or_default();
+        entry.key = player.key().to_string();
+        entry.name = player.name.clone();
+        entry.rounds += 1;
+        if let Some(n) = numeric_vote(&player.vote) {
+            entry.numeric_votes += 1;
+            entry.sum += n as u32;
+            if spread && Some(n) == max {
+                entry.highest += 1;
+            }
+            if spread && Some(n) == min {
+                entry.lowest += 1;
+            }
+        }
+    }
+}
+
+/// Figures of the whole session for the summary page.
+pub struct SessionReport<'a> {
+    pub rounds: usize,
+    pub consensus: usize,
+    pub total: Duration,
+    /// Sum of the agreed estimates, the cards closest to the averages.
+    pub committed: f32,
+    /// Rounds with the longest discussions, the longest first.
+    pub slowest: Vec<&'a HistoryEntry>,
+}
+
+impl SessionReport<'_> {
+    /// Percentage of the rounds that ended in consensus.
+    pub fn consensus_rate(&self) -> Option<usize> {
+        (self.rounds > 0).then(|| self.consensus * 100 / self.rounds)
+    }
+}
+
+pub fn session_report(history: &[HistoryEntry], slowest: usize) -> SessionReport<'_> {
+    let mut by_length: Vec<&HistoryEntry> = history.iter().collect();
+    by_length.sort_by_key(|entry| std::cmp::Reverse(entry.length));
+    by_length.truncate(slowest);
+    SessionReport {
+        rounds: history.len(),
+        consensus: history.iter().filter(|entry| entry.consensus).count(),
+        total: history.iter().map(|entry| entry.length).sum(),
+        committed: committed_points(history),
+        slowest: by_length,
+    }
+}
+
+/// Sum of the agreed estimates of the rounds, the numeric card closest to each average.
+pub fn committed_points(history: &[HistoryEntry]) -> f32 {
+    history.iter()
+        .filter_map(|entry| nearest_card(entry.average, &entry.deck))
+        .filter_map(|card| card.parse::<f32>().ok())
+        .sum()
+}
+
+fn numeric_vote(vote: &Vote) -> Option<u8> {
+    match vote {
+        Vote::Revealed(VoteData::Number(n)) => Some(*n),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_highest_and_lowest() {
+        let mut stats = HashMap::new();
+        record_round(&mut stats, &[
+            Player::new("alice", Vote::Revealed(VoteData::Number(8))),
+            Player::new("bob", Vote::Revealed(VoteData::Number(3))),
+            Player::new("carol", Vote::Revealed(VoteData::Special("?".to_string()))),
+            Player::new("dave", Vote::Missing),
+        ]);
+        record_round(&mut stats, &[
+            Player::new("alice", Vote::Revealed(VoteData::Number(5))),
+            Player::new("bob", Vote::Revealed(VoteData::Number(5))),
+        ]);
+
+        let alice = &stats["alice"];
+        assert_eq!((alice.rounds, alice.highest, alice.lowest), (2, 1, 0));
+        assert_eq!(alice.average(), Some(6.5));
+        assert_eq!(stats["bob"].lowest, 1);
+        assert_eq!(stats["carol"].average(), None);
+        assert!(!stats.contains_key("dave"));
+    }
+
+    #[test]
+    fn follows_renamed_players() {
+        let mut stats = HashMap::new();
+        let mut alice = Player::new("alice", Vote::Revealed(VoteData::Number(3)));
+        alice.id = Some("7".to_string());
+        record_round(&mut stats, &[alice.clone()]);
+        alice.name = "alicia".to_string();
+        record_round(&mut stats, &[alice]);
+
+        assert_eq!(stats.len(), 1);
+        assert_eq!((stats["7"].name.as_str(), stats["7"].rounds), ("alicia", 2));
+    }
+
+    #[test]
+    fn reports_consensus_and_slowest_rounds() {
+        let round = |round_number, seconds, consensus| HistoryEntry {
+            round_number,
+            length: Duration::from_secs(seconds),
+            consensus,
+            ..HistoryEntry::default()
+        };
+        let history = vec![round(1, 60, true), round(2, 300, false), round(3, 120, false)];
+
+        let report = session_report(&history, 2);
+        assert_eq!((report.rounds, report.consensus, report.consensus_rate()), (3, 1, Some(33)));
+        assert_eq!(report.total, Duration::from_secs(480));
+        let slowest: Vec<u32> = report.slowest.iter().map(|entry| entry.round_number).collect();
+        assert_eq!(slowest, vec![2, 3]);
+        assert_eq!(session_report(&[], 3).consensus_rate(), None);
+    }
+
+    #[test]
+    fn totals_the_cards_closest_to_the_averages() {
+        let deck: Vec<String> = ["1", "2", "3", "5", "8", "?"].iter().map(|c| c.to_string()).collect();
+        let round = |average| HistoryEntry { average, deck: deck.clone(), ..HistoryEntry::default() };
+
+        assert_eq!(committed_points(&[round(4.5), round(2.0), round(f32::NAN)]), 7.0);
+    }
+}
@@ -0,0 +1,152 @@
+//! Short status of a room for prompts and status bars, see `ppoker status`.
+//!
+//! A running instance keeps a status file per room up to date, so polling status bars don't have to join the room
+//! every few seconds. Without a recent status file the room is joined briefly instead.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppResult;
+use crate::config::{get_statedir, Config, StatusFormat};
+use crate::headless::HeadlessClient;
+use crate::models::{GamePhase, Room, UserType, Vote};
+use crate::persist;
+
+/// How often a running instance rewrites an unchanged status, so readers can tell it's still running.
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+/// Status files older than this are left over from an instance that didn't exit cleanly.
+const MAX_AGE: Duration = Duration::from_secs(90);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Status {
+    pub room: String,
+    /// `playing` or `revealed`.
+    pub phase: String,
+    pub missing_votes: usize,
+    pub own_vote_missing: bool,
+    /// Seconds since the unix epoch.
+    pub updated: u64,
+}
+
+impl Status {
+    pub fn new(room: &Room, own_vote_missing: bool) -> Self {
+        let (voted, total) = room.vote_progress();
+        Self {
+            room: room.name.clone(),
+            phase: match room.phase {
+                GamePhase::Playing => "playing",
+                GamePhase::Revealed => "revealed",
+                GamePhase::Unknown => "unknown",
+            }.to_string(),
+            missing_votes: if room.phase == GamePhase::Playing { total - voted } else { 0 },
+            own_vote_missing,
+            updated: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        }
+    }
+
+    /// Whether anything but the time of the update differs.
+    pub fn same_as(&self, other: &Status) -> bool {
+        Status { updated: other.updated, ..self.clone() } == *other
+    }
+
+    fn is_fresh(&self) -> bool {
+        let age = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs().saturating_sub(self.updated);
+        age <= MAX_AGE.as_secs()
+    }
+
+    pub fn format(&self, format: StatusFormat) -> String {
+        match format {
+            StatusFormat::Json => serde_json::to_string(self).expect("Failed to serialize status"),
+            StatusFormat::Plain => {
+                let mut line = self.phase.clone();
+                if self.missing_votes > 0 {
+                    line.push_str(&format!(", {} missing", self.missing_votes));
+                }
+                if self.own_vote_missing {
+                    line.push_str(", waiting on you");
+                }
+                line
+            }
+        }
+    }
+}
+
+pub fn status_file(room: &str) -> PathBuf {
+    get_statedir().join(format!("status-{}.json", urlencoding::encode(room)))
+}
+
+pub fn write(path: &Path, status: &Status) {
+    let content = serde_json::to_string(status).expect("Failed to serialize status");
+    if let Err(e) = persist::write_atomic(path, content.as_bytes()) {
+        warn!("Failed to write status file {:?}: {}", path, e);
+    }
+}
+
+pub fn remove(path: &Path) {
+    if let Err(e) = fs::remove_file(path) {
+        warn!("Failed to remove status file {:?}: {}", path, e);
+    }
+}
+
+fn read_fresh(path: &Path) -> Option<Status> {
+    let content = persist::read(path).ok()??;
+    serde_json::from_str::<Status>(&content).ok().filter(Status::is_fresh)
+}
+
+/// Joins the room as spectator just long enough to get its state. My vote is the one of the player with my name.
+fn fetch(config: &Config) -> AppResult<Status> {
+    let client = HeadlessClient::connect(config)?;
+    let room = &client.room;
+    let own_vote_missing = room.phase == GamePhase::Playing && room.players.iter()
+        .any(|p| p.name == config.name && p.user_type == UserType::Player && p.vote == Vote::Missing);
+    Ok(Status::new(room, own_vote_missing))
+}
+
+pub fn print(config: &Config, format: StatusFormat) -> AppResult<()> {
+    let status = match read_fresh(&status_file(&config.room)) {
+        Some(status) => status,
+        None => {
+            info!("No status file of a running instance for room {}, joining it.", config.room);
+            fetch(config)?
+        }
+    };
+    println!("{}", status.format(format));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(missing_votes: usize, own_vote_missing: bool) -> Status {
+        Status { room: "room".to_string(), phase: "playing".to_string(), missing_votes, own_vote_missing, updated: 0 }
+    }
+
+    #[test]
+    fn formats_plain_and_json() {
+        assert_eq!(status(2, true).format(StatusFormat::Plain), "playing, 2 missing, waiting on you");
+        assert_eq!(status(0, false).format(StatusFormat::Plain), "playing");
+        assert_eq!(
+            status(1, false).format(StatusFormat::Json),
+            r#"{"room":"room","phase":"playing","missing_votes":1,"own_vote_missing":false,"updated":0}"#
+        );
+    }
+
+    #[test]
+    fn old_status_is_not_fresh() {
+        assert!(!status(1, false).is_fresh());
+        assert!(Status::new(&Room { name: "r".to_string(), deck: vec![], phase: GamePhase::Playing, players: vec![] }, false).is_fresh());
+    }
+
+    #[test]
+    fn ignores_the_update_time_when_comparing() {
+        let mut other = status(2, true);
+        other.updated = 100;
+        assert!(status(2, true).same_as(&other));
+        assert!(!status(1, true).same_as(&other));
+    }
+}
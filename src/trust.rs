@@ -0,0 +1,162 @@
+//! One-time confirmation of the config fetched from `config_url` and of the commands the config would run, the hooks and
+//! the notification command, so a config taken from a shared location or shared dotfiles can't change or run anything
+//! the user hasn't seen. Decisions are remembered per fetched config and per command, a changed one asks again.
+
+use std::collections::HashMap;
+use std::io;
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+
+use log::{error, info, warn};
+
+use crate::config::{get_datadir, Config};
+use crate::persist;
+
+fn trust_file() -> PathBuf {
+    get_datadir().join("trust.json")
+}
+
+fn parse_decisions(content: Option<String>) -> HashMap<String, bool> {
+    content.and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default()
+}
+
+fn read_decisions() -> HashMap<String, bool> {
+    let path = trust_file();
+    match persist::read(&path) {
+        Ok(content) => parse_decisions(content),
+        Err(e) => {
+            warn!("Failed to read the trust decisions {:?}: {}", path, e);
+            HashMap::new()
+        }
+    }
+}
+
+fn store_decisions(keys: &[String], trusted: bool) {
+    let path = trust_file();
+    let result = persist::update(&path, |content| {
+        let mut decisions = parse_decisions(content);
+        decisions.extend(keys.iter().map(|key| (key.clone(), trusted)));
+        serde_json::to_string(&decisions).expect("Failed to serialize the trust decisions")
+    });
+    if let Err(e) = result {
+        warn!("Failed to write the trust decisions {:?}: {}", path, e);
+    }
+}
+
+/// Asks the question below what was printed before, only a `y` trusts it.
+fn ask(question: &str) -> io::Result<bool> {
+    println!("\nOnly allow it if you know where your config comes from.");
+    print!("\n{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut s = String::new();
+    io::stdin().read_line(&mut s)?;
+    Ok(s.trim().eq_ignore_ascii_case("y"))
+}
+
+fn fetch(url: &str) -> reqwest::Result<String> {
+    reqwest::blocking::get(url)?.error_for_status()?.text()
+}
+
+/// Fetches the config at `url` and asks once whether to load it, an untrusted config is not used at all.
+pub fn shared_config(url: &str) -> Option<String> {
+    let content = fetch(url)
+        .map_err(|e| error!("Failed to fetch the config from {}: {}", url, e))
+        .ok()?;
+    let mut decisions = read_decisions();
+
+    if !decisions.contains_key(&content) && io::stdin().is_terminal() {
+        println!("\nYour config loads these settings from {}:", url);
+        for line in content.lines() {
+            println!("  {}", line);
+        }
+        match ask("Do you trust this config?") {
+            Ok(trusted) => {
+                store_decisions(std::slice::from_ref(&content), trusted);
+                decisions.insert(content.clone(), trusted);
+            }
+            Err(e) => warn!("Failed to ask whether to trust the config from {}: {}", url, e),
+        }
+    }
+    if decisions.get(&content) != Some(&true) {
+        info!("Not loading the untrusted config from {}.", url);
+        return None;
+    }
+    Some(content)
+}
+
+/// The configured commands by the option they come from, blank ones are never run.
+fn commands(config: &mut Config) -> Vec<(&'static str, &mut Option<String>)> {
+    let hooks = &mut config.hooks;
+    vec![
+        ("notification_command", &mut config.notification_command),
+        ("hooks.reveal", &mut hooks.reveal),
+        ("hooks.round_start", &mut hooks.round_start),
+        ("hooks.my_vote_last_missing", &mut hooks.my_vote_last_missing),
+        ("hooks.chat_mention", &mut hooks.chat_mention),
+    ]
+        .into_iter()
+        .filter(|(_, command)| command.as_deref().is_some_and(|c| !c.trim().is_empty()))
+        .collect()
+}
+
+/// Removes the commands without a decision or a declined one from the config, so they are never run.
+fn remove_untrusted(config: &mut Config, decisions: &HashMap<String, bool>) {
+    for (option, command) in commands(config) {
+        if command.as_ref().and_then(|c| decisions.get(c)) != Some(&true) {
+            info!("Not running the untrusted {} {:?}.", option, command);
+            *command = None;
+        }
+    }
+}
+
+/// Asks once whether the new commands of the config may run and keeps only the trusted ones.
+pub fn confirm_commands(config: &mut Config) -> io::Result<()> {
+    let mut decisions = read_decisions();
+    let undecided: Vec<(&str, String)> = commands(config).into_iter()
+        .filter_map(|(option, command)| command.clone().map(|command| (option, command)))
+        .filter(|(_, command)| !decisions.contains_key(command))
+        .collect();
+
+    if !undecided.is_empty() && io::stdin().is_terminal() {
+        println!("\nYour config runs these commands:");
+        for (option, command) in &undecided {
+            println!("  * {}: {}", option, command);
+        }
+        let trusted = ask("Do you trust these commands?")?;
+        let undecided: Vec<String> = undecided.into_iter().map(|(_, command)| command).collect();
+        store_decisions(&undecided, trusted);
+        decisions.extend(undecided.into_iter().map(|command| (command, trusted)));
+    }
+    remove_untrusted(config, &decisions);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_trusted_commands_remain() {
+        let mut config = Config { notification_command: Some("notify-send".to_string()), ..Config::default() };
+        config.hooks.reveal = Some("curl -X POST http://example.com".to_string());
+        config.hooks.round_start = Some("rm -rf ~".to_string());
+        config.hooks.chat_mention = Some(" ".to_string());
+        let decisions = HashMap::from([
+            ("notify-send".to_string(), true),
+            ("rm -rf ~".to_string(), false),
+        ]);
+
+        remove_untrusted(&mut config, &decisions);
+        assert_eq!(config.notification_command.as_deref(), Some("notify-send"));
+        assert_eq!(config.hooks.reveal, None);
+        assert_eq!(config.hooks.round_start, None);
+        assert_eq!(config.hooks.chat_mention.as_deref(), Some(" "));
+    }
+
+    #[test]
+    fn broken_files_are_ignored() {
+        assert_eq!(parse_decisions(Some("{\"theme = 'light'\":true}".to_string())).get("theme = 'light'"), Some(&true));
+        assert!(parse_decisions(Some("not json".to_string())).is_empty());
+    }
+}
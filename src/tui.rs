@@ -1,24 +1,100 @@
 use std::{io, panic};
 use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use crossterm::event::{DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange, KeyEvent};
+use crossterm::cursor::MoveTo;
+use crossterm::event::{DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste, EnableFocusChange, EnableMouseCapture, KeyEvent, KeyModifiers};
+use crossterm::style::{Attribute, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor};
 use crossterm::terminal;
-use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen, SetTitle};
 use log::debug;
 use ratatui::prelude::*;
 
+use crate::announce;
+use crate::diagnose;
 use crate::app::{App, AppResult};
 use crate::events::{Event, EventHandler, FocusChange};
-use crate::ui::{Page, UIAction, UiPage};
+use crate::links::{detect_hyperlinks, hyperlink};
+use crate::ui::{CommandLine, drawn_links, DrawnLink, is_too_small, Page, render_tabs, render_too_small, sanitize, UIAction, UiPage, UpdateDialog};
+use crate::ui::keymap::{GlobalAction, GLOBAL_KEYS, lookup, render_help};
+use crate::ui::symbols::symbols;
 use crate::ui::HistoryPage;
 use crate::ui::LogPage;
+use crate::ui::RoomsPage;
+use crate::ui::StatsPage;
+use crate::ui::SummaryPage;
 use crate::ui::VotingPage;
 
+/// Set once the tmux window got renamed, so the reset can hand the name back to tmux.
+static TMUX_RENAMED: AtomicBool = AtomicBool::new(false);
+
+// xterm window title stack, so the original title is restored on exit
+const PUSH_TITLE: &[u8] = b"\x1b[22;0t";
+const POP_TITLE: &[u8] = b"\x1b[23;0t";
+
 pub struct Tui<B: Backend> {
     terminal: Terminal<B>,
     pub events: EventHandler,
     pub current_page: UiPage,
     pages: HashMap<UiPage, Box<dyn Page>>,
+    command_line: CommandLine,
+    update_dialog: Option<UpdateDialog>,
+    show_help: bool,
+    title: String,
+    /// Draw links again as OSC 8 hyperlinks after every frame.
+    hyperlinks: bool,
+}
+
+/// Title like `ppoker ● waiting on you · room`, to see the state of a background pane or tab.
+fn window_title(app: &App) -> String {
+    let room = sanitize(&app.room.name);
+    let symbols = symbols();
+    if app.is_own_vote_missing() {
+        format!("ppoker {} waiting on you {} {}", symbols.dot, symbols.separator, room)
+    } else {
+        format!("ppoker {} {} {} {}", symbols.separator, room, symbols.separator, app.room.phase)
+    }
+}
+
+/// Prints the links over the drawn frame again, as clickable hyperlinks in the style they were drawn with. The
+/// sequences can't go into the buffer, ratatui would count the url as visible width.
+fn print_hyperlinks(links: &[DrawnLink]) -> io::Result<()> {
+    let mut out = io::stderr().lock();
+    for link in links {
+        crossterm::queue!(
+            out,
+            MoveTo(link.x, link.y),
+            SetForegroundColor(link.style.fg.unwrap_or(Color::Reset).into()),
+            SetBackgroundColor(link.style.bg.unwrap_or(Color::Reset).into()),
+        )?;
+        let modifiers = [
+            (Modifier::BOLD, Attribute::Bold),
+            (Modifier::DIM, Attribute::Dim),
+            (Modifier::ITALIC, Attribute::Italic),
+            (Modifier::UNDERLINED, Attribute::Underlined),
+        ];
+        for (modifier, attribute) in modifiers {
+            if link.style.add_modifier.contains(modifier) {
+                crossterm::queue!(out, SetAttribute(attribute))?;
+            }
+        }
+        write!(out, "{}", hyperlink(&link.url, &link.url))?;
+        crossterm::queue!(out, SetAttribute(Attribute::Reset), ResetColor)?;
+    }
+    out.flush()
+}
+
+fn run_tmux(args: &[&str]) {
+    let status = Command::new("tmux").args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    if let Err(e) = status {
+        debug!("Failed to run tmux: {}", e);
+    }
 }
 
 impl<B: Backend> Tui<B> {
@@ -29,19 +105,27 @@ impl<B: Backend> Tui<B> {
                 UiPage::Voting => { pages.insert(page, Box::new(VotingPage::new())); }
                 UiPage::Log => { pages.insert(page, Box::new(LogPage::new())); }
                 UiPage::History => { pages.insert(page, Box::new(HistoryPage::new())); }
+                UiPage::Stats => { pages.insert(page, Box::new(StatsPage::new())); }
+                UiPage::Rooms => { pages.insert(page, Box::new(RoomsPage::new())); }
+                UiPage::Summary => { pages.insert(page, Box::new(SummaryPage::new())); }
             }
         });
-        Self { terminal, events, current_page: UiPage::Voting, pages }
+        Self { terminal, events, current_page: UiPage::Voting, pages, command_line: CommandLine::new(), update_dialog: None, show_help: false, title: String::new(), hyperlinks: detect_hyperlinks() }
     }
     pub fn init(&mut self) -> AppResult<()> {
         terminal::enable_raw_mode()?;
         crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableFocusChange, EnableBracketedPaste)?;
+        io::stderr().write_all(PUSH_TITLE)?;
 
         let panic_hook = panic::take_hook();
         panic::set_hook(Box::new(move |panic| {
             tui_logger::move_events();
             Self::reset().expect("failed to reset the terminal");
             panic_hook(panic);
+            match diagnose::write_report(Some(&panic.to_string())) {
+                Ok(path) => eprintln!("A crash report was written to {}, please attach it to a bug report.", path.display()),
+                Err(e) => eprintln!("Failed to write a crash report: {}", e),
+            }
         }));
 
         self.terminal.hide_cursor()?;
@@ -50,14 +134,61 @@ impl<B: Backend> Tui<B> {
     }
 
     pub fn draw(&mut self, app: &mut App) -> AppResult<()> {
+        self.update_title(app)?;
+        for text in app.announcements.drain(..) {
+            announce::emit(&text);
+        }
         let page = self.pages.get_mut(&self.current_page).unwrap();
-        self.terminal.draw(|frame| page.render(app, frame))?;
+        let command_line = &self.command_line;
+        let update_dialog = &mut self.update_dialog;
+        let show_help = self.show_help;
+        let current_page = self.current_page;
+        let frame = self.terminal.draw(|frame| {
+            if is_too_small(frame.size()) {
+                render_too_small(frame.size(), frame);
+                return;
+            }
+            let [tabs, body] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(frame.size());
+            render_tabs(current_page, tabs, frame);
+            page.render(app, body, frame);
+            if let Some(dialog) = update_dialog {
+                dialog.render(app, frame);
+            }
+            command_line.render(frame);
+            if show_help {
+                render_help(&page.keybindings(app), frame);
+            }
+        })?;
+        if self.hyperlinks {
+            print_hyperlinks(&drawn_links(frame.buffer))?;
+        }
         Ok(())
     }
 
     fn reset() -> AppResult<()> {
         terminal::disable_raw_mode()?;
-        crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableFocusChange, DisableBracketedPaste)?;
+        crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableFocusChange, DisableBracketedPaste, DisableMouseCapture)?;
+        io::stderr().write_all(POP_TITLE)?;
+        if TMUX_RENAMED.load(Ordering::Relaxed) {
+            run_tmux(&["set-option", "-w", "automatic-rename", "on"]);
+        }
+        Ok(())
+    }
+
+    fn update_title(&mut self, app: &App) -> AppResult<()> {
+        if app.config.disable_terminal_title {
+            return Ok(());
+        }
+        let title = window_title(app);
+        if title == self.title {
+            return Ok(());
+        }
+        crossterm::execute!(io::stderr(), SetTitle(&title))?;
+        if app.config.tmux_window_name && std::env::var_os("TMUX").is_some() {
+            run_tmux(&["rename-window", &title]);
+            TMUX_RENAMED.store(true, Ordering::Relaxed);
+        }
+        self.title = title;
         Ok(())
     }
 
@@ -70,9 +201,13 @@ impl<B: Backend> Tui<B> {
 
     pub fn handle_events(&mut self, app: &mut App) -> AppResult<()> {
         match self.events.next()? {
-            Event::Tick => app.tick(),
+            Event::Tick => app.tick()?,
             Event::Key(event) => self.handle_key(event, app)?,
-            Event::Mouse(_) => {}
+            Event::Mouse(event) => {
+                if let Some(dialog) = &mut self.update_dialog {
+                    dialog.mouse(event);
+                }
+            }
             Event::Resize(_, _) => {}
             Event::Focus(change) => {
                 debug!("Focus change: {:?}", change);
@@ -85,17 +220,67 @@ impl<B: Backend> Tui<B> {
                     }
                 }
             }
-            Event::Paste(text) => self.pages.get_mut(&self.current_page).unwrap().pasted(app, text)
+            Event::Paste(text) => {
+                if self.command_line.is_active() {
+                    self.command_line.pasted(&text);
+                } else if let Some(dialog) = &mut self.update_dialog {
+                    dialog.pasted(&text);
+                } else {
+                    self.pages.get_mut(&self.current_page).unwrap().pasted(app, text)
+                }
+            }
         }
         Ok(())
     }
 
     fn handle_key(&mut self, key_event: KeyEvent, app: &mut App) -> AppResult<()> {
+        app.register_interaction()?;
+        if self.show_help {
+            self.show_help = false;
+            return Ok(());
+        }
         let page = self.pages.get_mut(&self.current_page).unwrap();
-        let action = page.input(app, key_event)?;
+        let global = lookup(&GLOBAL_KEYS, &key_event)
+            .filter(|_| !page.is_editing() || key_event.modifiers.contains(KeyModifiers::CONTROL));
+        let action = if self.command_line.is_active() {
+            self.command_line.input(app, key_event)?
+        } else if let Some(dialog) = &mut self.update_dialog {
+            let action = dialog.input(app, key_event);
+            if dialog.is_closed() {
+                self.update_dialog = None;
+                crossterm::execute!(io::stderr(), DisableMouseCapture)?;
+            }
+            action
+        } else if let Some(global) = global {
+            match global {
+                GlobalAction::CommandLine => {
+                    self.command_line.open();
+                    UIAction::Continue
+                }
+                GlobalAction::Help => {
+                    self.show_help = true;
+                    UIAction::Continue
+                }
+                GlobalAction::NextPage => UIAction::ChangeView(enum_iterator::next_cycle(&self.current_page)),
+                GlobalAction::PreviousPage => UIAction::ChangeView(enum_iterator::previous_cycle(&self.current_page)),
+            }
+        } else {
+            page.input(app, key_event)?
+        };
         match action {
             UIAction::Continue => {}
             UIAction::ChangeView(page) => { self.current_page = page }
+            UIAction::ShowUpdate => {
+                self.update_dialog = app.available_update.clone().map(UpdateDialog::new);
+                // only while the dialog is open, mouse capture prevents selecting text in the terminal
+                if self.update_dialog.is_some() {
+                    crossterm::execute!(io::stderr(), EnableMouseCapture)?;
+                }
+            }
+            // the summary of the session is shown once before quitting, with a chance to export it
+            UIAction::Quit if self.current_page != UiPage::Summary && !app.history.is_empty() && !app.restart_after_update => {
+                self.current_page = UiPage::Summary;
+            }
             UIAction::Quit => { app.running = false; }
         }
         Ok(())
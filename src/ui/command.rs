@@ -0,0 +1,225 @@
+use clap::ValueEnum;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::prelude::*;
+use ratatui::widgets::{Clear, Paragraph};
+
+use crate::app::{App, AppResult};
+use crate::config::Theme;
+use crate::export::{export_history_csv, export_session_html, export_session_summary};
+use crate::i18n::{tr, tr_args};
+use crate::models::LogLevel;
+use crate::ui::sanitize::{display_width, single_line};
+use crate::ui::{render_box, UIAction, UiPage};
+
+const COMMANDS: [(&str, &str); 12] = [
+    ("vote", "<card>"),
+    ("rename", "<name>"),
+    ("chat", "<message>"),
+    ("reveal", ""),
+    ("restart", ""),
+    ("room", "<room>"),
+    ("next", ""),
+    ("post", "[card]"),
+    ("export", "csv|summary|html [path]"),
+    ("theme", "dark|light"),
+    ("page", "voting|history|stats|log|rooms|summary"),
+    ("quit", ""),
+];
+
+/// Ex-style command line, available on every page with `:` or Ctrl+P.
+pub struct CommandLine {
+    buffer: Option<String>,
+    history: Vec<String>,
+    history_index: Option<usize>,
+}
+
+impl CommandLine {
+    pub fn new() -> Self {
+        Self {
+            buffer: None,
+            history: vec![],
+            history_index: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.buffer.is_some()
+    }
+
+    pub fn open(&mut self) {
+        self.buffer = Some(String::new());
+        self.history_index = None;
+    }
+
+    pub fn pasted(&mut self, text: &str) {
+        if let Some(buffer) = &mut self.buffer {
+            buffer.push_str(single_line(text).as_str());
+        }
+    }
+
+    pub fn input(&mut self, app: &mut App, event: KeyEvent) -> AppResult<UIAction> {
+        let Some(buffer) = &mut self.buffer else {
+            return Ok(UIAction::Continue);
+        };
+        match event.code {
+            KeyCode::Esc => self.buffer = None,
+            KeyCode::Enter => {
+                let line = buffer.trim().to_string();
+                self.buffer = None;
+                if !line.is_empty() {
+                    self.history.retain(|l| *l != line);
+                    self.history.push(line.clone());
+                    return execute(app, &line);
+                }
+            }
+            KeyCode::Backspace if buffer.is_empty() => self.buffer = None,
+            KeyCode::Backspace => {
+                buffer.pop();
+            }
+            KeyCode::Tab => *buffer = complete(buffer),
+            KeyCode::Up => self.recall(-1),
+            KeyCode::Down => self.recall(1),
+            KeyCode::Char(c) => buffer.push(c),
+            _ => {}
+        }
+        Ok(UIAction::Continue)
+    }
+
+    fn recall(&mut self, direction: isize) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = match self.history_index {
+            None if direction < 0 => Some(self.history.len() - 1),
+            None => None,
+            Some(i) => i.checked_add_signed(direction).filter(|i| *i < self.history.len()),
+        };
+        self.history_index = index;
+        self.buffer = Some(index.map_or(String::new(), |i| self.history[i].clone()));
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let Some(buffer) = &self.buffer else {
+            return;
+        };
+        let size = frame.size();
+        let rect = Rect::new(size.x, size.bottom().saturating_sub(3), size.width, 3.min(size.height));
+        frame.render_widget(Clear, rect);
+        let inner = render_box(tr("Command"), rect, frame);
+
+        let hints: Vec<String> = matching_commands(buffer).iter()
+            .map(|(name, args)| format!("{} {}", name, args).trim_end().to_string())
+            .collect();
+        let line = Line::from(vec![
+            Span::raw(format!(":{}", buffer)),
+            Span::raw(format!("   {}", hints.join(" | "))).gray(),
+        ]);
+        frame.render_widget(Paragraph::new(line), inner);
+        frame.set_cursor(inner.x + 1 + display_width(buffer) as u16, inner.y);
+    }
+}
+
+fn matching_commands(buffer: &str) -> Vec<(&'static str, &'static str)> {
+    let name = buffer.split_whitespace().next().unwrap_or("");
+    if buffer.contains(' ') {
+        COMMANDS.iter().filter(|(c, _)| *c == name).copied().collect()
+    } else {
+        COMMANDS.iter().filter(|(c, _)| c.starts_with(name)).copied().collect()
+    }
+}
+
+fn complete(buffer: &str) -> String {
+    let (candidates, prefix, partial): (Vec<&str>, String, &str) = match buffer.split_once(' ') {
+        None => (COMMANDS.iter().map(|(c, _)| *c).collect(), String::new(), buffer),
+        Some((command, argument)) => {
+            let options: Vec<&str> = match command {
+                "theme" => vec!["dark", "light"],
+                "page" => vec!["voting", "history", "stats", "log", "rooms", "summary"],
+                "export" => vec!["csv", "summary", "html"],
+                _ => vec![],
+            };
+            (options, format!("{} ", command), argument)
+        }
+    };
+    let matches: Vec<&str> = candidates.into_iter().filter(|c| c.starts_with(partial)).collect();
+    match matches.as_slice() {
+        [] => buffer.to_string(),
+        [single] => format!("{}{} ", prefix, single),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.len(), |len, other| {
+                first.chars().zip(other.chars()).take(len).take_while(|(a, b)| a == b).count()
+            });
+            format!("{}{}", prefix, &first[..common])
+        }
+    }
+}
+
+fn execute(app: &mut App, line: &str) -> AppResult<UIAction> {
+    let (command, argument) = line.split_once(' ').map_or((line, ""), |(c, a)| (c, a.trim()));
+    match (command, argument) {
+        ("vote", card) if !card.is_empty() => app.vote(card)?,
+        ("rename", name) if !name.is_empty() => app.rename(name.to_string())?,
+        ("chat", message) if !message.is_empty() => app.chat(message.to_string())?,
+        ("reveal", "") => app.reveal()?,
+        ("restart", "") => app.restart()?,
+        ("room", room) if !room.is_empty() => {
+            if let Err(e) = app.join_room(room.to_string()) {
+                app.log_message(LogLevel::Error, tr_args("Failed to join room {}: {}", &[room, &e.to_string()]));
+            }
+        }
+        ("export", argument) if argument == "csv" || argument.starts_with("csv ") => {
+            let path = argument.strip_prefix("csv").map(str::trim).filter(|p| !p.is_empty());
+            match export_history_csv(&app.history, &app.room.name, path) {
+                Ok(path) => app.log_message(LogLevel::Info, tr_args("Exported history to {}", &[&path.display().to_string()])),
+                Err(e) => app.log_message(LogLevel::Error, tr_args("Failed to export history: {}", &[&e.to_string()])),
+            }
+        }
+        ("export", argument) if argument == "summary" || argument.starts_with("summary ") => {
+            let path = argument.strip_prefix("summary").map(str::trim).filter(|p| !p.is_empty());
+            match export_session_summary(&app.history, &app.room.name, &app.session_notes(), path) {
+                Ok(path) => app.log_message(LogLevel::Info, tr_args("Exported session summary to {}", &[&path.display().to_string()])),
+                Err(e) => app.log_message(LogLevel::Error, tr_args("Failed to export session summary: {}", &[&e.to_string()])),
+            }
+        }
+        ("export", argument) if argument == "html" || argument.starts_with("html ") => {
+            let path = argument.strip_prefix("html").map(str::trim).filter(|p| !p.is_empty());
+            match export_session_html(&app.history, &app.room.name, &app.session_notes(), path) {
+                Ok(path) => app.log_message(LogLevel::Info, tr_args("Exported session summary to {}", &[&path.display().to_string()])),
+                Err(e) => app.log_message(LogLevel::Error, tr_args("Failed to export session summary: {}", &[&e.to_string()])),
+            }
+        }
+        ("theme", theme) => match Theme::from_str(theme, true) {
+            Ok(theme) => app.config.theme = theme,
+            Err(_) => app.log_message(LogLevel::Error, tr_args("Unknown theme: {}", &[theme])),
+        },
+        ("page", page) => {
+            let target = enum_iterator::all::<UiPage>().find(|p| format!("{:?}", p).eq_ignore_ascii_case(page));
+            match target {
+                Some(page) => return Ok(UIAction::ChangeView(page)),
+                None => app.log_message(LogLevel::Error, tr_args("Unknown page: {}", &[page])),
+            }
+        }
+        ("next", "") => app.next_story()?,
+        ("post", card) => app.post_estimate(Some(card).filter(|c| !c.is_empty())),
+        ("quit", "") => return Ok(UIAction::Quit),
+        _ => app.log_message(LogLevel::Error, tr_args("Unknown command: {}", &[line])),
+    }
+    Ok(UIAction::Continue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_commands_and_arguments() {
+        assert_eq!(complete("vo"), "vote ");
+        assert_eq!(complete("re"), "re");
+        assert_eq!(complete("ren"), "rename ");
+        assert_eq!(complete("theme l"), "theme light ");
+        assert_eq!(complete("page "), "page ");
+        assert_eq!(complete("xyz"), "xyz");
+    }
+}
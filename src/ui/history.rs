@@ -2,27 +2,76 @@ use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::prelude::*;
-use ratatui::widgets::{Cell, Row, Table, TableState};
+use ratatui::widgets::{Cell, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState};
 
 use crate::app::{App, AppResult, HistoryEntry};
+use crate::config::Config;
+use crate::format::format_duration;
+use crate::i18n::tr;
 use crate::models::GamePhase;
-use crate::ui::{colored_box_style, footer_entries, format_duration, Page, render_box, render_box_colored, UIAction, UiPage};
-use crate::ui::voting::{format_vote, render_overview, render_own_vote};
+use crate::ui::keymap::{help, lookup, HelpEntry, KeyBinding};
+use crate::ui::sanitize::{display_width, sanitize};
+use crate::ui::symbols::symbols;
+use crate::ui::theme::player_style;
+use crate::ui::{colored_box_style, footer_entries, Page, render_box, render_box_colored, trim_name, UIAction, UiPage};
+use crate::ui::voting::{arrow_reference, color_reference, format_vote, vote_boxes_height, vote_with_confidence, render_average, render_distribution, render_overview, render_your_vote};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum HistoryAction {
+    Quit,
+    Back,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+    ShowLog,
+}
+
+const BACK_KEYS: [KeyCode; 13] = [
+    KeyCode::Char('v'), KeyCode::Char('-'), KeyCode::Char('h'),
+    KeyCode::Char('0'), KeyCode::Char('1'), KeyCode::Char('2'), KeyCode::Char('3'), KeyCode::Char('4'),
+    KeyCode::Char('5'), KeyCode::Char('6'), KeyCode::Char('7'), KeyCode::Char('8'), KeyCode::Char('9'),
+];
+
+const HISTORY_KEYS: [KeyBinding<HistoryAction>; 9] = [
+    KeyBinding::new(&[KeyCode::Up], "UP", "Select the previous round", HistoryAction::Up),
+    KeyBinding::new(&[KeyCode::Down], "DOWN", "Select the next round", HistoryAction::Down),
+    KeyBinding::new(&[KeyCode::PageUp], "PGUP/PGDN", "Select the round a page up or down", HistoryAction::PageUp),
+    KeyBinding::new(&[KeyCode::PageDown], "", "", HistoryAction::PageDown),
+    KeyBinding::new(&[KeyCode::Home], "HOME/END", "Select the first or last round", HistoryAction::Top),
+    KeyBinding::new(&[KeyCode::End], "", "", HistoryAction::Bottom),
+    KeyBinding::new(&[KeyCode::Char('c'), KeyCode::Enter], "c/ENTER", "Show the chat and log of the round", HistoryAction::ShowLog),
+    KeyBinding::new(&BACK_KEYS, "h/v", "Back to voting", HistoryAction::Back),
+    KeyBinding::new(&[KeyCode::Esc, KeyCode::Char('q')], "ESC/q", "Quit", HistoryAction::Quit),
+];
 
 pub struct HistoryPage {
     history_state: TableState,
+    /// Rounds fitting into the table, set on render.
+    page_size: usize,
 }
 
 impl HistoryPage {
     pub fn new() -> Self {
         Self {
             history_state: TableState::default(),
+            page_size: 1,
+        }
+    }
+
+    /// Moves the selection by `delta` rounds, staying within the history.
+    fn select(&mut self, delta: isize, len: usize) {
+        if let Some(selected) = self.history_state.selected() {
+            let index = selected.saturating_add_signed(delta).min(len.saturating_sub(1));
+            self.history_state.select(Some(index));
         }
     }
 }
 
 impl Page for HistoryPage {
-    fn render(&mut self, app: &mut App, frame: &mut Frame) {
+    fn render(&mut self, app: &mut App, area: Rect, frame: &mut Frame) {
         if self.history_state.selected().is_none() && app.history.len() > 0 {
             self.history_state.select(Some(0));
         }
@@ -34,40 +83,61 @@ impl Page for HistoryPage {
                 Constraint::Fill(1),
                 Constraint::Length(3)
             ])
-            .areas(frame.size());
+            .areas(area);
 
         render_overview(app, header, frame);
         self.render_main(app, body, frame);
         self.render_footer(app, footer, frame);
     }
 
-    fn input(&mut self, _app: &mut App, event: KeyEvent) -> AppResult<UIAction> {
-        return Ok(match event.code {
-            KeyCode::Esc | KeyCode::Char('q') => {
+    fn input(&mut self, app: &mut App, event: KeyEvent) -> AppResult<UIAction> {
+        return Ok(match lookup(&HISTORY_KEYS, &event) {
+            Some(HistoryAction::Quit) => {
                 UIAction::Quit
             }
-            KeyCode::Char(c) if c == 'v' || c == '-' || c == 'h' || c.is_ascii_digit() => {
+            Some(HistoryAction::Back) => {
                 UIAction::ChangeView(UiPage::Voting)
             }
-            KeyCode::Down => {
-                if let Some(s) = self.history_state.selected() {
-                    let mut new_index = s.saturating_add(1);
-                    if new_index >= _app.history.len() {
-                        new_index = _app.history.len().saturating_sub(1);
-                    }
-                    self.history_state.select(Some(new_index));
-                }
+            Some(HistoryAction::Down) => {
+                self.select(1, app.history.len());
                 UIAction::Continue
             }
-            KeyCode::Up => {
-                if let Some(s) = self.history_state.selected() {
-                    self.history_state.select(Some(s.saturating_sub(1)));
-                }
+            Some(HistoryAction::Up) => {
+                self.select(-1, app.history.len());
+                UIAction::Continue
+            }
+            Some(HistoryAction::PageDown) => {
+                self.select(self.page_size as isize, app.history.len());
+                UIAction::Continue
+            }
+            Some(HistoryAction::PageUp) => {
+                self.select(-(self.page_size as isize), app.history.len());
+                UIAction::Continue
+            }
+            Some(HistoryAction::Top) => {
+                self.select(isize::MIN, app.history.len());
+                UIAction::Continue
+            }
+            Some(HistoryAction::Bottom) => {
+                self.select(isize::MAX, app.history.len());
                 UIAction::Continue
             }
-            _ => { UIAction::Continue }
+            Some(HistoryAction::ShowLog) => {
+                match self.history_state.selected() {
+                    Some(index) => {
+                        app.log_round = Some(index);
+                        UIAction::ChangeView(UiPage::Voting)
+                    }
+                    None => UIAction::Continue,
+                }
+            }
+            None => { UIAction::Continue }
         });
     }
+
+    fn keybindings(&self, _app: &App) -> Vec<HelpEntry> {
+        help(&HISTORY_KEYS)
+    }
 }
 
 impl HistoryPage {
@@ -77,33 +147,34 @@ impl HistoryPage {
             Constraint::Percentage(60),
         ]).areas(rect);
 
-        let [vote_summary, players] = Layout::vertical([
+        let [vote_summary, distribution, players] = Layout::vertical([
+            Constraint::Length(vote_boxes_height(app.config.big_text_size)),
             Constraint::Length(9),
             Constraint::Fill(1),
         ]).areas(detail);
 
+        let [own_vote, average] = Layout::horizontal([
+            Constraint::Length(26),
+            Constraint::Length(40),
+        ]).areas(vote_summary);
+
         let current_entry = self.history_state.selected().map(|idx| {
             &app.history[idx]
         });
 
         if let Some(current_entry) = current_entry {
-            render_own_vote(
-                &current_entry.votes,
-                current_entry.average,
-                GamePhase::Revealed,
-                &current_entry.own_vote,
-                &current_entry.deck,
-                vote_summary,
-                frame,
-            );
-
-            render_player_list(&current_entry, players, frame);
+            let size = app.config.big_text_size;
+            render_your_vote(&current_entry.own_vote, GamePhase::Revealed, size, own_vote, frame);
+            render_average(&current_entry.votes, &current_entry.deck, current_entry.average, GamePhase::Revealed, size, average, frame);
+            render_distribution(&current_entry.votes, &current_entry.deck, GamePhase::Revealed, distribution, frame);
+
+            render_player_list(&current_entry, &app.config, players, frame);
         }
         self.render_history(app, history, frame);
     }
 
     fn render_footer(&mut self, app: &mut App, rect: Rect, frame: &mut Frame) {
-        let entries = vec!["Vote", "↑", "↓", "Quit"];
+        let entries = vec!["Vote", symbols().up, symbols().down, "Quit"];
         let mut footer = footer_entries(entries);
         if app.has_updates {
             footer = footer.style(Style::new().yellow());
@@ -112,48 +183,91 @@ impl HistoryPage {
     }
 
     fn render_history(&mut self, app: &mut App, rect: Rect, frame: &mut Frame) {
-        let inner = render_box("History", rect, frame);
+        let inner = render_box(tr("History"), rect, frame);
 
         let rows: Vec<Row> = app.history.iter().map(|entry| {
             Row::new(vec![
                 Cell::from(Span::raw(entry.round_number.to_string())),
-                Cell::from(Span::raw(format!("{:.1}", entry.average))),
+                Cell::from(Span::raw(format_average(entry))),
                 Cell::from(Span::raw(format_duration(&entry.length))),
+                Cell::from(Span::raw(sanitize(entry.topic.as_deref().unwrap_or("")))),
             ])
         }).collect();
 
-        let table = Table::new(rows, [Constraint::Length(5), Constraint::Length(8), Constraint::Fill(1)])
+        let table = Table::new(rows, [Constraint::Length(5), Constraint::Length(8), Constraint::Length(20), Constraint::Fill(1)])
             .column_spacing(4)
-            .header(Row::new(vec!["Round", "Average", "Duration"])
+            .header(Row::new(vec![tr("Round"), tr("Average"), tr("Duration"), tr("Topic")])
                 .style(Style::new().bold())
                 .bottom_margin(1))
             .highlight_symbol("> ")
             .highlight_style(Style::new().on_white().black());
 
+        // header and its margin take two lines
+        self.page_size = (inner.height as usize).saturating_sub(2).max(1);
         frame.render_stateful_widget(table, inner, &mut self.history_state);
+
+        if app.history.len() > self.page_size {
+            let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .track_symbol(Some(symbols().border.vertical_right))
+                .thumb_symbol(symbols().bar.full);
+            let mut state = ScrollbarState::new(app.history.len().saturating_sub(self.page_size))
+                .position(self.history_state.offset());
+            frame.render_stateful_widget(scrollbar, rect.inner(Margin { vertical: 1, horizontal: 0 }), &mut state);
+        }
     }
 }
 
-fn render_player_list(entry: &HistoryEntry, rect: Rect, frame: &mut Frame) {
-    let inner = render_box_colored("Players", colored_box_style(GamePhase::Revealed), rect, frame);
+fn format_average(entry: &HistoryEntry) -> String {
+    if entry.special_votes > 0 {
+        format!("{:.1} ({}?)", entry.average, entry.special_votes)
+    } else {
+        format!("{:.1}", entry.average)
+    }
+}
+
+fn render_player_list(entry: &HistoryEntry, config: &Config, rect: Rect, frame: &mut Frame) {
+    let inner = render_box_colored(tr("Players"), colored_box_style(GamePhase::Revealed), rect, frame);
     let mut longest_name = 0;
     let mut players = entry.votes.clone();
     players.sort();
+    let arrows = arrow_reference(config.vote_arrows, &entry.own_vote, entry.average);
+    let colors = color_reference(config.vote_colors, &entry.own_vote, entry.average);
     let rows: Vec<Row> = entry.votes.iter().map(|p| {
-        if p.name.len() > longest_name {
-            longest_name = p.name.len();
-        }
+        let name = trim_name(&p.name);
+        longest_name = longest_name.max(display_width(&name));
         Row::new(vec![
-            Cell::from(Span::raw(p.name.as_str())),
-            Cell::from(format_vote(&p.vote, &entry.own_vote)),
+            Cell::from(Span::styled(name, player_style(&p.name, config.theme))),
+            Cell::from(vote_with_confidence(format_vote(&p.vote, colors, config.theme, arrows), entry.confidence.get(p.key()))),
         ])
     }).collect();
 
     let table = Table::new(rows, [Constraint::Length(longest_name as u16), Constraint::Fill(1)])
         .column_spacing(4)
-        .header(Row::new(vec!["Name", "Vote"])
+        .header(Row::new(vec![tr("Name"), tr("Vote")])
             .style(Style::new().bold())
             .bottom_margin(1));
 
     frame.render_widget(table, inner);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_selection_within_the_history() {
+        let mut page = HistoryPage::new();
+        page.history_state.select(Some(0));
+        page.page_size = 4;
+        page.select(page.page_size as isize, 10);
+        assert_eq!(page.history_state.selected(), Some(4));
+        page.select(isize::MAX, 10);
+        assert_eq!(page.history_state.selected(), Some(9));
+        page.select(-(page.page_size as isize), 10);
+        assert_eq!(page.history_state.selected(), Some(5));
+        page.select(isize::MIN, 10);
+        assert_eq!(page.history_state.selected(), Some(0));
+    }
+}
@@ -0,0 +1,121 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout};
+use ratatui::prelude::*;
+use ratatui::widgets::{Cell, Clear, Row, Table};
+
+use crate::i18n::tr;
+use crate::ui::render_box;
+
+/// Key and description shown in the help overlay.
+pub type HelpEntry = (&'static str, &'static str);
+
+/// Binds keys to an action. Input handling and the help overlay both use these tables, so the help can't go stale.
+pub struct KeyBinding<A: Copy> {
+    pub keys: &'static [KeyCode],
+    pub modifiers: KeyModifiers,
+    /// Label shown in the help, bindings with an empty label are described by the one before.
+    pub label: &'static str,
+    pub description: &'static str,
+    pub action: A,
+}
+
+impl<A: Copy> KeyBinding<A> {
+    pub const fn new(keys: &'static [KeyCode], label: &'static str, description: &'static str, action: A) -> Self {
+        Self { keys, modifiers: KeyModifiers::NONE, label, description, action }
+    }
+
+    pub const fn with_modifiers(mut self, modifiers: KeyModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+}
+
+pub const DIGITS: [KeyCode; 10] = [
+    KeyCode::Char('0'), KeyCode::Char('1'), KeyCode::Char('2'), KeyCode::Char('3'), KeyCode::Char('4'),
+    KeyCode::Char('5'), KeyCode::Char('6'), KeyCode::Char('7'), KeyCode::Char('8'), KeyCode::Char('9'),
+];
+
+pub fn lookup<A: Copy>(bindings: &[KeyBinding<A>], event: &KeyEvent) -> Option<A> {
+    // shift is part of the character already, e.g. `?` or upper case letters
+    let modifiers = event.modifiers.difference(KeyModifiers::SHIFT);
+    bindings.iter()
+        .find(|b| b.modifiers == modifiers && b.keys.contains(&event.code))
+        .map(|b| b.action)
+}
+
+pub fn help<A: Copy>(bindings: &[KeyBinding<A>]) -> Vec<HelpEntry> {
+    bindings.iter()
+        .filter(|b| !b.label.is_empty())
+        .map(|b| (b.label, b.description))
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GlobalAction {
+    CommandLine,
+    Help,
+    NextPage,
+    PreviousPage,
+}
+
+/// Keys available on every page, those without modifiers only while the page isn't capturing text.
+pub const GLOBAL_KEYS: [KeyBinding<GlobalAction>; 5] = [
+    KeyBinding::new(&[KeyCode::Char(':')], ":", "Open the command line", GlobalAction::CommandLine),
+    KeyBinding::new(&[KeyCode::Char('p')], "Ctrl+P", "Open the command line", GlobalAction::CommandLine)
+        .with_modifiers(KeyModifiers::CONTROL),
+    KeyBinding::new(&[KeyCode::Char('?')], "?", "Show this help", GlobalAction::Help),
+    KeyBinding::new(&[KeyCode::Tab], "TAB", "Next page", GlobalAction::NextPage),
+    KeyBinding::new(&[KeyCode::BackTab], "SHIFT+TAB", "Previous page", GlobalAction::PreviousPage),
+];
+
+pub fn render_help(page: &[HelpEntry], frame: &mut Frame) {
+    let global = help(&GLOBAL_KEYS);
+    let height = (page.len() + global.len() + 5) as u16;
+    let [area] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(frame.size());
+    let [area] = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(area);
+
+    frame.render_widget(Clear, area);
+    let inner = render_box(tr("Help - press any key to close"), area, frame);
+
+    let section = |title: &'static str| Row::new(vec![Cell::from(tr(title)), Cell::from("")]).style(Style::new().bold());
+    let entry = |(key, description): &HelpEntry| Row::new(vec![
+        Cell::from(Span::raw(*key).bold()),
+        Cell::from(tr(description)),
+    ]);
+
+    let mut rows = vec![section("This page")];
+    rows.extend(page.iter().map(entry));
+    rows.push(Row::new(vec![""]));
+    rows.push(section("Everywhere"));
+    rows.extend(global.iter().map(entry));
+
+    let table = Table::new(rows, [Constraint::Length(16), Constraint::Fill(1)])
+        .column_spacing(2);
+    frame.render_widget(table, inner);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_respects_modifiers() {
+        let plain = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE);
+        let ctrl = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+        let question = KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT);
+        assert_eq!(lookup(&GLOBAL_KEYS, &plain), None);
+        assert_eq!(lookup(&GLOBAL_KEYS, &ctrl), Some(GlobalAction::CommandLine));
+        assert_eq!(lookup(&GLOBAL_KEYS, &question), Some(GlobalAction::Help));
+    }
+
+    #[test]
+    fn help_skips_unlabeled_bindings() {
+        let bindings = [
+            KeyBinding::new(&[KeyCode::Up], "UP/DOWN", "Navigate", 1),
+            KeyBinding::new(&[KeyCode::Down], "", "", 2),
+        ];
+        assert_eq!(help(&bindings), vec![("UP/DOWN", "Navigate")]);
+        assert_eq!(lookup(&bindings, &KeyEvent::new(KeyCode::Down, KeyModifiers::NONE)), Some(2));
+    }
+}
@@ -4,12 +4,36 @@ use ratatui::Frame;
 use ratatui::layout::{Constraint, Layout};
 use ratatui::prelude::*;
 use ratatui::style::Style;
-use ratatui::widgets::{Block, BorderType, Paragraph, Wrap};
+use ratatui::widgets::{Block, Paragraph, Wrap};
 use tui_logger::{TuiLoggerLevelOutput, TuiLoggerSmartWidget, TuiWidgetEvent, TuiWidgetState};
 
 use crate::app::{App, AppResult};
+use crate::ui::keymap::{help, lookup, HelpEntry, KeyBinding};
+use crate::ui::symbols::symbols;
 use crate::ui::{Page, UIAction, UiPage};
 
+#[derive(Debug, Clone, Copy)]
+enum LogAction {
+    Quit,
+    Leave,
+    Widget(TuiWidgetEvent),
+}
+
+const LOG_KEYS: [KeyBinding<LogAction>; 12] = [
+    KeyBinding::new(&[KeyCode::Char('h')], "h", "Toggle target selector", LogAction::Widget(TuiWidgetEvent::HideKey)),
+    KeyBinding::new(&[KeyCode::Char('f')], "f", "Toggle focus", LogAction::Widget(TuiWidgetEvent::FocusKey)),
+    KeyBinding::new(&[KeyCode::Up], "UP/DOWN", "Navigate", LogAction::Widget(TuiWidgetEvent::UpKey)),
+    KeyBinding::new(&[KeyCode::Down], "", "", LogAction::Widget(TuiWidgetEvent::DownKey)),
+    KeyBinding::new(&[KeyCode::Left], "LEFT/RIGHT", "Reduce/increase level", LogAction::Widget(TuiWidgetEvent::LeftKey)),
+    KeyBinding::new(&[KeyCode::Right], "", "", LogAction::Widget(TuiWidgetEvent::RightKey)),
+    KeyBinding::new(&[KeyCode::PageUp], "PAGEUP/PAGEDOWN", "Enter Page mode, scroll up/down", LogAction::Widget(TuiWidgetEvent::PrevPageKey)),
+    KeyBinding::new(&[KeyCode::PageDown], "", "", LogAction::Widget(TuiWidgetEvent::NextPageKey)),
+    KeyBinding::new(&[KeyCode::Esc], "ESCAPE", "Exit page mode", LogAction::Widget(TuiWidgetEvent::EscapeKey)),
+    KeyBinding::new(&[KeyCode::Char(' ')], "SPACE", "Toggle hiding disabled targets", LogAction::Widget(TuiWidgetEvent::SpaceKey)),
+    KeyBinding::new(&[KeyCode::Char('l')], "l", "Leave log view", LogAction::Leave),
+    KeyBinding::new(&[KeyCode::Char('q')], "q", "Quit application", LogAction::Quit),
+];
+
 pub struct LogPage {
     state: TuiWidgetState,
 }
@@ -26,23 +50,16 @@ impl LogPage {
 }
 
 impl Page for LogPage {
-    fn render(&mut self, _app: &mut App, frame: &mut Frame) {
-        let mut helptexts: Vec<Span> = vec![];
-        helptexts.append(&mut help_spans("h", "Toggle target selector"));
-        helptexts.append(&mut help_spans("f", "Toggle focus"));
-        helptexts.append(&mut help_spans("UP/DOWN", "Navigate"));
-        helptexts.append(&mut help_spans("LEFT/RIGHT", "Reduce/increase level"));
-        helptexts.append(&mut help_spans("PAGEUP/PAGEDOWN", "Enter Page mode, scroll up/down"));
-        helptexts.append(&mut help_spans("ESCAPE", "Exit page mode"));
-        helptexts.append(&mut help_spans("SPACE", "Toggle hiding disabled targets"));
-        helptexts.append(&mut help_spans("l", "Leave log view"));
-        helptexts.append(&mut help_spans("q", "Quit application"));
+    fn render(&mut self, _app: &mut App, area: Rect, frame: &mut Frame) {
+        let mut helptexts: Vec<Span> = help(&LOG_KEYS).into_iter()
+            .flat_map(|(key, description)| help_spans(key, description))
+            .collect();
         helptexts.pop();
 
         let help_paragraph = Paragraph::new(Line::from(helptexts))
             .wrap(Wrap { trim: true });
 
-        let help_lines = help_paragraph.line_count(frame.size().width.saturating_sub(2)) as u16;
+        let help_lines = help_paragraph.line_count(area.width.saturating_sub(2)) as u16;
 
         let [log, help] = Layout::default()
             .direction(Direction::Vertical)
@@ -50,7 +67,7 @@ impl Page for LogPage {
                 Constraint::Fill(1),
                 Constraint::Length(help_lines + 2)
             ])
-            .areas(frame.size());
+            .areas(area);
 
         let widget = TuiLoggerSmartWidget::default()
             .style_error(Style::default().red())
@@ -70,7 +87,7 @@ impl Page for LogPage {
         let block = Block::bordered()
             .title("Help")
             .title_alignment(Alignment::Left)
-            .border_type(BorderType::Rounded);
+            .border_set(symbols().border);
         let help_inner = block.inner(help);
 
         frame.render_widget(block, help);
@@ -79,23 +96,18 @@ impl Page for LogPage {
     }
 
     fn input(&mut self, _app: &mut App, event: KeyEvent) -> AppResult<UIAction> {
-        match event.code.into() {
-            KeyCode::Char('q') => return Ok(UIAction::Quit),
-            KeyCode::Char(' ') => self.state.transition(TuiWidgetEvent::SpaceKey),
-            KeyCode::Esc => self.state.transition(TuiWidgetEvent::EscapeKey),
-            KeyCode::PageUp => self.state.transition(TuiWidgetEvent::PrevPageKey),
-            KeyCode::PageDown => self.state.transition(TuiWidgetEvent::NextPageKey),
-            KeyCode::Up => self.state.transition(TuiWidgetEvent::UpKey),
-            KeyCode::Down => self.state.transition(TuiWidgetEvent::DownKey),
-            KeyCode::Left => self.state.transition(TuiWidgetEvent::LeftKey),
-            KeyCode::Right => self.state.transition(TuiWidgetEvent::RightKey),
-            KeyCode::Char('h') => self.state.transition(TuiWidgetEvent::HideKey),
-            KeyCode::Char('f') => self.state.transition(TuiWidgetEvent::FocusKey),
-            KeyCode::Char('l') => return Ok(UIAction::ChangeView(UiPage::Voting)),
-            _ => {}
+        match lookup(&LOG_KEYS, &event) {
+            Some(LogAction::Quit) => return Ok(UIAction::Quit),
+            Some(LogAction::Leave) => return Ok(UIAction::ChangeView(UiPage::Voting)),
+            Some(LogAction::Widget(event)) => self.state.transition(event),
+            None => {}
         }
         return Ok(UIAction::Continue);
     }
+
+    fn keybindings(&self, _app: &App) -> Vec<HelpEntry> {
+        help(&LOG_KEYS)
+    }
 }
 
 fn help_spans<'a>(key: &'a str, description: &'a str) -> Vec<Span<'a>> {
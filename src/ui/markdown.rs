@@ -0,0 +1,273 @@
+//! Renders the markdown of release notes: headings, nested lists, block quotes, fenced code, tables and inline
+//! strong, code and links. Links are shown as footnotes, so they stay readable in every terminal.
+
+use ratatui::prelude::*;
+use unicode_width::UnicodeWidthStr;
+
+use crate::ui::sanitize::sanitize;
+use crate::ui::symbols::symbols;
+
+/// Spaces of indentation per nested list level in the source and on screen.
+const LIST_INDENT: usize = 2;
+
+/// Rendered markdown with the positions of its headings.
+pub struct Markdown {
+    pub lines: Vec<Line<'static>>,
+    /// Index into `lines` and level of each heading, `##` is level 2.
+    pub headings: Vec<(usize, usize)>,
+}
+
+/// A line of markdown by what it is, with the markup of the block removed and the text sanitized.
+#[derive(Debug, PartialEq)]
+pub enum Block {
+    /// `##` is level 2.
+    Heading { level: usize, text: String },
+    /// Top level items are level 0.
+    ListItem { level: usize, text: String },
+    Quote(String),
+    /// Line inside a fenced code block.
+    Code(String),
+    TableRow(Vec<String>),
+    Text(String),
+}
+
+/// The blocks of the lines, without the fences of code blocks and the separators of tables.
+pub fn parse_blocks(text: &str) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut in_code = false;
+    for raw in text.lines() {
+        let raw = sanitize(raw.trim_end());
+        let trimmed = raw.trim_start();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        let block = if in_code {
+            Block::Code(raw.clone())
+        } else if trimmed.starts_with('|') {
+            if is_table_separator(trimmed) {
+                continue;
+            }
+            Block::TableRow(table_cells(trimmed))
+        } else if trimmed.starts_with('#') {
+            let level = trimmed.chars().take_while(|c| *c == '#').count();
+            Block::Heading { level, text: trimmed[level..].trim().to_string() }
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            Block::Quote(quote.trim_start().to_string())
+        } else if let Some(item) = trimmed.strip_prefix("* ").or_else(|| trimmed.strip_prefix("- ")) {
+            Block::ListItem { level: (raw.len() - trimmed.len()) / LIST_INDENT, text: item.to_string() }
+        } else {
+            Block::Text(raw.clone())
+        };
+        blocks.push(block);
+    }
+    blocks
+}
+
+pub fn render_markdown(text: &str) -> Markdown {
+    let mut headings = vec![];
+    let mut links: Vec<String> = vec![];
+    let mut lines: Vec<Line<'static>> = vec![];
+    let mut table: Vec<Vec<String>> = vec![];
+
+    for block in parse_blocks(text) {
+        if !matches!(block, Block::TableRow(_)) && !table.is_empty() {
+            lines.extend(render_table(&table, &mut links));
+            table.clear();
+        }
+        match block {
+            Block::Heading { level, text } => {
+                headings.push((lines.len(), level));
+                lines.push(Line::from(inline_spans(&text, &mut links)).bold());
+            }
+            Block::ListItem { level, text } => {
+                let mut spans = vec![Span::raw(format!("{}- ", " ".repeat(level * LIST_INDENT)))];
+                spans.extend(inline_spans(&text, &mut links));
+                lines.push(Line::from(spans));
+            }
+            Block::Quote(text) => {
+                let mut spans = vec![Span::raw(format!("{} ", symbols().border.vertical_left)).gray()];
+                spans.extend(inline_spans(&text, &mut links));
+                lines.push(Line::from(spans).italic());
+            }
+            Block::Code(code) => lines.push(Line::from(format!("  {}", code)).gray()),
+            Block::TableRow(cells) => table.push(cells),
+            Block::Text(text) => lines.push(Line::from(inline_spans(&text, &mut links))),
+        }
+    }
+    if !table.is_empty() {
+        lines.extend(render_table(&table, &mut links));
+    }
+
+    if !links.is_empty() {
+        lines.push(Line::from(""));
+        for (i, url) in links.iter().enumerate() {
+            lines.push(Line::from(format!("[{}] {}", i + 1, url)).gray());
+        }
+    }
+    Markdown { lines, headings }
+}
+
+/// Marks every case-insensitive occurrence of `query` in the line.
+pub fn highlight(line: &Line<'static>, query: &str) -> Line<'static> {
+    if query.is_empty() {
+        return line.clone();
+    }
+    let query = query.to_lowercase();
+    let mut spans = vec![];
+    for span in &line.spans {
+        let content = span.content.to_string();
+        // lowercasing can change the length of non-ascii text, matches are only exact for ascii then
+        let lower = content.to_lowercase();
+        if lower.len() != content.len() {
+            spans.push(span.clone());
+            continue;
+        }
+        let mut end = 0;
+        for (start, matched) in lower.match_indices(&query) {
+            if start > end {
+                spans.push(Span::styled(content[end..start].to_string(), span.style));
+            }
+            spans.push(Span::styled(content[start..start + matched.len()].to_string(), span.style.black().on_yellow()));
+            end = start + matched.len();
+        }
+        if end < content.len() {
+            spans.push(Span::styled(content[end..].to_string(), span.style));
+        }
+    }
+    Line::from(spans).style(line.style)
+}
+
+/// Rows like `|---|:--:|`.
+fn is_table_separator(line: &str) -> bool {
+    line.chars().all(|c| matches!(c, '|' | '-' | ':' | ' '))
+}
+
+fn table_cells(line: &str) -> Vec<String> {
+    line.trim_matches('|').split('|').map(|cell| cell.trim().to_string()).collect()
+}
+
+fn render_table(rows: &[Vec<String>], links: &mut Vec<String>) -> Vec<Line<'static>> {
+    let rows: Vec<Vec<Vec<Span<'static>>>> = rows.iter()
+        .map(|row| row.iter().map(|cell| inline_spans(cell, links)).collect())
+        .collect();
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| rows.iter()
+            .filter_map(|row| row.get(column))
+            .map(|cell| cell.iter().map(|span| span.content.width()).sum())
+            .max()
+            .unwrap_or(0))
+        .collect();
+
+    rows.into_iter().enumerate().map(|(i, row)| {
+        let mut spans = vec![];
+        for (column, cell) in row.into_iter().enumerate() {
+            let width: usize = cell.iter().map(|span| span.content.width()).sum();
+            spans.extend(cell);
+            spans.push(Span::raw(" ".repeat(widths[column] - width + 2)));
+        }
+        let line = Line::from(spans);
+        if i == 0 { line.bold() } else { line }
+    }).collect()
+}
+
+/// Splits a line into spans for `**strong**`, `` `code` `` and `[text](url)`, collecting the urls of the links.
+fn inline_spans(text: &str, links: &mut Vec<String>) -> Vec<Span<'static>> {
+    let mut spans = vec![];
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let parsed = if let Some(strong) = rest.strip_prefix("**").and_then(|r| r.split_once("**")) {
+            Some((Span::raw(strong.0.to_string()).bold(), strong.1))
+        } else if let Some(code) = rest.strip_prefix('`').and_then(|r| r.split_once('`')) {
+            Some((Span::raw(code.0.to_string()).gray(), code.1))
+        } else if let Some((label, url, after)) = parse_link(rest) {
+            links.push(url.to_string());
+            Some((Span::raw(format!("{}[{}]", label, links.len())).underlined(), after))
+        } else {
+            None
+        };
+        match parsed {
+            Some((span, after)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::raw(std::mem::take(&mut plain)));
+                }
+                spans.push(span);
+                rest = after;
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::raw(plain));
+    }
+    spans
+}
+
+/// `[label](url)` at the start of the text, returning label, url and the remaining text.
+fn parse_link(text: &str) -> Option<(&str, &str, &str)> {
+    let (label, rest) = text.strip_prefix('[')?.split_once("](")?;
+    let (url, rest) = rest.split_once(')')?;
+    if label.contains(']') || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label, url, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(markdown: &Markdown) -> Vec<String> {
+        markdown.lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn renders_headings_lists_and_links() {
+        let markdown = render_markdown("## [0.4.0](https://example.com/v0.4.0)\n\n### Features\n\n* **ui:** vote arrows\n  * nested `code`\n> quoted\r\n");
+        assert_eq!(text(&markdown), vec![
+            "0.4.0[1]",
+            "",
+            "Features",
+            "",
+            "- ui: vote arrows",
+            "  - nested code",
+            format!("{} quoted", symbols().border.vertical_left).as_str(),
+            "",
+            "[1] https://example.com/v0.4.0",
+        ]);
+        assert_eq!(markdown.headings, vec![(0, 2), (2, 3)]);
+        assert!(markdown.lines[2].style.add_modifier.contains(Modifier::BOLD));
+        assert!(markdown.lines[4].spans[1].style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn renders_code_blocks_and_tables() {
+        let markdown = render_markdown("```toml\nname = \"*me*\"\n```\n| Key | Action |\n|-----|:------:|\n| `q` | Quit |\n");
+        assert_eq!(text(&markdown), vec![
+            "  name = \"*me*\"",
+            "Key  Action  ",
+            "q    Quit    ",
+        ]);
+    }
+
+    #[test]
+    fn leaves_broken_markup_alone() {
+        assert_eq!(text(&render_markdown("a ** b [c] (d) `e")), vec!["a ** b [c] (d) `e"]);
+    }
+
+    #[test]
+    fn highlights_matches() {
+        let line = Line::from(vec![Span::raw("Fix the "), Span::raw("fixture").bold()]);
+        let highlighted = highlight(&line, "FIX");
+        let parts: Vec<&str> = highlighted.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(parts, vec!["Fix", " the ", "fix", "ture"]);
+        assert_eq!(highlighted.spans[0].style.bg, Some(Color::Yellow));
+        assert!(highlighted.spans[2].style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(highlighted.spans[3].style.bg, None);
+    }
+}
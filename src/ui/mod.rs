@@ -1,46 +1,101 @@
-use std::time::Duration;
 use crossterm::event::KeyEvent;
 use enum_iterator::Sequence;
 use ratatui::Frame;
 use ratatui::layout::{Alignment, Rect};
 use ratatui::prelude::*;
-use ratatui::widgets::{Block, BorderType, Paragraph};
+use ratatui::widgets::{Block, Paragraph, Tabs, Wrap};
 
 use crate::app::{App, AppResult};
+use crate::i18n::{tr, tr_args, tr_footer};
+use crate::links::find_urls;
 use crate::models::GamePhase;
+use crate::ui::sanitize::truncate_with_ellipsis;
+use crate::ui::symbols::symbols;
 
 pub use voting::VotingPage;
 pub use history::HistoryPage;
 pub use log::LogPage;
+pub use stats::StatsPage;
+pub use summary::SummaryPage;
+pub use rooms::RoomsPage;
+pub use command::CommandLine;
+pub use keymap::HelpEntry;
+pub use sanitize::sanitize;
+pub use update::UpdateDialog;
 
 mod voting;
 mod log;
 mod history;
+mod stats;
+mod summary;
+mod rooms;
+mod theme;
+mod command;
+mod sanitize;
+mod text_input;
+mod update;
+pub mod markdown;
+pub mod keymap;
+pub mod symbols;
 
 #[derive(Debug, PartialEq, Clone, Copy, Hash, Ord, PartialOrd, Eq, Sequence)]
 pub enum UiPage {
     Voting,
-    Log,
     History,
+    Stats,
+    Log,
+    Rooms,
+    Summary,
+}
+
+impl UiPage {
+    pub fn title(&self) -> &'static str {
+        match self {
+            UiPage::Voting => tr("Voting"),
+            UiPage::History => tr("History"),
+            UiPage::Stats => tr("Stats"),
+            UiPage::Log => tr("Log"),
+            UiPage::Rooms => tr("Rooms"),
+            UiPage::Summary => tr("Summary"),
+        }
+    }
 }
 
 pub enum UIAction {
     Continue,
     ChangeView(UiPage),
+    /// Open the dialog of the available update.
+    ShowUpdate,
     Quit,
 }
 
 pub trait Page {
-    fn render(&mut self, app: &mut App, frame: &mut Frame);
+    fn render(&mut self, app: &mut App, area: Rect, frame: &mut Frame);
     fn input(&mut self, app: &mut App, event: KeyEvent) -> AppResult<UIAction>;
     fn pasted(&mut self, _app: &mut App, _text: String) {}
+    /// Whether the page is currently capturing text input.
+    fn is_editing(&self) -> bool {
+        false
+    }
+    /// Keybindings of the page in its current state, shown in the help overlay.
+    fn keybindings(&self, app: &App) -> Vec<HelpEntry>;
+}
+
+/// One line listing all pages with the current one highlighted.
+pub fn render_tabs(current: UiPage, rect: Rect, frame: &mut Frame) {
+    let tabs = Tabs::new(enum_iterator::all::<UiPage>().map(|p| p.title()))
+        .select(enum_iterator::all::<UiPage>().position(|p| p == current).unwrap_or(0))
+        .style(Style::new().gray())
+        .highlight_style(Style::new().white().bold().reversed())
+        .divider("|");
+    frame.render_widget(tabs, rect);
 }
 
 fn render_box_colored(title: &str, color: Style, rect: Rect, frame: &mut Frame) -> Rect {
     let block = Block::bordered()
         .title(title)
         .title_alignment(Alignment::Left)
-        .border_type(BorderType::Rounded)
+        .border_set(symbols().border)
         .border_style(color);
     let inner = block.inner(rect);
     frame.render_widget(block, rect);
@@ -52,6 +107,7 @@ fn colored_box_style(game_phase: GamePhase) -> Style {
     match game_phase {
         GamePhase::Playing => { Style::new().white() }
         GamePhase::Revealed => { Style::new().light_blue() }
+        GamePhase::Unknown => { Style::new().dark_gray() }
     }
 }
 
@@ -59,45 +115,62 @@ fn render_box(title: &str, rect: Rect, frame: &mut Frame) -> Rect {
     render_box_colored(title, Style::new().white(), rect, frame)
 }
 
-fn trim_name(name: &str) -> &str {
-    let name = name.trim();
-    let mut chars = name.char_indices();
-    let end = chars.nth(25);
-    if let Some((idx, _char)) = end {
-        &name[..idx]
-    } else {
-        name
-    }
-    // todo: escape the name for control chars
+const MAX_NAME_WIDTH: usize = 25;
+
+/// Smallest terminal the pages can be drawn in, below it only a hint to resize is shown.
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 12;
+
+pub fn is_too_small(area: Rect) -> bool {
+    area.width < MIN_WIDTH || area.height < MIN_HEIGHT
+}
+
+pub fn render_too_small(area: Rect, frame: &mut Frame) {
+    let text = vec![
+        Line::from(tr("Terminal too small")).bold(),
+        Line::from(tr_args("{}x{}, needs at least {}x{}", &[
+            &area.width.to_string(), &area.height.to_string(), &MIN_WIDTH.to_string(), &MIN_HEIGHT.to_string(),
+        ])),
+    ];
+    let [center] = Layout::vertical([Constraint::Length(2)]).flex(layout::Flex::Center).areas(area);
+    frame.render_widget(Paragraph::new(text).centered().wrap(Wrap { trim: true }), center);
+}
+
+fn trim_name(name: &str) -> String {
+    let name = sanitize(name);
+    truncate_with_ellipsis(name.trim(), MAX_NAME_WIDTH)
 }
 
-fn render_confirmation_box(prompt: &str, rect: Rect, frame: &mut Frame) {
+/// Splits the first character off a text, underlined as the key of footer entries and confirmations.
+fn split_first(text: &str) -> (&str, &str) {
+    text.char_indices().nth(1).map_or((text, ""), |(index, _)| text.split_at(index))
+}
+
+fn render_confirmation_box(prompt: &'static str, rect: Rect, frame: &mut Frame) {
+    let (yes_key, yes) = split_first(tr("Yes"));
+    let (no_key, no) = split_first(tr("No"));
     let block = Block::bordered()
-        .title("Confirmation")
+        .title(tr("Confirmation"))
         .title_alignment(Alignment::Center)
-        .border_type(BorderType::Rounded);
+        .border_set(symbols().border);
     let inner = block.inner(rect);
     frame.render_widget(block, rect);
 
     let paragraph = Paragraph::new(Line::from(vec![
-        Span::raw(prompt),
-        Span::raw(" Y").bold(),
-        Span::raw("es/"),
-        Span::raw("N").bold(),
-        Span::raw("o"),
+        Span::raw(tr(prompt)),
+        Span::raw(" "),
+        Span::raw(yes_key).bold(),
+        Span::raw(format!("{}/", yes)),
+        Span::raw(no_key).bold(),
+        Span::raw(no),
     ]))
         .alignment(Alignment::Center);
     frame.render_widget(paragraph, inner);
 }
 
-fn footer_entries(entries: Vec<&str>) -> Paragraph {
-    let mut spans: Vec<Span> = entries.iter().flat_map(|item| {
-        let (first, remaining) = if item.char_indices().into_iter().count() > 1 {
-            let split_idx = item.char_indices().nth(1).expect("Unable to split string").0;
-            item.split_at(split_idx)
-        } else {
-            (*item, "")
-        };
+fn footer_entries(entries: Vec<&'static str>) -> Paragraph<'static> {
+    let mut spans: Vec<Span> = entries.into_iter().flat_map(|item| {
+        let (first, remaining) = split_first(tr_footer(item));
         vec![
             Span::raw(" "),
             Span::styled(first, Style::default().add_modifier(Modifier::BOLD).add_modifier(Modifier::UNDERLINED)),
@@ -110,17 +183,75 @@ fn footer_entries(entries: Vec<&str>) -> Paragraph {
     Paragraph::new(vec![Line::from(""), Line::from(spans)])
 }
 
-fn format_duration(duration: &Duration) -> String {
-    let secs = duration.as_secs();
-    let minutes = secs / 60;
-    let hours = minutes / 60;
-    if hours > 1 {
-        format!("{} hours {} minutes", hours, minutes % 60)
-    } else if minutes > 1 {
-        format!("{} minutes {} seconds", minutes, secs % 60)
-    } else if secs >= 100 {
-        format!("{} minute {} seconds", minutes, secs % 60)
+/// Splits text into spans with the links in it underlined.
+fn link_spans(text: String) -> Vec<Span<'static>> {
+    let mut spans = vec![];
+    let mut end = 0;
+    for url in find_urls(&text) {
+        spans.push(Span::raw(text[end..url.start].to_string()));
+        spans.push(Span::raw(text[url.clone()].to_string()).underlined());
+        end = url.end;
+    }
+    spans.push(Span::raw(text[end..].to_string()));
+    spans
+}
+
+/// A link as it was drawn, to draw it again as a hyperlink.
+pub struct DrawnLink {
+    pub x: u16,
+    pub y: u16,
+    pub url: String,
+    pub style: Style,
+}
+
+/// The links drawn in the rows of the buffer. Links running into a border or the edge were cut off and are left out.
+pub fn drawn_links(buffer: &Buffer) -> Vec<DrawnLink> {
+    let area = buffer.area;
+    let mut links = vec![];
+    for y in area.top()..area.bottom() {
+        let mut row = String::new();
+        // column of every byte of the row
+        let mut columns = vec![];
+        for x in area.left()..area.right() {
+            let symbol = buffer.get(x, y).symbol();
+            columns.extend(std::iter::repeat_n(x, symbol.len()));
+            row.push_str(symbol);
+        }
+        for url in find_urls(&row) {
+            let text = &row[url.clone()];
+            let ascii = text.find(|c: char| !c.is_ascii()).map_or(text, |end| &text[..end]);
+            if ascii.len() < text.len() || url.end == row.len() {
+                continue;
+            }
+            let x = columns[url.start];
+            links.push(DrawnLink { x, y, url: text.to_string(), style: buffer.get(x, y).style() });
+        }
+    }
+    links
+}
+
+/// Agreed estimates against the capacity like `34 of 40 points committed`, red once the capacity is exceeded.
+fn capacity_span(committed: f32, capacity: u32) -> Span<'static> {
+    let text = tr_args("{} of {} points committed", &[&committed.to_string(), &capacity.to_string()]);
+    if committed > capacity as f32 {
+        Span::raw(text).red()
     } else {
-        format!("{} seconds", secs)
+        Span::raw(text)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_drawn_links_that_are_complete() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 30, 2));
+        buffer.set_string(0, 0, "see https://a.io/x now", Style::new().underlined());
+        buffer.set_string(0, 1, "https://example.com/long/pat│", Style::new());
+        let links = drawn_links(&buffer);
+        assert_eq!(links.len(), 1);
+        assert_eq!((links[0].x, links[0].y, links[0].url.as_str()), (4, 0, "https://a.io/x"));
+        assert!(links[0].style.add_modifier.contains(Modifier::UNDERLINED));
+    }
+}
@@ -0,0 +1,302 @@
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout};
+use ratatui::prelude::*;
+use ratatui::widgets::{Cell, Clear, Paragraph, Row, Table, TableState, Wrap};
+
+use crate::app::{App, AppResult};
+use crate::i18n::{tr, tr_args};
+use crate::invite::generate_room_name;
+use crate::models::LogLevel;
+use crate::rooms::{fetch_rooms, RoomInfo};
+use crate::ui::keymap::{help, lookup, HelpEntry, KeyBinding};
+use crate::ui::sanitize::sanitize;
+use crate::ui::symbols::symbols;
+use crate::ui::text_input::{TextInput, EDIT_KEYS};
+use crate::ui::voting::render_overview;
+use crate::ui::{footer_entries, render_box, Page, UIAction, UiPage};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum RoomsAction {
+    Quit,
+    Back,
+    Up,
+    Down,
+    Join,
+    Refresh,
+    Create,
+}
+
+const ROOMS_KEYS: [KeyBinding<RoomsAction>; 7] = [
+    KeyBinding::new(&[KeyCode::Up], "UP", "Select the previous room", RoomsAction::Up),
+    KeyBinding::new(&[KeyCode::Down], "DOWN", "Select the next room", RoomsAction::Down),
+    KeyBinding::new(&[KeyCode::Enter], "ENTER", "Join the selected room", RoomsAction::Join),
+    KeyBinding::new(&[KeyCode::Char('r')], "r", "Load the rooms again", RoomsAction::Refresh),
+    KeyBinding::new(&[KeyCode::Char('c')], "c", "Create a new room", RoomsAction::Create),
+    KeyBinding::new(&[KeyCode::Esc, KeyCode::Char('v')], "ESC/v", "Back to voting", RoomsAction::Back),
+    KeyBinding::new(&[KeyCode::Char('q')], "q", "Quit", RoomsAction::Quit),
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum NewRoomAction {
+    Create,
+    Cancel,
+    NextDeck,
+    PreviousDeck,
+}
+
+const NEW_ROOM_KEYS: [KeyBinding<NewRoomAction>; 4] = [
+    KeyBinding::new(&[KeyCode::Enter], "ENTER", "Create and join the room, copying the invite", NewRoomAction::Create),
+    KeyBinding::new(&[KeyCode::Esc], "ESC", "Cancel", NewRoomAction::Cancel),
+    KeyBinding::new(&[KeyCode::Tab], "TAB", "Next deck", NewRoomAction::NextDeck),
+    KeyBinding::new(&[KeyCode::BackTab], "SHIFT+TAB", "Previous deck", NewRoomAction::PreviousDeck),
+];
+
+/// Dialog for a new room, prefilled with a random name.
+struct NewRoom {
+    input: TextInput,
+    /// The deck of the server followed by the deck presets.
+    decks: Vec<Option<String>>,
+    deck: usize,
+}
+
+impl NewRoom {
+    fn new(app: &App) -> Self {
+        let mut input = TextInput::new();
+        input.set_text(generate_room_name());
+        let decks: Vec<Option<String>> = std::iter::once(None)
+            .chain(app.config.deck_names().into_iter().map(Some))
+            .collect();
+        let deck = decks.iter().position(|deck| *deck == app.config.deck).unwrap_or(0);
+        Self { input, decks, deck }
+    }
+
+    fn cycle_deck(&mut self, step: usize) {
+        self.deck = (self.deck + step) % self.decks.len();
+    }
+}
+
+enum Listing {
+    Loading(Receiver<Result<Option<Vec<RoomInfo>>, String>>),
+    Loaded(Vec<RoomInfo>),
+    /// The server has no listing.
+    Unavailable,
+    Failed(String),
+}
+
+/// Active rooms of the server, for servers that list them.
+pub struct RoomsPage {
+    listing: Option<Listing>,
+    rooms_state: TableState,
+    new_room: Option<NewRoom>,
+}
+
+impl RoomsPage {
+    pub fn new() -> Self {
+        Self {
+            listing: None,
+            rooms_state: TableState::default(),
+            new_room: None,
+        }
+    }
+
+    /// Fetches the rooms in the background, the result is picked up while rendering.
+    fn load(&mut self, app: &App) {
+        let server = app.config.server.clone();
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            let _ = sender.send(fetch_rooms(&server).map_err(|e| e.to_string()));
+        });
+        self.listing = Some(Listing::Loading(receiver));
+    }
+
+    fn rooms(&self) -> &[RoomInfo] {
+        match &self.listing {
+            Some(Listing::Loaded(rooms)) => rooms,
+            _ => &[],
+        }
+    }
+
+    fn new_room_input(&mut self, app: &mut App, event: KeyEvent) -> UIAction {
+        let Some(dialog) = &mut self.new_room else {
+            return UIAction::Continue;
+        };
+        match lookup(&NEW_ROOM_KEYS, &event) {
+            Some(NewRoomAction::Cancel) => self.new_room = None,
+            Some(NewRoomAction::NextDeck) => dialog.cycle_deck(1),
+            Some(NewRoomAction::PreviousDeck) => dialog.cycle_deck(dialog.decks.len() - 1),
+            Some(NewRoomAction::Create) => {
+                let room = dialog.input.text().trim().to_string();
+                if room.is_empty() {
+                    return UIAction::Continue;
+                }
+                let deck = dialog.decks[dialog.deck].clone();
+                self.new_room = None;
+                return match app.create_room(room.clone(), deck) {
+                    Ok(()) => UIAction::ChangeView(UiPage::Voting),
+                    Err(e) => {
+                        app.log_message(LogLevel::Error, tr_args("Failed to create room {}: {}", &[&room, &e.to_string()]));
+                        UIAction::Continue
+                    }
+                };
+            }
+            None => {
+                dialog.input.input(&event);
+            }
+        }
+        UIAction::Continue
+    }
+}
+
+/// Popup with the name and the deck of the new room.
+fn render_new_room(dialog: &mut NewRoom, area: Rect, frame: &mut Frame) {
+    let [popup] = Layout::vertical([Constraint::Length(5)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center).areas(popup);
+    frame.render_widget(Clear, popup);
+    let inner = render_box(tr("New room"), popup, frame);
+
+    let [name, deck, hint] = Layout::vertical([Constraint::Length(1); 3]).areas(inner);
+    let [label, input] = Layout::horizontal([Constraint::Length(6), Constraint::Fill(1)]).areas(name);
+    frame.render_widget(Paragraph::new(tr("Name:")), label);
+    dialog.input.render(input, frame);
+    frame.render_widget(Paragraph::new(Line::from(vec![
+        Span::raw(tr("Deck: ")),
+        Span::raw(dialog.decks[dialog.deck].clone().unwrap_or(tr("deck of the server").to_string())).bold(),
+        Span::raw(tr(" (Tab to change)")).gray(),
+    ])), deck);
+    frame.render_widget(Paragraph::new(tr("Enter creates the room and copies the invite")).gray(), hint);
+}
+
+impl Page for RoomsPage {
+    fn render(&mut self, app: &mut App, area: Rect, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+        ]).areas(area);
+        render_overview(app, header, frame);
+
+        if self.listing.is_none() {
+            self.load(app);
+        }
+        if let Some(Listing::Loading(receiver)) = &self.listing {
+            if let Ok(result) = receiver.try_recv() {
+                self.listing = Some(match result {
+                    Ok(Some(rooms)) => Listing::Loaded(rooms),
+                    Ok(None) => Listing::Unavailable,
+                    Err(e) => Listing::Failed(e),
+                });
+            }
+        }
+        let count = self.rooms().len();
+        if count == 0 {
+            self.rooms_state.select(None);
+        } else if self.rooms_state.selected().is_none_or(|s| s >= count) {
+            self.rooms_state.select(Some(0));
+        }
+
+        let inner = render_box(tr("Rooms"), body, frame);
+        let message = match &self.listing {
+            Some(Listing::Loading(_)) | None => Some(tr("Loading the rooms...").to_string()),
+            Some(Listing::Loaded(rooms)) if rooms.is_empty() => Some(tr("There are no active rooms.").to_string()),
+            Some(Listing::Loaded(_)) => None,
+            Some(Listing::Unavailable) => Some(tr("This server doesn't list its rooms, use :room <room> instead.").to_string()),
+            Some(Listing::Failed(e)) => Some(tr_args("Failed to load the rooms: {}", &[&e.to_string()])),
+        };
+        if let Some(message) = message {
+            frame.render_widget(Paragraph::new(message).gray().wrap(Wrap { trim: true }), inner);
+        } else {
+            let rows: Vec<Row> = self.rooms().iter().map(|room| {
+                let current = room.name == app.room.name;
+                let name = Cell::from(sanitize(&room.name));
+                Row::new(vec![
+                    if current { name.bold() } else { name },
+                    Cell::from(room.participants.map_or("-".to_string(), |count| count.to_string())),
+                ])
+            }).collect();
+            let table = Table::new(rows, [Constraint::Fill(1), Constraint::Length(12)])
+                .column_spacing(2)
+                .header(Row::new(vec![tr("Room"), tr("Participants")])
+                    .style(Style::new().bold())
+                    .bottom_margin(1))
+                .highlight_symbol("> ")
+                .highlight_style(Style::new().on_white().black());
+            frame.render_stateful_widget(table, inner, &mut self.rooms_state);
+        }
+
+        frame.render_widget(footer_entries(vec![symbols().up, symbols().down, "Enter join", "Refresh", "Create", "Voting", "Quit"]), footer);
+
+        if let Some(dialog) = &mut self.new_room {
+            render_new_room(dialog, area, frame);
+        }
+    }
+
+    fn input(&mut self, app: &mut App, event: KeyEvent) -> AppResult<UIAction> {
+        if self.new_room.is_some() {
+            return Ok(self.new_room_input(app, event));
+        }
+        let count = self.rooms().len();
+        Ok(match lookup(&ROOMS_KEYS, &event) {
+            Some(RoomsAction::Quit) => UIAction::Quit,
+            Some(RoomsAction::Back) => UIAction::ChangeView(UiPage::Voting),
+            Some(RoomsAction::Up) => {
+                if let Some(s) = self.rooms_state.selected() {
+                    self.rooms_state.select(Some(s.saturating_sub(1)));
+                }
+                UIAction::Continue
+            }
+            Some(RoomsAction::Down) => {
+                if let Some(s) = self.rooms_state.selected() {
+                    self.rooms_state.select(Some((s + 1).min(count.saturating_sub(1))));
+                }
+                UIAction::Continue
+            }
+            Some(RoomsAction::Refresh) => {
+                self.load(app);
+                UIAction::Continue
+            }
+            Some(RoomsAction::Create) => {
+                self.new_room = Some(NewRoom::new(app));
+                UIAction::Continue
+            }
+            Some(RoomsAction::Join) => {
+                let Some(room) = self.rooms_state.selected().and_then(|s| self.rooms().get(s)) else {
+                    return Ok(UIAction::Continue);
+                };
+                let room = room.name.clone();
+                if room == app.room.name {
+                    return Ok(UIAction::ChangeView(UiPage::Voting));
+                }
+                match app.join_room(room.clone()) {
+                    Ok(()) => UIAction::ChangeView(UiPage::Voting),
+                    Err(e) => {
+                        app.log_message(LogLevel::Error, tr_args("Failed to join room {}: {}", &[&room, &e.to_string()]));
+                        UIAction::Continue
+                    }
+                }
+            }
+            None => UIAction::Continue,
+        })
+    }
+
+    fn pasted(&mut self, _app: &mut App, text: String) {
+        if let Some(dialog) = &mut self.new_room {
+            dialog.input.pasted(&text);
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        self.new_room.is_some()
+    }
+
+    fn keybindings(&self, _app: &App) -> Vec<HelpEntry> {
+        if self.new_room.is_some() {
+            [help(&NEW_ROOM_KEYS), help(&EDIT_KEYS)].concat()
+        } else {
+            help(&ROOMS_KEYS)
+        }
+    }
+}
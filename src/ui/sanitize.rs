@@ -0,0 +1,95 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::ui::symbols::symbols;
+
+/// Makes text from other users safe to render.
+/// Control characters could move the cursor or garble the terminal, and bidi overrides and
+/// zero-width characters can make a name look like another one.
+pub fn sanitize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !is_invisible(*c))
+        .filter_map(|c| match c {
+            '\t' | '\n' | '\r' => Some(' '),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+/// Turns pasted text into a single line for the text inputs, line breaks become spaces.
+pub fn single_line(text: &str) -> String {
+    let text = text.trim_end_matches(['\r', '\n']).replace("\r\n", "\n");
+    sanitize(&text)
+}
+
+/// Bidi controls and zero-width characters. The zero-width joiner is kept, emoji sequences need it.
+fn is_invisible(c: char) -> bool {
+    matches!(c,
+        '\u{061C}'
+        | '\u{200B}' | '\u{200C}' | '\u{200E}' | '\u{200F}'
+        | '\u{202A}'..='\u{202E}'
+        | '\u{2060}'..='\u{2069}'
+        | '\u{FEFF}')
+}
+
+/// Number of terminal columns the text takes up.
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// Longest prefix of `text` that fits into `max_width` terminal columns, never splitting a grapheme.
+pub fn truncate_to_width(text: &str, max_width: usize) -> &str {
+    let mut width = 0;
+    for (idx, grapheme) in text.grapheme_indices(true) {
+        width += grapheme.width();
+        if width > max_width {
+            return &text[..idx];
+        }
+    }
+    text
+}
+
+/// Like `truncate_to_width`, but ends cut text with an ellipsis.
+pub fn truncate_with_ellipsis(text: &str, max_width: usize) -> String {
+    if display_width(text) <= max_width {
+        text.to_string()
+    } else {
+        format!("{}{}", truncate_to_width(text, max_width.saturating_sub(1)), symbols().ellipsis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_control_and_bidi_characters() {
+        assert_eq!(sanitize("evil\u{1b}[2Jname"), "evil[2Jname");
+        assert_eq!(sanitize("multi\nline"), "multi line");
+        assert_eq!(sanitize("\u{202E}nimda\u{200B}"), "nimda");
+        assert_eq!(sanitize("👨\u{200D}👩"), "👨\u{200D}👩");
+    }
+
+    #[test]
+    fn pastes_multiple_lines_as_one() {
+        assert_eq!(single_line("first\r\nsecond\nthird\r\n"), "first second third");
+        assert_eq!(single_line("tab\tand\u{7}bell\n"), "tab andbell");
+    }
+
+    #[test]
+    fn truncates_by_display_width() {
+        assert_eq!(truncate_to_width("abcdef", 4), "abcd");
+        assert_eq!(truncate_to_width("日本語", 5), "日本");
+        assert_eq!(truncate_to_width("ab", 4), "ab");
+        assert_eq!(truncate_to_width("e\u{301}e\u{301}", 1), "e\u{301}");
+    }
+
+    #[test]
+    fn ellipsis_fits_into_width() {
+        assert_eq!(truncate_with_ellipsis("short", 5), "short");
+        assert_eq!(truncate_with_ellipsis("longer name", 5), "long…");
+        assert_eq!(truncate_with_ellipsis("山田太郎さん", 7), "山田太…");
+        assert_eq!(display_width(&truncate_with_ellipsis("😀😀😀😀", 6)), 5);
+    }
+}
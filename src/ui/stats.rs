@@ -0,0 +1,181 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout};
+use ratatui::prelude::*;
+use ratatui::widgets::{Cell, Clear, Row, Table, TableState};
+
+use crate::app::{App, AppResult};
+use crate::i18n::{tr, tr_args};
+use crate::stats::{committed_points, PlayerStats};
+use crate::ui::keymap::{help, lookup, HelpEntry, KeyBinding};
+use crate::ui::theme::player_style;
+use crate::ui::voting::{format_vote, render_overview};
+use crate::ui::sanitize::sanitize;
+use crate::ui::symbols::symbols;
+use crate::ui::{capacity_span, footer_entries, render_box, trim_name, Page, UIAction, UiPage};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum StatsAction {
+    Quit,
+    Back,
+    Up,
+    Down,
+    Details,
+}
+
+const STATS_KEYS: [KeyBinding<StatsAction>; 5] = [
+    KeyBinding::new(&[KeyCode::Up], "UP", "Select the previous player", StatsAction::Up),
+    KeyBinding::new(&[KeyCode::Down], "DOWN", "Select the next player", StatsAction::Down),
+    KeyBinding::new(&[KeyCode::Enter], "ENTER", "Show the votes of the selected player", StatsAction::Details),
+    KeyBinding::new(&[KeyCode::Char('v'), KeyCode::Char('h')], "h/v", "Back to voting", StatsAction::Back),
+    KeyBinding::new(&[KeyCode::Esc, KeyCode::Char('q')], "ESC/q", "Close the details or quit", StatsAction::Quit),
+];
+
+pub struct StatsPage {
+    players_state: TableState,
+    show_details: bool,
+}
+
+impl StatsPage {
+    pub fn new() -> Self {
+        Self {
+            players_state: TableState::default(),
+            show_details: false,
+        }
+    }
+}
+
+/// Players with stats, the most active first.
+fn sorted_stats(app: &App) -> Vec<&PlayerStats> {
+    let mut stats: Vec<&PlayerStats> = app.player_stats.values().collect();
+    stats.sort_by(|a, b| b.rounds.cmp(&a.rounds).then_with(|| a.name.cmp(&b.name)));
+    stats
+}
+
+impl Page for StatsPage {
+    fn render(&mut self, app: &mut App, area: Rect, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+        ]).areas(area);
+        render_overview(app, header, frame);
+
+        let stats = sorted_stats(app);
+        if self.players_state.selected().is_none() && !stats.is_empty() {
+            self.players_state.select(Some(0));
+        }
+
+        let inner = render_box(tr("Player stats"), body, frame);
+        let consensus = app.history.iter().filter(|e| e.consensus).count();
+        let [summary, network, table] = Layout::vertical([Constraint::Length(1), Constraint::Length(2), Constraint::Fill(1)]).areas(inner);
+        let mut line = Line::from(tr_args("{} rounds, {} ended in consensus", &[&app.history.len().to_string(), &consensus.to_string()]));
+        if let Some(capacity) = app.config.capacity {
+            line.push_span(Span::raw(", "));
+            line.push_span(capacity_span(committed_points(&app.history), capacity));
+        }
+        frame.render_widget(line, summary);
+        let metrics = &app.metrics;
+        let errors = Span::raw(tr_args("{} server errors", &[&metrics.server_errors.to_string()]));
+        let mut line = Line::from(vec![
+            Span::raw(tr_args("{} room updates received, ", &[&metrics.messages_received.to_string()])),
+            if metrics.server_errors > 0 { errors.red() } else { errors },
+        ]);
+        if let Some(latency) = app.latency() {
+            line.push_span(Span::raw(tr_args(", ping {}/{}/{}ms min/avg/max, {}ms jitter", &[
+                &latency.min.as_millis().to_string(),
+                &latency.avg.as_millis().to_string(),
+                &latency.max.as_millis().to_string(),
+                &latency.jitter().as_millis().to_string(),
+            ])));
+        }
+        frame.render_widget(line.gray(), network);
+
+        let rows: Vec<Row> = stats.iter().map(|s| {
+            Row::new(vec![
+                Cell::from(Span::styled(trim_name(&s.name), player_style(&s.name, app.config.theme))),
+                Cell::from(s.rounds.to_string()),
+                Cell::from(s.average().map_or("-".to_string(), |a| format!("{:.1}", a))),
+                Cell::from(s.highest.to_string()),
+                Cell::from(s.lowest.to_string()),
+            ])
+        }).collect();
+        let widths = [Constraint::Fill(1), Constraint::Length(7), Constraint::Length(8), Constraint::Length(8), Constraint::Length(8)];
+        let players = Table::new(rows, widths)
+            .column_spacing(2)
+            .header(Row::new(vec![tr("Name"), tr("Rounds"), tr("Average"), tr("Highest"), tr("Lowest")])
+                .style(Style::new().bold())
+                .bottom_margin(1))
+            .highlight_symbol("> ")
+            .highlight_style(Style::new().on_white().black());
+        frame.render_stateful_widget(players, table, &mut self.players_state);
+
+        frame.render_widget(footer_entries(vec!["Vote", symbols().up, symbols().down, "Enter details", "Quit"]), footer);
+
+        if self.show_details {
+            if let Some(player) = self.players_state.selected().and_then(|i| stats.get(i)) {
+                render_details(app, player, area, frame);
+            }
+        }
+    }
+
+    fn input(&mut self, app: &mut App, event: KeyEvent) -> AppResult<UIAction> {
+        let count = app.player_stats.len();
+        Ok(match lookup(&STATS_KEYS, &event) {
+            Some(StatsAction::Quit) if self.show_details => {
+                self.show_details = false;
+                UIAction::Continue
+            }
+            Some(StatsAction::Quit) => UIAction::Quit,
+            Some(StatsAction::Back) => UIAction::ChangeView(UiPage::Voting),
+            Some(StatsAction::Up) => {
+                if let Some(s) = self.players_state.selected() {
+                    self.players_state.select(Some(s.saturating_sub(1)));
+                }
+                UIAction::Continue
+            }
+            Some(StatsAction::Down) => {
+                if let Some(s) = self.players_state.selected() {
+                    self.players_state.select(Some((s + 1).min(count.saturating_sub(1))));
+                }
+                UIAction::Continue
+            }
+            Some(StatsAction::Details) => {
+                self.show_details = !self.show_details && count > 0;
+                UIAction::Continue
+            }
+            None => UIAction::Continue,
+        })
+    }
+
+    fn keybindings(&self, _app: &App) -> Vec<HelpEntry> {
+        help(&STATS_KEYS)
+    }
+}
+
+/// Popup listing the votes of a player in every round.
+fn render_details(app: &App, player: &PlayerStats, area: Rect, frame: &mut Frame) {
+    let height = (app.history.len() as u16 + 6).min(area.height);
+    let [popup] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(area);
+    let [popup] = Layout::horizontal([Constraint::Percentage(50)]).flex(Flex::Center).areas(popup);
+    frame.render_widget(Clear, popup);
+    let inner = render_box(&trim_name(&player.name), popup, frame);
+
+    let rows: Vec<Row> = app.history.iter().map(|entry| {
+        let vote = entry.votes.iter()
+            .find(|p| p.key() == player.key)
+            .map_or(Span::raw("-").gray(), |p| format_vote(&p.vote, None, app.config.theme, None));
+        Row::new(vec![
+            Cell::from(entry.round_number.to_string()),
+            Cell::from(vote),
+            Cell::from(format!("{:.1}", entry.average)),
+            Cell::from(sanitize(entry.topic.as_deref().unwrap_or_default())),
+        ])
+    }).collect();
+    let table = Table::new(rows, [Constraint::Length(5), Constraint::Length(6), Constraint::Length(8), Constraint::Fill(1)])
+        .column_spacing(2)
+        .header(Row::new(vec![tr("Round"), tr("Vote"), tr("Average"), tr("Topic")])
+            .style(Style::new().bold())
+            .bottom_margin(1));
+    frame.render_widget(table, inner);
+}
@@ -0,0 +1,177 @@
+use std::fs;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::prelude::*;
+use ratatui::widgets::{Cell, Row, Table, TableState};
+
+use crate::app::{App, AppResult};
+use crate::export::{export_session_html, export_session_summary};
+use crate::format::format_duration;
+use crate::i18n::{tr, tr_args};
+use crate::stats::session_report;
+use crate::ui::keymap::{help, lookup, HelpEntry, KeyBinding};
+use crate::ui::sanitize::sanitize;
+use crate::ui::symbols::symbols;
+use crate::ui::voting::render_overview;
+use crate::ui::{capacity_span, footer_entries, render_box, Page, UIAction, UiPage};
+
+/// Rounds listed as the slowest discussions.
+const SLOWEST_ROUNDS: usize = 3;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum SummaryAction {
+    Quit,
+    Back,
+    Up,
+    Down,
+    ExportMarkdown,
+    ExportHtml,
+}
+
+const SUMMARY_KEYS: [KeyBinding<SummaryAction>; 6] = [
+    KeyBinding::new(&[KeyCode::Up], "UP", "Select the previous round", SummaryAction::Up),
+    KeyBinding::new(&[KeyCode::Down], "DOWN", "Select the next round", SummaryAction::Down),
+    KeyBinding::new(&[KeyCode::Char('m')], "m", "Export the summary as markdown", SummaryAction::ExportMarkdown),
+    KeyBinding::new(&[KeyCode::Char('w')], "w", "Export the summary as html", SummaryAction::ExportHtml),
+    KeyBinding::new(&[KeyCode::Esc, KeyCode::Char('v')], "ESC/v", "Back to voting", SummaryAction::Back),
+    KeyBinding::new(&[KeyCode::Char('q'), KeyCode::Enter], "q/ENTER", "Quit", SummaryAction::Quit),
+];
+
+/// Report of the whole session, shown before quitting.
+pub struct SummaryPage {
+    rounds_state: TableState,
+    /// Result of the last export.
+    status: Option<String>,
+}
+
+impl SummaryPage {
+    pub fn new() -> Self {
+        Self {
+            rounds_state: TableState::default(),
+            status: None,
+        }
+    }
+
+    /// Writes the summary into the summary directory, or the working directory if none is configured.
+    fn export(&mut self, app: &App, html: bool) {
+        let dir = app.config.summary_dir.as_deref();
+        let result = dir.map_or(Ok(()), fs::create_dir_all).map_err(Into::into).and_then(|_| {
+            if html {
+                export_session_html(&app.history, &app.room.name, &app.session_notes(), dir)
+            } else {
+                export_session_summary(&app.history, &app.room.name, &app.session_notes(), dir)
+            }
+        });
+        self.status = Some(match result {
+            Ok(path) => tr_args("Exported to {}", &[&path.display().to_string()]),
+            Err(e) => tr_args("Failed to export: {}", &[&e.to_string()]),
+        });
+    }
+}
+
+impl Page for SummaryPage {
+    fn render(&mut self, app: &mut App, area: Rect, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+        ]).areas(area);
+        render_overview(app, header, frame);
+
+        if self.rounds_state.selected().is_none() && !app.history.is_empty() {
+            self.rounds_state.select(Some(0));
+        }
+
+        let report = session_report(&app.history, SLOWEST_ROUNDS);
+        let inner = render_box(tr("Session summary"), body, frame);
+        let [figures, status, tables] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ]).areas(inner);
+        let consensus = report.consensus_rate().map_or("-".to_string(), |rate| format!("{}%", rate));
+        let mut line = Line::from(tr_args(
+            "{} rounds, {} consensus, {} in total",
+            &[&report.rounds.to_string(), &consensus, &format_duration(&report.total)],
+        ));
+        if let Some(capacity) = app.config.capacity {
+            line.push_span(Span::raw(", "));
+            line.push_span(capacity_span(report.committed, capacity));
+        }
+        frame.render_widget(line, figures);
+        if let Some(text) = &self.status {
+            frame.render_widget(Line::from(text.as_str()).gray(), status);
+        }
+
+        let [rounds, slowest] = Layout::horizontal([
+            Constraint::Percentage(60),
+            Constraint::Percentage(40),
+        ]).areas(tables);
+        let rows: Vec<Row> = app.history.iter().map(|entry| {
+            Row::new(vec![
+                Cell::from(entry.round_number.to_string()),
+                Cell::from(format!("{:.1}", entry.average)),
+                Cell::from(tr(if entry.consensus { "yes" } else { "no" })),
+                Cell::from(sanitize(entry.topic.as_deref().unwrap_or_default())),
+            ])
+        }).collect();
+        let table = Table::new(rows, [Constraint::Length(5), Constraint::Length(8), Constraint::Length(9), Constraint::Fill(1)])
+            .column_spacing(2)
+            .header(Row::new(vec![tr("Round"), tr("Average"), tr("Consensus"), tr("Topic")])
+                .style(Style::new().bold())
+                .bottom_margin(1))
+            .highlight_symbol("> ")
+            .highlight_style(Style::new().on_white().black());
+        frame.render_stateful_widget(table, rounds, &mut self.rounds_state);
+
+        let rows: Vec<Row> = report.slowest.iter().map(|entry| {
+            Row::new(vec![
+                Cell::from(entry.round_number.to_string()),
+                Cell::from(format_duration(&entry.length)),
+            ])
+        }).collect();
+        let table = Table::new(rows, [Constraint::Length(5), Constraint::Fill(1)])
+            .column_spacing(2)
+            .header(Row::new(vec![tr("Round"), tr("Slowest discussions")])
+                .style(Style::new().bold())
+                .bottom_margin(1));
+        frame.render_widget(table, slowest);
+
+        frame.render_widget(footer_entries(vec![symbols().up, symbols().down, "Markdown", "Web page", "Voting", "Quit"]), footer);
+    }
+
+    fn input(&mut self, app: &mut App, event: KeyEvent) -> AppResult<UIAction> {
+        let count = app.history.len();
+        Ok(match lookup(&SUMMARY_KEYS, &event) {
+            Some(SummaryAction::Quit) => UIAction::Quit,
+            Some(SummaryAction::Back) => UIAction::ChangeView(UiPage::Voting),
+            Some(SummaryAction::Up) => {
+                if let Some(s) = self.rounds_state.selected() {
+                    self.rounds_state.select(Some(s.saturating_sub(1)));
+                }
+                UIAction::Continue
+            }
+            Some(SummaryAction::Down) => {
+                if let Some(s) = self.rounds_state.selected() {
+                    self.rounds_state.select(Some((s + 1).min(count.saturating_sub(1))));
+                }
+                UIAction::Continue
+            }
+            Some(SummaryAction::ExportMarkdown) => {
+                self.export(app, false);
+                UIAction::Continue
+            }
+            Some(SummaryAction::ExportHtml) => {
+                self.export(app, true);
+                UIAction::Continue
+            }
+            None => UIAction::Continue,
+        })
+    }
+
+    fn keybindings(&self, _app: &App) -> Vec<HelpEntry> {
+        help(&SUMMARY_KEYS)
+    }
+}
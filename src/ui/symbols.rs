@@ -0,0 +1,127 @@
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use ratatui::symbols::{bar, border};
+
+static ASCII: AtomicBool = AtomicBool::new(false);
+
+/// The characters the UI draws with, apart from the text itself.
+pub struct Symbols {
+    pub border: border::Set,
+    pub bar: bar::Set,
+    pub dot: &'static str,
+    pub empty_dot: &'static str,
+    pub higher: &'static str,
+    pub lower: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    pub ellipsis: &'static str,
+    pub separator: &'static str,
+    /// Marks the players who have voted.
+    pub check: &'static str,
+    /// One per `Reaction`, in the order of `Reaction::ALL`.
+    pub reactions: [&'static str; 4],
+    /// Big text like the average needs block characters, plain ascii shows it as normal text instead.
+    pub big_text: bool,
+}
+
+const UNICODE: Symbols = Symbols {
+    border: border::ROUNDED,
+    bar: bar::NINE_LEVELS,
+    dot: "●",
+    empty_dot: "○",
+    higher: "▲",
+    lower: "▼",
+    up: "↑",
+    down: "↓",
+    ellipsis: "…",
+    separator: "·",
+    check: "✓",
+    reactions: ["👍", "👎", "😮", "🤔"],
+    big_text: true,
+};
+
+const ASCII_ONLY: Symbols = Symbols {
+    border: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
+    bar: bar::Set {
+        full: "#",
+        seven_eighths: "#",
+        three_quarters: "#",
+        five_eighths: "#",
+        half: "=",
+        three_eighths: "=",
+        one_quarter: "-",
+        one_eighth: "-",
+        empty: " ",
+    },
+    dot: "*",
+    empty_dot: ".",
+    higher: "^",
+    lower: "v",
+    up: "Up",
+    down: "Down",
+    ellipsis: "~",
+    separator: "-",
+    check: "x",
+    reactions: ["+1", "-1", ":o", "hmm"],
+    big_text: false,
+};
+
+/// Switches the whole UI to plain ascii. Called once on startup.
+pub fn set_ascii(ascii: bool) {
+    ASCII.store(ascii, Ordering::Relaxed);
+}
+
+pub fn symbols() -> &'static Symbols {
+    if ASCII.load(Ordering::Relaxed) {
+        &ASCII_ONLY
+    } else {
+        &UNICODE
+    }
+}
+
+/// Guesses from `TERM` and the locale whether the terminal can't show unicode.
+pub fn detect_ascii() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"].iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty());
+    needs_ascii(&term, locale.as_deref())
+}
+
+fn needs_ascii(term: &str, locale: Option<&str>) -> bool {
+    if matches!(term, "linux" | "vt100" | "vt102" | "vt220" | "dumb") {
+        return true;
+    }
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            !(locale.contains("utf-8") || locale.contains("utf8"))
+        }
+        // Windows has no locale variables, its terminals handle unicode.
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_limited_terminals() {
+        assert!(needs_ascii("linux", Some("en_US.UTF-8")));
+        assert!(needs_ascii("xterm-256color", Some("C")));
+        assert!(needs_ascii("xterm", Some("de_DE.ISO-8859-1")));
+        assert!(!needs_ascii("xterm-256color", Some("en_US.utf8")));
+        assert!(!needs_ascii("", None));
+    }
+}
@@ -0,0 +1,457 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::prelude::*;
+use ratatui::widgets::Paragraph;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::ui::keymap::{lookup, KeyBinding};
+use crate::ui::sanitize::{display_width, single_line, truncate_to_width};
+
+const KILL_RING_SIZE: usize = 8;
+const HISTORY_SIZE: usize = 100;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EditAction {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Start,
+    End,
+    DeleteBack,
+    DeleteForward,
+    KillToSpace,
+    KillWordBack,
+    KillWordForward,
+    KillToStart,
+    KillToEnd,
+    Yank,
+    YankPop,
+}
+
+/// Readline style editing keys, shared by all text inputs.
+pub const EDIT_KEYS: [KeyBinding<EditAction>; 19] = [
+    KeyBinding::new(&[KeyCode::Left], "LEFT/RIGHT", "Move the cursor", EditAction::Left),
+    KeyBinding::new(&[KeyCode::Right], "", "", EditAction::Right),
+    KeyBinding::new(&[KeyCode::Char('b')], "Alt+B/F", "Move the cursor by words", EditAction::WordLeft)
+        .with_modifiers(KeyModifiers::ALT),
+    KeyBinding::new(&[KeyCode::Char('f')], "", "", EditAction::WordRight)
+        .with_modifiers(KeyModifiers::ALT),
+    KeyBinding::new(&[KeyCode::Home], "HOME/Ctrl+A", "Jump to the start of the line", EditAction::Start),
+    KeyBinding::new(&[KeyCode::Char('a')], "", "", EditAction::Start)
+        .with_modifiers(KeyModifiers::CONTROL),
+    KeyBinding::new(&[KeyCode::End], "END/Ctrl+E", "Jump to the end of the line", EditAction::End),
+    KeyBinding::new(&[KeyCode::Char('e')], "", "", EditAction::End)
+        .with_modifiers(KeyModifiers::CONTROL),
+    KeyBinding::new(&[KeyCode::Backspace], "BACKSPACE", "Delete the character before the cursor", EditAction::DeleteBack),
+    KeyBinding::new(&[KeyCode::Delete], "DEL/Ctrl+D", "Delete the character under the cursor", EditAction::DeleteForward),
+    KeyBinding::new(&[KeyCode::Char('d')], "", "", EditAction::DeleteForward)
+        .with_modifiers(KeyModifiers::CONTROL),
+    KeyBinding::new(&[KeyCode::Char('w')], "Ctrl+W", "Cut up to the previous space", EditAction::KillToSpace)
+        .with_modifiers(KeyModifiers::CONTROL),
+    KeyBinding::new(&[KeyCode::Backspace], "Alt+BACKSPACE", "Cut the word before the cursor", EditAction::KillWordBack)
+        .with_modifiers(KeyModifiers::ALT),
+    KeyBinding::new(&[KeyCode::Char('d')], "Alt+D", "Cut the word after the cursor", EditAction::KillWordForward)
+        .with_modifiers(KeyModifiers::ALT),
+    KeyBinding::new(&[KeyCode::Delete], "", "", EditAction::KillWordForward)
+        .with_modifiers(KeyModifiers::ALT),
+    KeyBinding::new(&[KeyCode::Char('u')], "Ctrl+U", "Cut to the start of the line", EditAction::KillToStart)
+        .with_modifiers(KeyModifiers::CONTROL),
+    KeyBinding::new(&[KeyCode::Char('k')], "Ctrl+K", "Cut to the end of the line", EditAction::KillToEnd)
+        .with_modifiers(KeyModifiers::CONTROL),
+    KeyBinding::new(&[KeyCode::Char('y')], "Ctrl+Y", "Paste the last cut text", EditAction::Yank)
+        .with_modifiers(KeyModifiers::CONTROL),
+    KeyBinding::new(&[KeyCode::Char('y')], "Alt+Y", "Swap the pasted text for an older cut", EditAction::YankPop)
+        .with_modifiers(KeyModifiers::ALT),
+];
+
+/// Single line text input with readline style editing and a small kill ring.
+/// The kill ring outlives the edited text, so text cut in one input can be yanked into the next.
+#[derive(Debug, Default)]
+pub struct TextInput {
+    text: String,
+    /// Byte offset of the cursor, always on a char boundary.
+    cursor: usize,
+    /// Byte offset of the first visible grapheme, when the text is wider than the input.
+    offset: usize,
+    kill_ring: Vec<String>,
+    /// Start of the text inserted by the last yank and the kill ring entry it came from.
+    last_yank: Option<(usize, usize)>,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Number of user perceived characters, wide glyphs and emoji count as one.
+    pub fn char_count(&self) -> usize {
+        self.text.graphemes(true).count()
+    }
+
+    /// Replaces the text and moves the cursor to its end.
+    pub fn set_text(&mut self, text: String) {
+        self.cursor = text.len();
+        self.offset = 0;
+        self.text = text;
+        self.last_yank = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.set_text(String::new());
+    }
+
+    pub fn insert_str(&mut self, text: &str) {
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+        self.last_yank = None;
+    }
+
+    pub fn pasted(&mut self, text: &str) {
+        self.insert_str(&single_line(text));
+    }
+
+    /// Applies an editing key, returns false if the key doesn't edit text.
+    pub fn input(&mut self, event: &KeyEvent) -> bool {
+        if let Some(action) = lookup(&EDIT_KEYS, event) {
+            self.apply(action);
+            return true;
+        }
+        match event.code {
+            KeyCode::Char(c) if event.modifiers.difference(KeyModifiers::SHIFT).is_empty() => {
+                self.insert_str(c.encode_utf8(&mut [0; 4]));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn apply(&mut self, action: EditAction) {
+        let last_yank = self.last_yank.take();
+        match action {
+            EditAction::Left => self.cursor = self.previous_boundary(self.cursor),
+            EditAction::Right => self.cursor = self.next_boundary(self.cursor),
+            EditAction::WordLeft => self.cursor = self.word_start(char::is_alphanumeric),
+            EditAction::WordRight => self.cursor = self.word_end(char::is_alphanumeric),
+            EditAction::Start => self.cursor = 0,
+            EditAction::End => self.cursor = self.text.len(),
+            EditAction::DeleteBack => {
+                let start = self.previous_boundary(self.cursor);
+                self.text.replace_range(start..self.cursor, "");
+                self.cursor = start;
+            }
+            EditAction::DeleteForward => {
+                let end = self.next_boundary(self.cursor);
+                self.text.replace_range(self.cursor..end, "");
+            }
+            // like in a shell, Ctrl+W cuts up to the previous whitespace while Alt+Backspace stops at punctuation
+            EditAction::KillToSpace => self.kill(self.word_start(|c| !c.is_whitespace()), self.cursor),
+            EditAction::KillWordBack => self.kill(self.word_start(char::is_alphanumeric), self.cursor),
+            EditAction::KillWordForward => self.kill(self.cursor, self.word_end(char::is_alphanumeric)),
+            EditAction::KillToStart => self.kill(0, self.cursor),
+            EditAction::KillToEnd => self.kill(self.cursor, self.text.len()),
+            EditAction::Yank => {
+                if let Some(text) = self.kill_ring.last().cloned() {
+                    let start = self.cursor;
+                    self.insert_str(&text);
+                    self.last_yank = Some((start, self.kill_ring.len() - 1));
+                }
+            }
+            EditAction::YankPop => {
+                // only valid right after a yank, cycles through older cuts
+                if let Some((start, index)) = last_yank {
+                    let index = index.checked_sub(1).unwrap_or(self.kill_ring.len() - 1);
+                    let text = self.kill_ring[index].clone();
+                    self.text.replace_range(start..self.cursor, &text);
+                    self.cursor = start + text.len();
+                    self.last_yank = Some((start, index));
+                }
+            }
+        }
+    }
+
+    /// Text between the previous whitespace and the cursor.
+    pub fn current_word(&self) -> &str {
+        let before = &self.text[..self.cursor];
+        &before[before.trim_end_matches(|c: char| !c.is_whitespace()).len()..]
+    }
+
+    /// Replaces the `current_word` with the given text.
+    pub fn replace_current_word(&mut self, text: &str) {
+        let start = self.cursor - self.current_word().len();
+        self.text.replace_range(start..self.cursor, text);
+        self.cursor = start + text.len();
+        self.last_yank = None;
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.text.split('\n').count()
+    }
+
+    /// Draws the visible part of the text with a character counter and places the terminal cursor.
+    /// Only the line with the cursor scrolls horizontally, the others are cut at the edge.
+    pub fn render(&mut self, rect: Rect, frame: &mut Frame) {
+        let counter = format!(" {}", self.char_count());
+        let [text_area, counter_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(counter.len() as u16),
+        ]).areas(rect);
+        let width = text_area.width as usize;
+        self.scroll_to_cursor(width);
+
+        let cursor_line = self.text[..self.cursor].matches('\n').count();
+        let first_line = (cursor_line + 1).saturating_sub(text_area.height.max(1) as usize);
+        let line_end = self.cursor + self.text[self.cursor..].find('\n').unwrap_or(self.text.len() - self.cursor);
+        let lines: Vec<Line> = self.text.split('\n').enumerate()
+            .skip(first_line)
+            .map(|(i, line)| {
+                let line = if i == cursor_line { &self.text[self.offset..line_end] } else { line };
+                Line::from(truncate_to_width(line, width))
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), text_area);
+        frame.render_widget(Line::from(counter).gray(), counter_area);
+        frame.set_cursor(
+            text_area.x + display_width(&self.text[self.offset..self.cursor]) as u16,
+            text_area.y + (cursor_line - first_line) as u16,
+        );
+    }
+
+    /// Moves the viewport of the cursor line so the cursor stays visible, keeping a column free for it at the end.
+    fn scroll_to_cursor(&mut self, width: usize) {
+        let line_start = self.text[..self.cursor].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.cursor + self.text[self.cursor..].find('\n').unwrap_or(self.text.len() - self.cursor);
+        if display_width(&self.text[line_start..line_end]) < width {
+            self.offset = line_start;
+            return;
+        }
+        self.offset = self.offset.clamp(line_start, self.cursor);
+        while self.offset < self.cursor && display_width(&self.text[self.offset..self.cursor]) >= width {
+            self.offset += self.text[self.offset..].graphemes(true).next().map_or(0, str::len);
+        }
+    }
+
+    fn kill(&mut self, start: usize, end: usize) {
+        if start == end {
+            return;
+        }
+        let killed: String = self.text.drain(start..end).collect();
+        self.cursor = start;
+        if self.kill_ring.len() == KILL_RING_SIZE {
+            self.kill_ring.remove(0);
+        }
+        self.kill_ring.push(killed);
+    }
+
+    fn previous_boundary(&self, pos: usize) -> usize {
+        self.text[..pos].char_indices().next_back().map_or(0, |(idx, _)| idx)
+    }
+
+    fn next_boundary(&self, pos: usize) -> usize {
+        self.text[pos..].chars().next().map_or(pos, |c| pos + c.len_utf8())
+    }
+
+    /// Start of the word before the cursor, skipping separators first.
+    fn word_start(&self, is_word: fn(char) -> bool) -> usize {
+        let before = &self.text[..self.cursor];
+        let end = before.trim_end_matches(|c| !is_word(c)).len();
+        before[..end].trim_end_matches(is_word).len()
+    }
+
+    /// End of the word after the cursor, skipping separators first.
+    fn word_end(&self, is_word: fn(char) -> bool) -> usize {
+        let after = &self.text[self.cursor..];
+        let start = after.len() - after.trim_start_matches(|c| !is_word(c)).len();
+        self.text.len() - after[start..].trim_start_matches(is_word).len()
+    }
+}
+
+/// Previously submitted inputs, recalled with Up and Down like in a shell.
+#[derive(Debug, Default)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    /// Entry currently shown, `None` while editing new text.
+    index: Option<usize>,
+    /// Text that was being edited before recalling an entry, restored when stepping past the newest one.
+    draft: String,
+}
+
+impl InputHistory {
+    /// Remembers a submitted input, moving repeated ones to the end.
+    pub fn push(&mut self, entry: &str) {
+        self.index = None;
+        if entry.is_empty() {
+            return;
+        }
+        self.entries.retain(|e| e != entry);
+        if self.entries.len() == HISTORY_SIZE {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry.to_string());
+    }
+
+    pub fn reset(&mut self) {
+        self.index = None;
+    }
+
+    /// Steps to an older (negative direction) or newer entry, returning the text to show if it changes.
+    pub fn recall(&mut self, direction: isize, current: &str) -> Option<String> {
+        match self.index {
+            None if direction < 0 && !self.entries.is_empty() => {
+                self.draft = current.to_string();
+                self.index = Some(self.entries.len() - 1);
+            }
+            None => return None,
+            Some(index) => match index.checked_add_signed(direction) {
+                None => return None,
+                Some(index) if index >= self.entries.len() => {
+                    self.index = None;
+                    return Some(std::mem::take(&mut self.draft));
+                }
+                Some(index) => self.index = Some(index),
+            },
+        }
+        self.index.map(|index| self.entries[index].clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(text: &str, cursor: usize) -> TextInput {
+        let mut input = TextInput::new();
+        input.set_text(text.to_string());
+        input.cursor = cursor;
+        input
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn edits_at_the_cursor() {
+        let mut text = input("hllo", 1);
+        assert!(text.input(&key(KeyCode::Char('e'), KeyModifiers::NONE)));
+        assert_eq!((text.text(), text.cursor), ("hello", 2));
+        text.input(&key(KeyCode::Char('a'), KeyModifiers::CONTROL));
+        text.input(&key(KeyCode::Delete, KeyModifiers::NONE));
+        assert_eq!((text.text(), text.cursor), ("ello", 0));
+        text.input(&key(KeyCode::Char('e'), KeyModifiers::CONTROL));
+        text.input(&key(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!((text.text(), text.cursor), ("ell", 3));
+        assert!(!text.input(&key(KeyCode::Char('x'), KeyModifiers::CONTROL)));
+    }
+
+    #[test]
+    fn moves_over_multibyte_characters() {
+        let mut text = input("añb", 0);
+        text.apply(EditAction::Right);
+        text.apply(EditAction::Right);
+        assert_eq!(text.cursor, 3);
+        text.apply(EditAction::DeleteBack);
+        assert_eq!(text.text(), "ab");
+    }
+
+    #[test]
+    fn kills_words() {
+        let mut text = input("vote for foo-bar", 16);
+        text.input(&key(KeyCode::Backspace, KeyModifiers::ALT));
+        assert_eq!(text.text(), "vote for foo-");
+        text.input(&key(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert_eq!(text.text(), "vote for ");
+        text.cursor = 0;
+        text.input(&key(KeyCode::Char('d'), KeyModifiers::ALT));
+        assert_eq!(text.text(), " for ");
+    }
+
+    #[test]
+    fn kills_to_line_start_and_end() {
+        let mut text = input("hello world", 5);
+        text.apply(EditAction::KillToEnd);
+        assert_eq!(text.text(), "hello");
+        text.apply(EditAction::KillToStart);
+        assert_eq!((text.text(), text.cursor), ("", 0));
+        assert_eq!(text.kill_ring, vec![" world", "hello"]);
+    }
+
+    #[test]
+    fn scrolls_to_keep_the_cursor_visible() {
+        let mut text = input("日本語日本語", 18);
+        text.scroll_to_cursor(5);
+        assert_eq!(&text.text[text.offset..], "本語");
+
+        text.apply(EditAction::Start);
+        text.scroll_to_cursor(5);
+        assert_eq!(text.offset, 0);
+
+        // the cursor after the last character needs a column as well
+        text.set_text("short".to_string());
+        text.scroll_to_cursor(5);
+        assert_eq!(text.offset, 1);
+        text.scroll_to_cursor(6);
+        assert_eq!(text.offset, 0);
+        assert_eq!(input("a😀b", 0).char_count(), 3);
+    }
+
+    #[test]
+    fn scrolls_only_the_cursor_line() {
+        let mut text = input("first line\nsecond", 17);
+        assert_eq!(text.line_count(), 2);
+        text.scroll_to_cursor(4);
+        assert_eq!(&text.text[text.offset..], "ond");
+        text.apply(EditAction::Start);
+        text.scroll_to_cursor(4);
+        assert_eq!(text.offset, 0);
+    }
+
+    #[test]
+    fn replaces_the_word_before_the_cursor() {
+        let mut text = input("hi @jo how", 6);
+        assert_eq!(text.current_word(), "@jo");
+        text.replace_current_word("@John ");
+        assert_eq!((text.text(), text.cursor), ("hi @John  how", 9));
+    }
+
+    #[test]
+    fn recalls_history_and_restores_the_draft() {
+        let mut history = InputHistory::default();
+        assert_eq!(history.recall(-1, ""), None);
+        history.push("hello");
+        history.push("5");
+        history.push("hello");
+
+        assert_eq!(history.recall(-1, "dra").as_deref(), Some("hello"));
+        assert_eq!(history.recall(-1, "hello").as_deref(), Some("5"));
+        assert_eq!(history.recall(-1, "5"), None);
+        assert_eq!(history.recall(1, "5").as_deref(), Some("hello"));
+        assert_eq!(history.recall(1, "hello").as_deref(), Some("dra"));
+        assert_eq!(history.recall(1, "dra"), None);
+    }
+
+    #[test]
+    fn yanks_from_the_kill_ring() {
+        let mut text = input("one two", 7);
+        text.apply(EditAction::KillToSpace);
+        text.apply(EditAction::KillToSpace);
+        assert_eq!(text.text(), "");
+
+        text.apply(EditAction::Yank);
+        assert_eq!(text.text(), "one ");
+        text.apply(EditAction::YankPop);
+        assert_eq!(text.text(), "two");
+        text.apply(EditAction::YankPop);
+        assert_eq!(text.text(), "one ");
+
+        // yank pop does nothing unless it follows a yank
+        text.apply(EditAction::Start);
+        text.apply(EditAction::YankPop);
+        assert_eq!(text.text(), "one ");
+    }
+}
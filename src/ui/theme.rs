@@ -0,0 +1,82 @@
+use std::cmp::Ordering;
+
+use ratatui::style::{Color, Style};
+
+use crate::config::Theme;
+
+const DARK_PALETTE: [Color; 10] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Yellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::LightRed,
+    Color::Indexed(208),
+    Color::Indexed(141),
+    Color::Indexed(79),
+];
+
+const LIGHT_PALETTE: [Color; 10] = [
+    Color::Blue,
+    Color::Magenta,
+    Color::Red,
+    Color::Cyan,
+    Color::Indexed(25),
+    Color::Indexed(90),
+    Color::Indexed(130),
+    Color::Indexed(28),
+    Color::Indexed(124),
+    Color::Indexed(54),
+];
+
+fn palette(theme: Theme) -> &'static [Color] {
+    match theme {
+        Theme::Dark => &DARK_PALETTE,
+        Theme::Light => &LIGHT_PALETTE,
+    }
+}
+
+/// FNV-1a, so a name keeps its color across sessions and builds.
+fn name_hash(name: &str) -> u32 {
+    name.bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    })
+}
+
+/// Style of a vote the same as, higher or lower than the reference it is compared with.
+pub fn comparison_style(theme: Theme, ordering: Ordering) -> Style {
+    let color = match (theme, ordering) {
+        (_, Ordering::Equal) => Color::Green,
+        (Theme::Dark, Ordering::Greater) => Color::LightBlue,
+        (Theme::Dark, Ordering::Less) => Color::Yellow,
+        (Theme::Light, Ordering::Greater) => Color::Blue,
+        // yellow is hard to read on a light background
+        (Theme::Light, Ordering::Less) => Color::Indexed(130),
+    };
+    Style::new().fg(color)
+}
+
+pub fn player_style(name: &str, theme: Theme) -> Style {
+    let palette = palette(theme);
+    Style::new().fg(palette[name_hash(name.trim()) as usize % palette.len()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_name_same_color() {
+        assert_eq!(player_style("Johnnie Waters", Theme::Dark), player_style(" Johnnie Waters ", Theme::Dark));
+        assert_eq!(name_hash("Ralph Muller"), 0xb59bde19);
+    }
+
+    #[test]
+    fn palette_depends_on_theme() {
+        let dark = player_style("Ralph Muller", Theme::Dark).fg.unwrap();
+        let light = player_style("Ralph Muller", Theme::Light).fg.unwrap();
+        assert!(DARK_PALETTE.contains(&dark));
+        assert!(LIGHT_PALETTE.contains(&light));
+    }
+}
@@ -0,0 +1,306 @@
+use std::sync::{Arc, Mutex};
+
+use crossterm::event::{KeyCode, KeyEvent, MouseEvent, MouseEventKind};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Flex, Layout};
+use ratatui::prelude::*;
+use ratatui::widgets::{Clear, Paragraph, Wrap};
+use self_update::cargo_crate_version;
+
+use crate::app::App;
+use crate::changelog;
+use crate::i18n::{tr, tr_args};
+use crate::ui::keymap::{lookup, KeyBinding};
+use crate::ui::markdown::{highlight, render_markdown};
+use crate::ui::sanitize::{display_width, single_line};
+use crate::ui::{render_box, UIAction};
+use crate::update::{channel_name, install_in_background, snooze, AvailableUpdate, InstallProgress};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum UpdateAction {
+    Confirm,
+    Snooze,
+    Close,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    NextVersion,
+    PreviousVersion,
+    ScrollUp,
+    ScrollDown,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+}
+
+const UPDATE_KEYS: [KeyBinding<UpdateAction>; 14] = [
+    KeyBinding::new(&[KeyCode::Enter], "ENTER", "Install the update, restart once it is installed", UpdateAction::Confirm),
+    KeyBinding::new(&[KeyCode::Char('l')], "l", "Remind me later", UpdateAction::Snooze),
+    KeyBinding::new(&[KeyCode::Esc], "ESC", "Close", UpdateAction::Close),
+    KeyBinding::new(&[KeyCode::Char('/')], "/", "Search the release notes", UpdateAction::Search),
+    KeyBinding::new(&[KeyCode::Char('n')], "n/N", "Next or previous match", UpdateAction::NextMatch),
+    KeyBinding::new(&[KeyCode::Char('N')], "", "", UpdateAction::PreviousMatch),
+    KeyBinding::new(&[KeyCode::Char(']')], "]/[", "Next or previous version", UpdateAction::NextVersion),
+    KeyBinding::new(&[KeyCode::Char('[')], "", "", UpdateAction::PreviousVersion),
+    KeyBinding::new(&[KeyCode::Up], "UP/DOWN", "Scroll the release notes", UpdateAction::ScrollUp),
+    KeyBinding::new(&[KeyCode::Down], "", "", UpdateAction::ScrollDown),
+    KeyBinding::new(&[KeyCode::PageUp], "PGUP/PGDN", "Scroll the release notes by a page", UpdateAction::PageUp),
+    KeyBinding::new(&[KeyCode::PageDown], "", "", UpdateAction::PageDown),
+    KeyBinding::new(&[KeyCode::Home, KeyCode::Char('g')], "g/G", "Scroll to the top or the end", UpdateAction::Top),
+    KeyBinding::new(&[KeyCode::End, KeyCode::Char('G')], "", "", UpdateAction::Bottom),
+];
+
+/// Lines scrolled per step of the mouse wheel.
+const WHEEL_LINES: isize = 3;
+
+/// Heading level of the versions in the release notes, `## [0.4.0](...)`.
+const VERSION_HEADING_LEVEL: usize = 2;
+
+/// Dialog over the current page showing the available update with the notes of all releases since the running version,
+/// installing it on confirmation.
+pub struct UpdateDialog {
+    update: AvailableUpdate,
+    notes: Vec<Line<'static>>,
+    /// Lines starting the notes of a version.
+    versions: Vec<usize>,
+    /// First line of the notes shown.
+    top: usize,
+    /// Lines of notes fitting into the dialog when it was last drawn.
+    page_size: usize,
+    /// Search being typed after `/`.
+    search: Option<String>,
+    /// Last confirmed search, highlighted in the notes.
+    query: String,
+    progress: Option<Arc<Mutex<InstallProgress>>>,
+    closed: bool,
+}
+
+impl UpdateDialog {
+    pub fn new(update: AvailableUpdate) -> Self {
+        let text = Some(update.notes.as_str()).filter(|text| !text.trim().is_empty()).unwrap_or(tr("No release notes."));
+        let markdown = render_markdown(text);
+        let versions = markdown.headings.iter()
+            .filter(|(_, level)| *level <= VERSION_HEADING_LEVEL)
+            .map(|(line, _)| *line)
+            .collect();
+        Self {
+            update,
+            notes: markdown.lines,
+            versions,
+            top: 0,
+            page_size: 1,
+            search: None,
+            query: String::new(),
+            progress: None,
+            closed: false,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    fn progress(&self) -> Option<InstallProgress> {
+        self.progress.as_ref().map(|progress| progress.lock().expect("Install progress poisoned").clone())
+    }
+
+    pub fn pasted(&mut self, text: &str) {
+        if let Some(search) = &mut self.search {
+            search.push_str(&single_line(text));
+        }
+    }
+
+    /// Lines of the notes containing the query.
+    fn matches(&self) -> Vec<usize> {
+        if self.query.is_empty() {
+            return vec![];
+        }
+        let query = self.query.to_lowercase();
+        self.notes.iter().enumerate()
+            .filter(|(_, line)| line.to_string().to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn scroll(&mut self, delta: isize) {
+        self.top = self.top.saturating_add_signed(delta).min(self.notes.len().saturating_sub(1));
+    }
+
+    pub fn mouse(&mut self, event: MouseEvent) {
+        match event.kind {
+            MouseEventKind::ScrollUp => self.scroll(-WHEEL_LINES),
+            MouseEventKind::ScrollDown => self.scroll(WHEEL_LINES),
+            _ => {}
+        }
+    }
+
+    fn jump(&mut self, lines: &[usize], forward: bool) {
+        if let Some(line) = next_line(lines, self.top, forward) {
+            self.top = line;
+        }
+    }
+
+    fn search_input(&mut self, event: KeyEvent) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+        match event.code {
+            KeyCode::Esc => self.search = None,
+            KeyCode::Enter => {
+                self.query = search.trim().to_string();
+                self.search = None;
+                // the current line counts as well when starting a search
+                self.top = self.top.saturating_sub(1);
+                self.jump(&self.matches(), true);
+            }
+            KeyCode::Backspace => {
+                search.pop();
+            }
+            KeyCode::Char(c) => search.push(c),
+            _ => {}
+        }
+    }
+
+    pub fn input(&mut self, app: &mut App, event: KeyEvent) -> UIAction {
+        if self.search.is_some() {
+            self.search_input(event);
+            return UIAction::Continue;
+        }
+        let Some(action) = lookup(&UPDATE_KEYS, &event) else {
+            return UIAction::Continue;
+        };
+        let progress = self.progress();
+        let busy = matches!(progress, Some(InstallProgress::Downloading { .. } | InstallProgress::Installing));
+        match action {
+            UpdateAction::Confirm => match progress {
+                None => self.progress = Some(install_in_background(app.config.update_source(), self.update.release.clone())),
+                Some(InstallProgress::Done) => {
+                    app.restart_after_update = true;
+                    return UIAction::Quit;
+                }
+                _ => {}
+            },
+            UpdateAction::Snooze if progress.is_none() => {
+                snooze();
+                app.available_update = None;
+                self.closed = true;
+            }
+            UpdateAction::Close if !busy => {
+                if progress == Some(InstallProgress::Done) {
+                    app.available_update = None;
+                }
+                self.closed = true;
+            }
+            UpdateAction::Search => self.search = Some(String::new()),
+            UpdateAction::NextMatch => self.jump(&self.matches(), true),
+            UpdateAction::PreviousMatch => self.jump(&self.matches(), false),
+            UpdateAction::NextVersion => self.jump(&self.versions.clone(), true),
+            UpdateAction::PreviousVersion => self.jump(&self.versions.clone(), false),
+            UpdateAction::ScrollUp => self.scroll(-1),
+            UpdateAction::ScrollDown => self.scroll(1),
+            UpdateAction::PageUp => self.scroll(-(self.page_size as isize)),
+            UpdateAction::PageDown => self.scroll(self.page_size as isize),
+            UpdateAction::Top => self.top = 0,
+            UpdateAction::Bottom => self.top = self.notes.len().saturating_sub(self.page_size),
+            _ => {}
+        }
+        UIAction::Continue
+    }
+
+    pub fn render(&mut self, app: &App, frame: &mut Frame) {
+        let [area] = Layout::vertical([Constraint::Percentage(70)]).flex(Flex::Center).areas(frame.size());
+        let [area] = Layout::horizontal([Constraint::Percentage(70)]).flex(Flex::Center).areas(area);
+        frame.render_widget(Clear, area);
+        let inner = render_box(&tr_args("Update to v{}", &[&self.update.release.version]), area, frame);
+
+        let [header, notes, status] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+        ]).areas(inner);
+
+        let mut header_line = Line::from(vec![
+            Span::raw(tr("Current version: ")),
+            Span::raw(format!("v{}", cargo_crate_version!())).bold(),
+            Span::raw(tr(" | New version: ")),
+            Span::raw(format!("v{}", self.update.release.version)).bold(),
+            Span::raw(tr(" | Channel: ")),
+            Span::raw(channel_name(&app.config.update_channel())).bold(),
+        ]);
+        if let Some(summary) = changelog::summary(&changelog::parse(&self.update.notes)) {
+            header_line.push_span(Span::raw(tr(" | Changes: ")));
+            header_line.push_span(Span::raw(summary).bold());
+        }
+        frame.render_widget(Paragraph::new(header_line).wrap(Wrap { trim: true }), header);
+
+        self.page_size = (notes.height as usize).max(1);
+        let lines: Vec<Line> = self.notes.iter().skip(self.top).map(|line| highlight(line, &self.query)).collect();
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), notes);
+
+        let status_line = if let Some(search) = &self.search {
+            frame.set_cursor(status.x + 1 + display_width(search) as u16, status.y + 1);
+            Line::from(format!("/{}", search))
+        } else {
+            match self.progress() {
+                None => {
+                    let mut line = Line::from(tr("ENTER install | / search | ]/[ versions | PGUP/PGDN g/G scroll | l remind me later | ESC close"));
+                    if !self.query.is_empty() {
+                        line.push_span(Span::raw(tr_args(" | n/N {} matches", &[&self.matches().len().to_string()])).yellow());
+                    }
+                    line
+                }
+                Some(InstallProgress::Downloading { received }) => Line::from(tr_args("Downloading... {} KiB", &[&(received / 1024).to_string()])).yellow(),
+                Some(InstallProgress::Installing) => Line::from(tr("Installing...")).yellow(),
+                Some(InstallProgress::Done) => {
+                    Line::from(tr("Update installed. ENTER restarts ppoker, ESC keeps the running version until you quit.")).green()
+                }
+                Some(InstallProgress::Failed(error)) => Line::from(tr_args("Update failed: {} | ESC close", &[error.as_str()])).red(),
+            }
+        };
+        frame.render_widget(Paragraph::new(vec![Line::from(""), status_line]), status);
+    }
+}
+
+/// The next line of `lines` after `current`, or the one before it going backwards, wrapping around at the ends.
+fn next_line(lines: &[usize], current: usize, forward: bool) -> Option<usize> {
+    if forward {
+        lines.iter().find(|line| **line > current).or(lines.first()).copied()
+    } else {
+        lines.iter().rev().find(|line| **line < current).or(lines.last()).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyModifiers;
+    use self_update::update::Release;
+
+    use super::*;
+
+    #[test]
+    fn jumps_between_lines_wrapping_around() {
+        let lines = [2, 5, 9];
+        assert_eq!(next_line(&lines, 0, true), Some(2));
+        assert_eq!(next_line(&lines, 5, true), Some(9));
+        assert_eq!(next_line(&lines, 9, true), Some(2));
+        assert_eq!(next_line(&lines, 5, false), Some(2));
+        assert_eq!(next_line(&lines, 2, false), Some(9));
+        assert_eq!(next_line(&[], 2, false), None);
+    }
+
+    #[test]
+    fn scrolls_with_the_mouse_wheel_within_the_notes() {
+        let notes = (0..20).map(|i| format!("line {}", i)).collect::<Vec<_>>().join("\n");
+        let mut dialog = UpdateDialog::new(AvailableUpdate { release: Release::default(), notes });
+        let wheel = |kind| MouseEvent { kind, column: 0, row: 0, modifiers: KeyModifiers::NONE };
+
+        dialog.mouse(wheel(MouseEventKind::ScrollDown));
+        assert_eq!(dialog.top, WHEEL_LINES as usize);
+        dialog.scroll(100);
+        assert_eq!(dialog.top, 19);
+        dialog.mouse(wheel(MouseEventKind::ScrollUp));
+        dialog.scroll(-100);
+        assert_eq!(dialog.top, 0);
+    }
+}
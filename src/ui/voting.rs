@@ -1,52 +1,313 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::ops::{AddAssign, DerefMut};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::Frame;
 use ratatui::prelude::*;
-use ratatui::widgets::{Bar, BarChart, BarGroup, Cell, List, ListDirection, ListItem, ListState, Paragraph, Row, Table, Wrap};
+use ratatui::widgets::{Bar, BarChart, BarGroup, Block, Cell, Clear, List, ListDirection, ListItem, ListState, Paragraph, Row, Table, TableState, Wrap};
 use tui_big_text::{BigText, PixelSize};
 
-use crate::app::{App, AppResult};
-use crate::models::{GamePhase, LogLevel, LogSource, Player, UserType, Vote, VoteData};
-use crate::ui::{colored_box_style, footer_entries, format_duration, Page, render_box, render_box_colored, render_confirmation_box, trim_name, UIAction, UiPage};
+use crate::announce::phase_announcement;
+use crate::app::{App, AppResult, AUTO_REVEAL_EXTENSION, MAX_REVEAL_DELAY};
+use crate::config::{BigTextSize, EventDisplay, LogTimestamps, ServerEvents, Theme, VoteArrows, VoteColors};
+use crate::estimation::{Technique, COMMAND_PREFIX};
+use crate::format::{format_age, format_clock, format_duration, format_time_of_day};
+use crate::groups::{group_stats, GroupStats};
+use crate::i18n::{tr, tr_args};
+use crate::models::{count_special_votes, format_confidence, is_consensus, majority_vote, nearest_card, unique_name, EventCategory, GamePhase, LogEntry, LogLevel, LogSource, Player, Reaction, UserType, Vote, VoteData, UNKNOWN_PHASE_WARNING};
+use crate::roles::{format_role_averages, role_averages, Role};
+use crate::ui::keymap::{help, lookup, HelpEntry, KeyBinding, DIGITS};
+use crate::ui::sanitize::{display_width, sanitize};
+use crate::ui::symbols::symbols;
+use crate::ui::text_input::{InputHistory, TextInput, EDIT_KEYS};
+use crate::ui::theme::{comparison_style, player_style};
+use crate::ui::{colored_box_style, footer_entries, link_spans, Page, render_box, render_box_colored, render_confirmation_box, trim_name, UIAction, UiPage};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub enum InputMode {
     Menu,
     Vote,
+    QuickVote,
+    AutoReveal,
+    AutoRestart,
     Name,
     Chat,
+    Technique,
+    RevealDelay,
     RevealConfirm,
     ResetConfirm,
+    SpectateConfirm,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum MenuAction {
+    Quit,
+    VoteCard,
+    Vote,
+    QuickVote,
+    Chat,
+    Name,
+    Technique,
+    Log,
+    OpenLink,
+    LogFilter,
+    PlainVotes,
+    Legend,
+    RevealOrRestart,
+    ScheduleReveal,
+    FirstSpeaker,
+    React,
+    Retract,
+    PostSuggestion,
+    RevealChat,
+    History,
+    InstallUpdate,
+    ScrollUp,
+    ScrollDown,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+}
+
+const MENU_KEYS: [KeyBinding<MenuAction>; 28] = [
+    KeyBinding::new(&DIGITS, "0-9", "Start voting with this card", MenuAction::VoteCard),
+    KeyBinding::new(&[KeyCode::Char('-')], "-", "Start voting with a negative card", MenuAction::VoteCard),
+    KeyBinding::new(&[KeyCode::Char('v')], "v", "Vote", MenuAction::Vote),
+    KeyBinding::new(&[KeyCode::Char('f')], "f", "Fast vote, a single key picks a card", MenuAction::QuickVote),
+    KeyBinding::new(&[KeyCode::Char('x'), KeyCode::Backspace], "x/BACKSPACE", "Retract your vote", MenuAction::Retract),
+    KeyBinding::new(&[KeyCode::Char('c')], "c", "Chat", MenuAction::Chat),
+    KeyBinding::new(&[KeyCode::Char('n')], "n", "Change your name", MenuAction::Name),
+    KeyBinding::new(&[KeyCode::Char('t')], "t", "Switch the estimation technique", MenuAction::Technique),
+    KeyBinding::new(&[KeyCode::Char('r')], "r", "Reveal the cards or start a new round", MenuAction::RevealOrRestart),
+    KeyBinding::new(&[KeyCode::Char('R')], "R", "Reveal for everyone in a number of seconds, or cancel the scheduled reveal", MenuAction::ScheduleReveal),
+    KeyBinding::new(&[KeyCode::Char('p')], "p", "Pick who explains their vote first after reveal", MenuAction::FirstSpeaker),
+    KeyBinding::new(&[KeyCode::F(1), KeyCode::F(2), KeyCode::F(3), KeyCode::F(4)], "F1-F4", "React to the revealed cards with thumbs up, thumbs down, surprise or thinking", MenuAction::React),
+    KeyBinding::new(&[KeyCode::Char('s')], "s", "Post the suggested card to the chat after reveal", MenuAction::PostSuggestion),
+    KeyBinding::new(&[KeyCode::Char('l')], "l", "Show the log", MenuAction::Log),
+    KeyBinding::new(&[KeyCode::Char('L')], "L", "Show only chat, server events, errors or everything in the log", MenuAction::LogFilter),
+    KeyBinding::new(&[KeyCode::Char('u')], "u", "Show the newest chat message hidden by the chat filter", MenuAction::RevealChat),
+    KeyBinding::new(&[KeyCode::Char('o')], "o", "Open the last link of the chat in the browser", MenuAction::OpenLink),
+    KeyBinding::new(&[KeyCode::Char('h')], "h", "Show the history", MenuAction::History),
+    KeyBinding::new(&[KeyCode::Char('b')], "b", "Show your vote and the average as big or plain text", MenuAction::PlainVotes),
+    KeyBinding::new(&[KeyCode::Char('k')], "k", "Show or hide the legend of the vote colors", MenuAction::Legend),
+    KeyBinding::new(&[KeyCode::Char('U')], "U", "Show and install the available update", MenuAction::InstallUpdate),
+    KeyBinding::new(&[KeyCode::Up], "UP/DOWN", "Scroll the players", MenuAction::ScrollUp),
+    KeyBinding::new(&[KeyCode::Down], "", "", MenuAction::ScrollDown),
+    KeyBinding::new(&[KeyCode::PageUp], "PGUP/PGDN", "Scroll the players by a page", MenuAction::PageUp),
+    KeyBinding::new(&[KeyCode::PageDown], "", "", MenuAction::PageDown),
+    KeyBinding::new(&[KeyCode::Home], "HOME/END", "Scroll to the first or last player", MenuAction::Top),
+    KeyBinding::new(&[KeyCode::End], "", "", MenuAction::Bottom),
+    KeyBinding::new(&[KeyCode::Esc, KeyCode::Char('q')], "ESC/q", "Quit", MenuAction::Quit),
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum TextInputAction {
+    Cancel,
+    Confirm,
+    NewLine,
+    Previous,
+    Next,
+}
+
+/// Keys of the text inputs besides the editing keys of `TextInput`.
+const TEXT_INPUT_KEYS: [KeyBinding<TextInputAction>; 4] = [
+    KeyBinding::new(&[KeyCode::Enter], "ENTER", "Confirm", TextInputAction::Confirm),
+    KeyBinding::new(&[KeyCode::Esc], "ESC", "Cancel", TextInputAction::Cancel),
+    KeyBinding::new(&[KeyCode::Up], "UP/DOWN", "Recall previous inputs", TextInputAction::Previous),
+    KeyBinding::new(&[KeyCode::Down], "", "", TextInputAction::Next),
+];
+
+const CHAT_KEYS: [KeyBinding<TextInputAction>; 1] = [
+    KeyBinding::new(&[KeyCode::Enter], "Alt+ENTER", "Start a new line, Shift+ENTER where the terminal supports it", TextInputAction::NewLine)
+        .with_modifiers(KeyModifiers::ALT),
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum MentionAction {
+    Next,
+    Previous,
+    Insert,
+    Close,
+}
+
+/// Keys of the name completion popup, opened by typing `@` in the chat.
+const MENTION_KEYS: [KeyBinding<MentionAction>; 4] = [
+    KeyBinding::new(&[KeyCode::Tab, KeyCode::Down], "TAB/DOWN", "Select the next name", MentionAction::Next),
+    KeyBinding::new(&[KeyCode::BackTab, KeyCode::Up], "SHIFT+TAB/UP", "Select the previous name", MentionAction::Previous),
+    KeyBinding::new(&[KeyCode::Enter], "ENTER", "Insert the selected name", MentionAction::Insert),
+    KeyBinding::new(&[KeyCode::Esc], "ESC", "Close the completion", MentionAction::Close),
+];
+
+/// Entries shown in the log box, so chat doesn't drown in join and leave messages in long sessions.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum LogFilter {
+    All,
+    Chat,
+    Server,
+    Errors,
+}
+
+impl LogFilter {
+    fn next(self) -> Self {
+        match self {
+            LogFilter::All => LogFilter::Chat,
+            LogFilter::Chat => LogFilter::Server,
+            LogFilter::Server => LogFilter::Errors,
+            LogFilter::Errors => LogFilter::All,
+        }
+    }
+
+    fn matches(self, entry: &LogEntry) -> bool {
+        match self {
+            LogFilter::All => true,
+            LogFilter::Chat => entry.level == LogLevel::Chat,
+            LogFilter::Server => entry.level != LogLevel::Chat && entry.source == LogSource::Server,
+            LogFilter::Errors => entry.level == LogLevel::Error,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            LogFilter::All => tr("Log"),
+            LogFilter::Chat => tr("Log (chat only)"),
+            LogFilter::Server => tr("Log (server events only)"),
+            LogFilter::Errors => tr("Log (errors only)"),
+        }
+    }
+}
+
+/// Lines of a chat message shown at once while typing it.
+const MAX_CHAT_LINES: usize = 5;
+
+/// Below this width the players are shown above the votes and the log instead of next to them.
+const NARROW_WIDTH: u16 = 80;
+/// Space the vote boxes need after the reveal, below it they are replaced by a single line.
+const VOTE_BOXES_WIDTH: u16 = 70;
+const VOTE_BOXES_HEIGHT: u16 = 16;
+
+/// Joins and leaves per minute from which the overview warns that the room is churning, e.g. clients reconnecting in a loop.
+const CHURN_WARNING_PER_MINUTE: usize = 6;
+/// Spread of the round trip times from which the overview shows their range.
+const UNSTABLE_JITTER: Duration = Duration::from_millis(100);
+
+/// Keys picking a card in fast vote mode, by position in the deck.
+const QUICK_VOTE_HINTS: &str = "123456789abcdefghijklmnopqrstuvwxyz";
+
+const QUICK_VOTE_CARDS: [KeyCode; 35] = [
+    KeyCode::Char('1'), KeyCode::Char('2'), KeyCode::Char('3'), KeyCode::Char('4'), KeyCode::Char('5'),
+    KeyCode::Char('6'), KeyCode::Char('7'), KeyCode::Char('8'), KeyCode::Char('9'),
+    KeyCode::Char('a'), KeyCode::Char('b'), KeyCode::Char('c'), KeyCode::Char('d'), KeyCode::Char('e'),
+    KeyCode::Char('f'), KeyCode::Char('g'), KeyCode::Char('h'), KeyCode::Char('i'), KeyCode::Char('j'),
+    KeyCode::Char('k'), KeyCode::Char('l'), KeyCode::Char('m'), KeyCode::Char('n'), KeyCode::Char('o'),
+    KeyCode::Char('p'), KeyCode::Char('q'), KeyCode::Char('r'), KeyCode::Char('s'), KeyCode::Char('t'),
+    KeyCode::Char('u'), KeyCode::Char('v'), KeyCode::Char('w'), KeyCode::Char('x'), KeyCode::Char('y'),
+    KeyCode::Char('z'),
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum QuickVoteAction {
+    Card,
+    Cancel,
+}
+
+const QUICK_VOTE_KEYS: [KeyBinding<QuickVoteAction>; 2] = [
+    KeyBinding::new(&QUICK_VOTE_CARDS, "1-9/a-z", "Vote for the card with this hint", QuickVoteAction::Card),
+    KeyBinding::new(&[KeyCode::Esc], "ESC", "Back to the menu", QuickVoteAction::Cancel),
+];
+
+fn quick_vote_hint(index: usize) -> Option<char> {
+    QUICK_VOTE_HINTS.chars().nth(index)
+}
+
+fn quick_vote_index(key: char) -> Option<usize> {
+    QUICK_VOTE_HINTS.find(key)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum AutoRevealAction {
+    Extend,
+    RevealNow,
+    Cancel,
+}
+
+const AUTO_REVEAL_KEYS: [KeyBinding<AutoRevealAction>; 3] = [
+    KeyBinding::new(&[KeyCode::Char('+')], "+", "Wait a few more seconds, announced in the chat", AutoRevealAction::Extend),
+    KeyBinding::new(&[KeyCode::Enter], "ENTER", "Reveal now", AutoRevealAction::RevealNow),
+    KeyBinding::new(&[KeyCode::Esc], "ESC", "Cancel the automatic reveal for this round", AutoRevealAction::Cancel),
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum AutoRestartAction {
+    RestartNow,
+    Cancel,
+}
+
+const AUTO_RESTART_KEYS: [KeyBinding<AutoRestartAction>; 2] = [
+    KeyBinding::new(&[KeyCode::Enter], "ENTER", "Start the next round now", AutoRestartAction::RestartNow),
+    KeyBinding::new(&[KeyCode::Esc], "ESC", "Stay on the results", AutoRestartAction::Cancel),
+];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum ConfirmAction {
+    Yes,
+    No,
+    Quit,
 }
 
+const CONFIRM_KEYS: [KeyBinding<ConfirmAction>; 3] = [
+    KeyBinding::new(&[KeyCode::Char('y'), KeyCode::Char('j'), KeyCode::Enter], "y/j/ENTER", "Yes", ConfirmAction::Yes),
+    KeyBinding::new(&[KeyCode::Char('n'), KeyCode::Esc], "n/ESC", "No", ConfirmAction::No),
+    KeyBinding::new(&[KeyCode::Char('q')], "q", "Quit", ConfirmAction::Quit),
+];
+
 pub struct VotingPage {
     pub input_mode: InputMode,
-    pub input_buffer: Option<String>,
-    last_phase: GamePhase
+    input: TextInput,
+    /// Submitted inputs of this session, by the mode they were entered in.
+    input_history: HashMap<InputMode, InputHistory>,
+    /// Selected name of the @mention completion, `None` while it is closed.
+    mention: Option<usize>,
+    last_phase: GamePhase,
+    players_state: TableState,
+    players_page_size: usize,
+    log_filter: LogFilter,
+    plain_votes: bool,
+    /// Whether the footer explains the colors of the revealed votes.
+    show_legend: bool,
 }
 
 impl Page for VotingPage {
-    fn render(&mut self, app: &mut App, frame: &mut Frame) {
+    fn render(&mut self, app: &mut App, area: Rect, frame: &mut Frame) {
         app.has_updates = false;
 
+        let footer_height = if self.input_mode == InputMode::Chat {
+            self.input.line_count().min(MAX_CHAT_LINES) as u16 + 2
+        } else {
+            3
+        };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3),
                 Constraint::Fill(1),
-                Constraint::Length(3)
+                Constraint::Length(footer_height)
             ])
-            .split(frame.size());
+            .split(area);
 
         let header = chunks[0];
         let primary = chunks[1];
         let footer = chunks[2];
 
+        let direction = if primary.width < NARROW_WIDTH {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
         let chunks = Layout::default()
-            .direction(Direction::Horizontal)
+            .direction(direction)
             .constraints([
                 Constraint::Percentage(30),
                 Constraint::Min(26),
@@ -55,15 +316,23 @@ impl Page for VotingPage {
         let left_side = chunks[0];
         let right_side = chunks[1];
 
+        let big_text_size = app.config.big_text_size;
+        let compact = self.plain_votes || right_side.width < VOTE_BOXES_WIDTH || right_side.height < VOTE_BOXES_HEIGHT;
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(9),
+                Constraint::Length(if compact { 3 } else { vote_boxes_height(big_text_size) }),
                 Constraint::Fill(1),
             ]).split(right_side);
 
-        let vote_view = chunks[0];
-        let log = chunks[1];
+        let mut vote_view = chunks[0];
+        let mut log = chunks[1];
+        if app.estimation.is_active() {
+            [vote_view, log] = Layout::vertical([
+                Constraint::Percentage(50),
+                Constraint::Fill(1),
+            ]).areas(right_side);
+        }
 
         if app.room.phase != self.last_phase {
             if self.input_mode != InputMode::Name {
@@ -71,120 +340,309 @@ impl Page for VotingPage {
             }
             self.last_phase = app.room.phase;
         }
+        if app.idle_prompt && self.input_mode == InputMode::Menu {
+            self.input_mode = InputMode::SpectateConfirm;
+        }
+        if app.auto_reveal_at.is_some() && self.input_mode == InputMode::Menu {
+            self.input_mode = InputMode::AutoReveal;
+        } else if app.auto_reveal_at.is_none() && self.input_mode == InputMode::AutoReveal {
+            self.input_mode = InputMode::Menu;
+        }
+        if app.auto_restart_at.is_some() && self.input_mode == InputMode::Menu {
+            self.input_mode = InputMode::AutoRestart;
+        } else if app.auto_restart_at.is_none() && self.input_mode == InputMode::AutoRestart {
+            self.input_mode = InputMode::Menu;
+        }
+
+        if app.config.screen_reader {
+            self.render_linear(app, header.union(primary), frame);
+            self.render_footer(app, footer, frame);
+            if let Some(selected) = self.mention {
+                render_mentions(&self.mention_candidates(app), selected, footer, frame);
+            }
+            return;
+        }
 
         match app.room.phase {
+            _ if app.estimation.is_active() => {
+                render_technique(app, vote_view, frame);
+            }
+            GamePhase::Unknown => render_unknown_phase(vote_view, frame),
             GamePhase::Revealed if app.history.len() > 0 => {
                 let entry = app.history.as_slice().last().expect("Can't get last item of history.");
-                render_own_vote(&entry.votes, entry.average, GamePhase::Revealed, &entry.own_vote, &entry.deck, vote_view, frame);
+                let averages = role_averages(&entry.votes, &app.config.roles);
+                let summary = VoteSummary {
+                    players: &entry.votes,
+                    deck: &entry.deck,
+                    average: entry.average,
+                    own_vote: &entry.own_vote,
+                    phase: GamePhase::Revealed,
+                    reactions: &app.reactions,
+                    role_averages: &averages,
+                };
+                if compact {
+                    render_vote_line(&summary, vote_view, frame);
+                } else {
+                    render_own_vote(&summary, big_text_size, vote_view, frame);
+                }
             }
             _ => {
-                render_own_vote(&app.room.players, app.average_votes(), app.room.phase, &app.vote, &app.room.deck, vote_view, frame);
+                let own_vote = if app.hides_own_vote() {
+                    app.vote.as_ref().map(|_| VoteData::Special("#".to_string()))
+                } else {
+                    app.vote.clone()
+                };
+                let averages = app.role_averages();
+                let summary = VoteSummary {
+                    players: &app.room.players,
+                    deck: &app.room.deck,
+                    average: app.average_votes(),
+                    own_vote: &own_vote,
+                    phase: app.room.phase,
+                    reactions: &app.reactions,
+                    role_averages: &averages,
+                };
+                if compact {
+                    render_vote_line(&summary, vote_view, frame);
+                } else {
+                    render_own_vote(&summary, big_text_size, vote_view, frame);
+                }
             }
         }
         self.render_log(app, log, frame);
         self.render_votes(app, left_side, frame);
         render_overview(app, header, frame);
         self.render_footer(app, footer, frame);
+        if let Some(selected) = self.mention {
+            render_mentions(&self.mention_candidates(app), selected, footer, frame);
+        }
     }
 
     fn input(&mut self, app: &mut App, event: KeyEvent) -> AppResult<UIAction> {
-        match &self.input_mode {
+        let mode = self.input_mode;
+        match mode {
             InputMode::Menu => {
-                match event.code {
-                    KeyCode::Esc | KeyCode::Char('q') => {
+                if event.code == KeyCode::Esc && app.log_round.take().is_some() {
+                    return Ok(UIAction::Continue);
+                }
+                let Some(action) = lookup(&MENU_KEYS, &event) else {
+                    return Ok(UIAction::Continue);
+                };
+                match action {
+                    MenuAction::Quit => {
                         return Ok(UIAction::Quit);
                     }
-                    KeyCode::Char(c) if c.is_ascii_digit() => {
-                        self.change_mode(InputMode::Vote, c.to_string(), app);
-                    }
-                    KeyCode::Char('-') => {
-                        self.change_mode(InputMode::Vote, String::from("-"), app)
+                    MenuAction::VoteCard => {
+                        if let KeyCode::Char(c) = event.code {
+                            self.change_mode(InputMode::Vote, c.to_string(), app);
+                        }
                     }
-                    KeyCode::Char('v') => {
+                    MenuAction::Vote => {
                         self.change_mode(InputMode::Vote, String::new(), app)
                     }
-                    KeyCode::Char('c') if !event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    MenuAction::QuickVote => {
+                        if app.room.phase == GamePhase::Playing && !app.estimation.is_active() {
+                            self.input_mode = InputMode::QuickVote;
+                        }
+                    }
+                    MenuAction::Chat => {
                         self.change_mode(InputMode::Chat, String::new(), app)
                     }
-                    KeyCode::Char('n') => {
-                        self.change_mode(InputMode::Name, app.name.clone(), app)
+                    MenuAction::Name => {
+                        let name = if app.has_duplicate_name() { unique_name(&app.name, &app.room.players) } else { app.name.clone() };
+                        self.change_mode(InputMode::Name, name, app)
+                    }
+                    MenuAction::Technique => {
+                        self.change_mode(InputMode::Technique, String::new(), app)
                     }
-                    KeyCode::Char('l') => {
+                    MenuAction::Log => {
                         return Ok(UIAction::ChangeView(UiPage::Log));
                     }
-                    KeyCode::Char('r') => {
-                        if app.room.phase == GamePhase::Playing {
+                    MenuAction::OpenLink => app.open_last_link(),
+                    MenuAction::RevealChat => app.reveal_chat(),
+                    MenuAction::LogFilter => self.log_filter = self.log_filter.next(),
+                    MenuAction::PlainVotes => self.plain_votes = !self.plain_votes,
+                    MenuAction::Legend => self.show_legend = !self.show_legend,
+                    MenuAction::RevealOrRestart => match app.room.phase {
+                        GamePhase::Playing => {
                             if app.room.players.iter().any(|p| p.user_type != UserType::Spectator && p.vote == Vote::Missing) {
                                 self.input_mode = InputMode::RevealConfirm;
                             } else {
                                 app.reveal()?;
                             }
-                        } else {
-                            self.input_mode = InputMode::ResetConfirm;
+                        }
+                        GamePhase::Revealed => self.input_mode = InputMode::ResetConfirm,
+                        GamePhase::Unknown => app.flash_message(UNKNOWN_PHASE_WARNING),
+                    },
+                    MenuAction::ScheduleReveal => {
+                        if app.scheduled_reveal.is_some() {
+                            app.cancel_scheduled_reveal()?;
+                        } else if app.room.phase == GamePhase::Playing {
+                            self.change_mode(InputMode::RevealDelay, String::new(), app);
                         }
                     }
-                    KeyCode::Char('h') => {
+                    MenuAction::FirstSpeaker => app.pick_first_speaker()?,
+                    MenuAction::React => {
+                        if let KeyCode::F(n) = event.code {
+                            if let Some(reaction) = Reaction::ALL.get(n as usize - 1) {
+                                app.react(*reaction)?;
+                            }
+                        }
+                    }
+                    MenuAction::Retract => {
+                        app.retract_vote()?;
+                    }
+                    MenuAction::PostSuggestion => {
+                        app.post_suggestion()?;
+                    }
+                    MenuAction::History => {
                         return Ok(UIAction::ChangeView(UiPage::History));
                     }
-                    _ => {}
+                    MenuAction::InstallUpdate => {
+                        if app.available_update.is_some() {
+                            return Ok(UIAction::ShowUpdate);
+                        }
+                    }
+                    MenuAction::ScrollUp => self.scroll_players(-1),
+                    MenuAction::ScrollDown => self.scroll_players(1),
+                    MenuAction::PageUp => self.scroll_players(-(self.players_page_size as isize)),
+                    MenuAction::PageDown => self.scroll_players(self.players_page_size as isize),
+                    MenuAction::Top => self.scroll_players(isize::MIN),
+                    MenuAction::Bottom => self.scroll_players(isize::MAX),
                 }
             }
-            InputMode::Vote | InputMode::Name | InputMode::Chat => {
-                match event.code {
-                    KeyCode::Esc => {
+            InputMode::Chat if self.mention.is_some() && lookup(&MENTION_KEYS, &event).is_some() => {
+                if let Some(action) = lookup(&MENTION_KEYS, &event) {
+                    self.mention_input(app, action);
+                }
+            }
+            InputMode::Vote | InputMode::Name | InputMode::Chat | InputMode::Technique | InputMode::RevealDelay => {
+                let action = if self.input_mode != InputMode::Chat {
+                    lookup(&TEXT_INPUT_KEYS, &event)
+                } else if event.code == KeyCode::Enter && event.modifiers.contains(KeyModifiers::SHIFT) {
+                    // lookup ignores shift, it would confirm the message
+                    Some(TextInputAction::NewLine)
+                } else {
+                    lookup(&CHAT_KEYS, &event).or_else(|| lookup(&TEXT_INPUT_KEYS, &event))
+                };
+                match action {
+                    Some(TextInputAction::Cancel) => {
                         self.cancel_input();
                     }
-
-                    KeyCode::Enter => {
+                    Some(TextInputAction::Confirm) => {
                         self.confirm_input(app)?;
                     }
-
-                    KeyCode::Backspace => {
-                        if let Some(input_buffer) = &mut self.input_buffer {
-                            input_buffer.pop();
-                        }
+                    Some(TextInputAction::NewLine) => {
+                        self.input.insert_str("\n");
                     }
-
-                    KeyCode::Char(c) => {
-                        if let Some(input_buffer) = &mut self.input_buffer {
-                            input_buffer.push(c);
+                    Some(TextInputAction::Previous) => self.recall_input(-1),
+                    Some(TextInputAction::Next) => self.recall_input(1),
+                    None => {
+                        self.input.input(&event);
+                        self.update_mention(app);
+                    }
+                }
+            }
+            InputMode::QuickVote => {
+                match lookup(&QUICK_VOTE_KEYS, &event) {
+                    Some(QuickVoteAction::Card) => {
+                        let card = match event.code {
+                            KeyCode::Char(c) => quick_vote_index(c).and_then(|i| app.room.deck.get(i)).cloned(),
+                            _ => None,
+                        };
+                        if let Some(card) = card {
+                            app.vote(&card)?;
+                            self.input_mode = InputMode::Menu;
                         }
                     }
-                    _ => {}
+                    Some(QuickVoteAction::Cancel) => self.input_mode = InputMode::Menu,
+                    None => {}
                 }
             }
-            InputMode::ResetConfirm => {
-                match event.code {
-                    KeyCode::Char('y') | KeyCode::Enter => {
+            InputMode::AutoReveal => {
+                match lookup(&AUTO_REVEAL_KEYS, &event) {
+                    Some(AutoRevealAction::Extend) => app.extend_auto_reveal()?,
+                    Some(AutoRevealAction::RevealNow) => {
+                        app.cancel_auto_reveal();
+                        app.reveal()?;
+                        self.input_mode = InputMode::Menu;
+                    }
+                    Some(AutoRevealAction::Cancel) => {
+                        app.cancel_auto_reveal();
+                        self.input_mode = InputMode::Menu;
+                    }
+                    None => {}
+                }
+            }
+            InputMode::AutoRestart => {
+                match lookup(&AUTO_RESTART_KEYS, &event) {
+                    Some(AutoRestartAction::RestartNow) => {
+                        app.auto_restart_at = None;
                         app.restart()?;
                         self.input_mode = InputMode::Menu;
                     }
-                    KeyCode::Char('n') | KeyCode::Esc => { self.input_mode = InputMode::Menu; }
-                    KeyCode::Char('q') => { return Ok(UIAction::Quit); }
-                    _ => {}
+                    Some(AutoRestartAction::Cancel) => {
+                        app.auto_restart_at = None;
+                        self.input_mode = InputMode::Menu;
+                    }
+                    None => {}
                 }
             }
-            InputMode::RevealConfirm => {
-                match event.code {
-                    KeyCode::Char('y') | KeyCode::Enter => {
-                        app.reveal()?;
+            InputMode::ResetConfirm | InputMode::RevealConfirm | InputMode::SpectateConfirm => {
+                match lookup(&CONFIRM_KEYS, &event) {
+                    Some(ConfirmAction::Yes) => {
+                        match mode {
+                            InputMode::ResetConfirm => app.restart()?,
+                            InputMode::RevealConfirm => app.reveal()?,
+                            _ => {
+                                app.idle_prompt = false;
+                                app.change_user_type(UserType::Spectator)?;
+                            }
+                        }
+                        self.input_mode = InputMode::Menu;
+                    }
+                    Some(ConfirmAction::No) => {
+                        if mode == InputMode::SpectateConfirm {
+                            app.idle_prompt = false;
+                        }
                         self.input_mode = InputMode::Menu;
                     }
-                    KeyCode::Char('n') | KeyCode::Esc => { self.input_mode = InputMode::Menu; }
-                    KeyCode::Char('q') => { return Ok(UIAction::Quit); }
-                    _ => {}
+                    Some(ConfirmAction::Quit) => { return Ok(UIAction::Quit); }
+                    None => {}
                 }
             }
         }
         Ok(UIAction::Continue)
     }
 
-    fn pasted(&mut self, _app: &mut App, text: String) {
+    fn keybindings(&self, _app: &App) -> Vec<HelpEntry> {
         match self.input_mode {
-            InputMode::Chat | InputMode::Vote | InputMode::Name => {
-                if let Some(input_buffer) = &mut self.input_buffer {
-                    input_buffer.push_str(text.as_str());
-                }
+            InputMode::Menu => help(&MENU_KEYS),
+            InputMode::QuickVote => help(&QUICK_VOTE_KEYS),
+            InputMode::AutoReveal => help(&AUTO_REVEAL_KEYS),
+            InputMode::AutoRestart => help(&AUTO_RESTART_KEYS),
+            InputMode::Chat if self.mention.is_some() => {
+                [help(&MENTION_KEYS), help(&CHAT_KEYS), help(&EDIT_KEYS)].concat()
+            }
+            InputMode::Chat => {
+                [help(&TEXT_INPUT_KEYS), help(&CHAT_KEYS), help(&EDIT_KEYS)].concat()
+            }
+            InputMode::Vote | InputMode::Name | InputMode::Technique | InputMode::RevealDelay => {
+                [help(&TEXT_INPUT_KEYS), help(&EDIT_KEYS)].concat()
+            }
+            InputMode::ResetConfirm | InputMode::RevealConfirm | InputMode::SpectateConfirm => help(&CONFIRM_KEYS),
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        matches!(self.input_mode, InputMode::Vote | InputMode::Name | InputMode::Chat | InputMode::Technique | InputMode::RevealDelay)
+    }
+
+    fn pasted(&mut self, app: &mut App, text: String) {
+        match self.input_mode {
+            InputMode::Chat | InputMode::Vote | InputMode::Name | InputMode::Technique | InputMode::RevealDelay => {
+                self.input.pasted(&text);
+                self.update_mention(app);
             }
             _ => {}
         }
@@ -196,48 +654,112 @@ impl VotingPage {
     pub fn new() -> Self {
         Self {
             input_mode: InputMode::Menu,
-            input_buffer: None,
+            input: TextInput::new(),
+            input_history: HashMap::new(),
+            mention: None,
             last_phase: GamePhase::Playing,
+            players_state: TableState::default(),
+            players_page_size: 1,
+            log_filter: LogFilter::All,
+            plain_votes: false,
+            show_legend: false,
         }
     }
 
+    fn scroll_players(&mut self, delta: isize) {
+        let offset = self.players_state.offset().saturating_add_signed(delta);
+        *self.players_state.offset_mut() = offset;
+    }
+
     pub fn change_mode(&mut self, mode: InputMode, default_text: String, app: &App) {
-        if mode == InputMode::Vote && app.room.phase == GamePhase::Playing {
+        if mode == InputMode::Vote && (app.room.phase == GamePhase::Playing || app.estimation.is_active()) {
             self.start_input(mode, default_text)
-        } else if mode == InputMode::Name || mode == InputMode::Chat {
+        } else if mode == InputMode::Name || mode == InputMode::Chat || mode == InputMode::Technique {
             self.start_input(mode, default_text)
         }
     }
 
     fn start_input(&mut self, mode: InputMode, default: String) {
         self.input_mode = mode;
-        self.input_buffer = Some(default);
+        self.input.set_text(default);
+        self.input_history.entry(mode).or_default().reset();
+    }
+
+    /// Names of the other players matching the `@` mention before the cursor.
+    fn mention_candidates(&self, app: &App) -> Vec<String> {
+        let Some(prefix) = self.input.current_word().strip_prefix('@') else {
+            return vec![];
+        };
+        let prefix = prefix.to_lowercase();
+        let mut names: Vec<String> = app.room.players.iter()
+            .filter(|p| !p.is_you)
+            .map(|p| sanitize(&p.name))
+            .filter(|name| name.to_lowercase().starts_with(&prefix))
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn update_mention(&mut self, app: &App) {
+        let count = self.mention_candidates(app).len();
+        self.mention = if self.input_mode == InputMode::Chat && count > 0 {
+            Some(self.mention.unwrap_or(0).min(count - 1))
+        } else {
+            None
+        };
+    }
+
+    fn mention_input(&mut self, app: &App, action: MentionAction) {
+        let candidates = self.mention_candidates(app);
+        let Some(selected) = self.mention.filter(|_| !candidates.is_empty()) else {
+            self.mention = None;
+            return;
+        };
+        match action {
+            MentionAction::Next => self.mention = Some((selected + 1) % candidates.len()),
+            MentionAction::Previous => self.mention = Some((selected + candidates.len() - 1) % candidates.len()),
+            MentionAction::Insert => {
+                self.input.replace_current_word(&format!("@{} ", candidates[selected.min(candidates.len() - 1)]));
+                self.mention = None;
+            }
+            MentionAction::Close => self.mention = None,
+        }
+    }
+
+    fn recall_input(&mut self, direction: isize) {
+        let history = self.input_history.entry(self.input_mode).or_default();
+        if let Some(text) = history.recall(direction, self.input.text()) {
+            self.input.set_text(text);
+        }
     }
 
     pub fn confirm_input(&mut self, app: &mut App) -> AppResult<()> {
-        let buffer = self.input_buffer.as_ref().map(|b| b.trim().replace('\n', ""));
+        let input = self.input.text().trim().to_string();
+        self.input_history.entry(self.input_mode).or_default().push(&input);
         match self.input_mode {
-            InputMode::Vote if app.room.phase == GamePhase::Playing => {
-                if let Some(input_buffer) = &buffer {
-                    let vote = input_buffer.clone();
-                    app.vote(vote.as_str())?;
-                }
+            InputMode::Vote if app.room.phase == GamePhase::Playing || app.estimation.is_active() => {
+                app.vote(input.as_str())?;
                 self.cancel_input();
             }
             InputMode::Name => {
-                if let Some(input_buffer) = &buffer {
-                    let name = input_buffer.clone();
-                    app.rename(name)?;
-                }
+                app.rename(input)?;
                 self.cancel_input();
             }
             InputMode::Chat => {
-                if let Some(input_buffer) = &buffer {
-                    let name = input_buffer.clone();
-                    app.chat(name)?;
-                }
+                app.chat_input(input)?;
+                self.cancel_input();
+            }
+            InputMode::Technique => {
+                app.set_technique(&input)?;
                 self.cancel_input();
             }
+            InputMode::RevealDelay => match input.parse::<u64>() {
+                Ok(seconds) => {
+                    app.schedule_reveal(seconds)?;
+                    self.cancel_input();
+                }
+                Err(_) => app.flash_message(tr("Enter the number of seconds until the reveal.")),
+            },
             _ => {}
         }
 
@@ -246,56 +768,168 @@ impl VotingPage {
 
     pub fn cancel_input(&mut self) {
         self.input_mode = InputMode::Menu;
-        self.input_buffer = None;
+        self.input.clear();
+        self.mention = None;
     }
 
     fn render_votes(&mut self, app: &mut App, rect: Rect, frame: &mut Frame) {
-        let rect = render_box_colored("Players", colored_box_style(app.room.phase), rect, frame);
+        let outer = rect;
+        let rect = render_box_colored(tr("Players"), colored_box_style(app.room.phase), rect, frame);
 
         let mut longest_name: usize = 0;
 
         let mut players = app.room.players.clone();
-        if app.room.phase == GamePhase::Revealed {
-            players.sort();
-        } else {
-            players.sort_by(|p, p2| {
-                p.name.cmp(&p2.name)
+        let revealed = app.room.phase == GamePhase::Revealed;
+        players.sort_by(|p, p2| {
+            let spectators_last = (p.user_type == UserType::Spectator).cmp(&(p2.user_type == UserType::Spectator));
+            spectators_last.then_with(|| {
+                if revealed {
+                    p.cmp(p2)
+                } else {
+                    p.name.cmp(&p2.name)
+                }
             })
+        });
+
+        // players of a group together, below a line with the votes of the group, the ones without a group last
+        let mut grouped: Vec<(Option<String>, Player)> = players.into_iter().map(|p| (app.player_group(&p), p)).collect();
+        grouped.sort_by(|(a, _), (b, _)| a.is_none().cmp(&b.is_none()).then_with(|| a.cmp(b)));
+        let (groups, players): (Vec<Option<String>>, Vec<Player>) = grouped.into_iter().unzip();
+        let group_count = if groups.iter().any(Option::is_some) {
+            let mut distinct = groups.clone();
+            distinct.dedup();
+            distinct.len()
+        } else {
+            0
+        };
+        let row_count = players.len() + group_count;
+
+        // header and its bottom margin take two lines
+        let mut visible_rows = rect.height.saturating_sub(2) as usize;
+        let [table_area, more_area] = if row_count > visible_rows {
+            visible_rows = visible_rows.saturating_sub(1);
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(rect)
+        } else {
+            [rect, Rect::default()]
+        };
+        self.players_page_size = visible_rows.max(1);
+        let max_offset = row_count.saturating_sub(visible_rows);
+        if self.players_state.offset() > max_offset {
+            *self.players_state.offset_mut() = max_offset;
         }
+        let hidden_rows = row_count.saturating_sub(self.players_state.offset() + visible_rows);
 
+        // while playing, a narrow first column checks off who has voted
+        let playing = app.room.phase == GamePhase::Playing;
         let rows: Vec<Row> = players.iter().map(|player| {
             let player_color = if player.is_you {
-                Style::new().green()
+                player_style(&player.name, app.config.theme).bold()
             } else {
-                Style::new()
+                player_style(&player.name, app.config.theme)
             };
-            let name = crate::ui::voting::trim_name(&player.name);
-            if name.len() > longest_name {
-                longest_name = name.len()
-            }
+            let player_color = if app.is_away(player) {
+                player_color.dim().italic()
+            } else {
+                player_color
+            };
+            let name = trim_name(&player.name);
+            longest_name = longest_name.max(display_width(&name));
 
-            Row::new(vec![
+            let vote = if player.is_you && player.vote != Vote::Missing && app.hides_own_vote() {
+                &Vote::Hidden
+            } else {
+                &player.vote
+            };
+
+            let mut cells = vec![
                 Cell::from(Span::styled(name, player_color)),
-                Cell::from(format_vote(&player.vote, &app.vote)),
-                Cell::from(if player.user_type == UserType::Spectator { "Spectator" } else { "Player" }),
-            ])
+                Cell::from(if app.room.phase == GamePhase::Revealed {
+                    let arrows = arrow_reference(app.config.vote_arrows, &app.vote, app.average_votes());
+                    let colors = color_reference(app.config.vote_colors, &app.vote, app.average_votes());
+                    vote_with_confidence(format_vote(vote, colors, app.config.theme, arrows), app.confidence.get(player.key()))
+                } else {
+                    Line::from(format_vote(vote, None, app.config.theme, None))
+                }),
+                Cell::from(if player.user_type == UserType::Spectator {
+                    tr("Spectator").to_string()
+                } else if app.is_away(player) {
+                    tr("Away").to_string()
+                } else {
+                    match app.player_role(player) {
+                        Role::Other => tr("Player").to_string(),
+                        role => role.to_string(),
+                    }
+                }),
+            ];
+            if playing {
+                let voted = player.user_type != UserType::Spectator && player.vote != Vote::Missing;
+                cells.insert(0, Cell::from(Span::raw(if voted { symbols().check } else { "" }).green().dim()));
+            }
+            Row::new(cells)
         }).collect();
+        let rows = if group_count > 0 {
+            let mut result = Vec::with_capacity(row_count);
+            for (i, row) in rows.into_iter().enumerate() {
+                if i == 0 || groups[i] != groups[i - 1] {
+                    let name = groups[i].as_deref().map_or(tr("No group").to_string(), trim_name);
+                    longest_name = longest_name.max(display_width(&name));
+                    let stats = group_stats(players.iter().zip(&groups).filter(|(_, g)| **g == groups[i]).map(|(p, _)| p));
+                    result.push(group_row(name, &stats, app.room.phase));
+                }
+                result.push(row);
+            }
+            result
+        } else {
+            rows
+        };
 
-        let table = Table::new(rows, [Constraint::Length(longest_name as u16), Constraint::Length(7), Constraint::Fill(1)])
+        let mut widths = vec![Constraint::Length(longest_name as u16), Constraint::Length(9), Constraint::Fill(1)];
+        let mut header = vec![tr("Name"), tr("Vote"), tr("Type")];
+        if playing {
+            widths.insert(0, Constraint::Length(1));
+            header.insert(0, "");
+        }
+        let table = Table::new(rows, widths)
             .column_spacing(3)
             .header(
-                Row::new(vec!["Name", "Vote", "Type"])
+                Row::new(header)
                     .style(Style::new().bold())
                     .bottom_margin(1)
             );
 
-        frame.render_widget(table, rect);
+        frame.render_stateful_widget(table, table_area, &mut self.players_state);
+
+        if more_area.height > 0 {
+            let more = if hidden_rows > 0 {
+                tr_args("+{} more ({}/{} to scroll)", &[&hidden_rows.to_string(), symbols().up, symbols().down])
+            } else {
+                tr_args("{} players ({}/{} to scroll)", &[&players.len().to_string(), symbols().up, symbols().down])
+            };
+            frame.render_widget(Paragraph::new(more).style(Style::new().gray()), more_area);
+        }
+
+        let averages = app.role_averages();
+        if revealed && !averages.is_empty() && outer.height > 1 {
+            // on the bottom border, like a title
+            let line = Rect { x: outer.x + 1, y: outer.bottom() - 1, width: outer.width.saturating_sub(2), height: 1 };
+            frame.render_widget(Line::from(format!(" {} ", format_role_averages(&averages))).light_blue(), line);
+        }
     }
 
     fn render_log(&mut self, app: &mut App, rect: Rect, frame: &mut Frame) {
-        let rect = render_box_colored("Log", colored_box_style(app.room.phase), rect, frame);
+        let (title, log) = match app.log_round.filter(|index| *index < app.history.len()) {
+            Some(index) => {
+                let title = tr_args("{} of round {}, ESC for all", &[self.log_filter.title(), &app.history[index].round_number.to_string()]);
+                (title, app.round_log(index))
+            }
+            None => (self.log_filter.title().to_string(), app.log.as_slice()),
+        };
+        let rect = render_box_colored(&title, colored_box_style(app.room.phase), rect, frame);
 
-        let entries: Vec<ListItem> = app.log.iter().map(|logentry| {
+        let theme = app.config.theme;
+        let timestamps = app.config.log_timestamps;
+        let visible = collapse_events(log.iter().filter(|entry| self.log_filter.matches(entry)), &app.config.server_events);
+        let entries: Vec<ListItem> = visible.iter().map(|logentry| {
             let color = match logentry.level {
                 LogLevel::Chat => { Style::new().light_blue() }
                 LogLevel::Info => {
@@ -313,16 +947,104 @@ impl VotingPage {
                     format!("[{:?}]: ", logentry.source)
                 }
             };
-            ListItem::new(format!("{}{}", prefix, logentry.message)).style(color)
+            let (first, message) = match logentry.chat_sender(&app.room.players) {
+                Some(sender) => {
+                    let (sender, message) = logentry.message.split_at(sender.len());
+                    (Span::styled(sanitize(sender), player_style(sender, theme)), message)
+                }
+                _ => (Span::raw(prefix), logentry.message.as_str()),
+            };
+            let message = match logentry.level {
+                LogLevel::Chat => match parse_reaction(message) {
+                    Some(parsed) => Cow::Owned(format!(" {}", reaction_symbol(parsed))),
+                    None => app.chat_text(logentry, message),
+                },
+                _ => Cow::Borrowed(message),
+            };
+            // multi-line messages continue indented below the sender
+            let mut lines = message.split('\n');
+            let mut first_line = Line::from(first);
+            if let Some(timestamp) = format_timestamp(logentry, timestamps) {
+                first_line.spans.insert(0, Span::raw(timestamp).dim());
+            }
+            first_line.spans.extend(link_spans(sanitize(lines.next().unwrap_or(""))));
+            let text: Vec<Line> = std::iter::once(first_line)
+                .chain(lines.map(|line| Line::from(link_spans(format!("  {}", sanitize(line))))))
+                .collect();
+            ListItem::new(text).style(color)
         }).collect();
 
-        let mut state = ListState::default().with_offset(entries.len().saturating_sub(rect.height as usize));
+        // keep the newest entries visible, some of them may span multiple lines
+        let mut offset = entries.len();
+        let mut height = 0;
+        while offset > 0 && height + entries[offset - 1].height() <= rect.height as usize {
+            offset -= 1;
+            height += entries[offset].height();
+        }
+        let mut state = ListState::default().with_offset(offset);
         let list = List::new(entries)
             .direction(ListDirection::TopToBottom);
 
         frame.render_stateful_widget(list, rect, &mut state);
     }
 
+    /// The room as labelled lines in a fixed order without any boxes, for screen readers.
+    fn render_linear(&self, app: &App, rect: Rect, frame: &mut Frame) {
+        let mut lines = vec![tr_args("Room: {}. State: {}. Round: {}.", &[&sanitize(&app.room.name), phase_name(app.room.phase), &app.round_number.to_string()])];
+        if let Some(topic) = &app.topic {
+            lines.push(tr_args("Topic: {}.", &[&sanitize(topic)]));
+        }
+        let own_vote = match &app.vote {
+            Some(_) if app.hides_own_vote() => tr("hidden").to_string(),
+            Some(vote) => vote.to_string(),
+            None => tr("none").to_string(),
+        };
+        lines.push(tr_args("Your vote: {}.", &[&own_vote]));
+        let (voted, total) = app.room.vote_progress();
+        lines.push(tr_args("Votes: {} of {}.", &[&voted.to_string(), &total.to_string()]));
+        if app.room.phase != GamePhase::Playing {
+            lines.push(phase_announcement(&app.room, app.round_number));
+        }
+        if app.room.phase == GamePhase::Revealed && !app.reactions.is_empty() {
+            let reactions: Vec<String> = app.reactions.iter()
+                .map(|(name, reaction)| format!("{} {}", sanitize(name), reaction.name()))
+                .collect();
+            lines.push(tr_args("Reactions: {}.", &[&reactions.join(", ")]));
+        }
+
+        lines.push(tr("Players:").to_string());
+        let mut players = app.room.players.clone();
+        players.sort();
+        for player in &players {
+            let vote = if player.user_type == UserType::Spectator {
+                tr("spectator").to_string()
+            } else {
+                match &player.vote {
+                    Vote::Missing => tr("no vote").to_string(),
+                    Vote::Revealed(_) if player.is_you && app.hides_own_vote() => tr("voted").to_string(),
+                    Vote::Hidden => tr("voted").to_string(),
+                    Vote::Revealed(vote) => vote.to_string(),
+                }
+            };
+            let you = if player.is_you { tr(" (you)") } else { "" };
+            lines.push(format!("{}{}: {}.", sanitize(&player.name), you, vote));
+        }
+
+        lines.push(format!("{}:", self.log_filter.title()));
+        let messages: Vec<String> = app.log.iter()
+            .filter(|entry| self.log_filter.matches(entry))
+            .map(|entry| match entry.level {
+                LogLevel::Error => tr_args("Error: {}", &[&sanitize(&entry.message)]),
+                _ => sanitize(&app.chat_text(entry, &entry.message)),
+            })
+            .collect();
+        let shown = (rect.height as usize).saturating_sub(lines.len()).min(messages.len());
+        lines.extend_from_slice(&messages[messages.len() - shown..]);
+
+        let text: Vec<Line> = lines.into_iter().map(Line::from).collect();
+        frame.render_widget(Paragraph::new(text), rect);
+    }
+
     fn render_footer(&mut self, app: &mut App, rect: Rect, frame: &mut Frame) {
         match &self.input_mode {
             InputMode::Vote => {
@@ -331,7 +1053,13 @@ impl VotingPage {
                     .constraints([Constraint::Length(20), Constraint::Fill(1)])
                     .split(rect);
 
-                self.render_text_input("Vote", layout[0], frame);
+                self.render_text_input(tr("Vote"), layout[0], frame);
+                if app.estimation.is_active() {
+                    let hint = tr_args("   Enter {}", &[app.estimation.technique.input_hint()]);
+                    let hint = Paragraph::new(vec![Line::from(""), Line::from(hint)]).style(Style::new().gray());
+                    frame.render_widget(hint, layout[1]);
+                    return;
+                }
                 let mut spans: Vec<Span> = app.room.deck.iter().flat_map(|item| {
                     vec![
                         Span::raw(" "),
@@ -340,18 +1068,48 @@ impl VotingPage {
                     ]
                 }).collect();
 
-                spans.insert(0, Span::raw("   Possible values:"));
+                spans.insert(0, Span::raw(tr("   Possible values:")));
                 spans.remove(spans.len() - 1);
 
 
                 let possible_values = Paragraph::new(vec![Line::from(""), Line::from(spans)]).style(Style::new().gray());
                 frame.render_widget(possible_values, layout[1]);
             }
+            InputMode::QuickVote => {
+                let width = app.room.deck.iter().map(|c| c.chars().count()).max().unwrap_or(1) + 3;
+                let cards: Vec<Span> = app.room.deck.iter()
+                    .map(|card| Span::raw(format!("{:^width$}", card)))
+                    .collect();
+                let hints: Vec<Span> = (0..app.room.deck.len())
+                    .map(|i| Span::raw(format!("{:^width$}", quick_vote_hint(i).unwrap_or(' '))).bold().yellow())
+                    .collect();
+                let lines = vec![
+                    Line::from(cards),
+                    Line::from(hints),
+                    Line::from(tr(" Fast vote: press the key below a card, ESC to cancel")).gray(),
+                ];
+                frame.render_widget(Paragraph::new(lines), rect);
+            }
+            InputMode::AutoReveal => {
+                let message = tr_args("Everyone has voted, revealing in {}s.", &[&seconds_until(app.auto_reveal_at).to_string()]);
+                let extend = tr_args("wait {}s", &[&AUTO_REVEAL_EXTENSION.to_string()]);
+                render_countdown(tr("Auto reveal"), &message, &[("+", &extend), ("Enter", tr("reveal now")), ("Esc", tr("cancel"))], rect, frame);
+            }
+            InputMode::AutoRestart => {
+                let message = tr_args("Next round starts in {}s.", &[&seconds_until(app.auto_restart_at).to_string()]);
+                render_countdown(tr("Auto restart"), &message, &[("Enter", tr("start now")), ("Esc", tr("stay on the results"))], rect, frame);
+            }
             InputMode::Name => {
-                self.render_text_input("Rename", rect, frame);
+                self.render_text_input(tr("Rename"), rect, frame);
             }
             InputMode::Chat => {
-                self.render_text_input("Chat", rect, frame);
+                self.render_text_input(tr("Chat (/rename, /reveal, /reset, /spectate, /topic)"), rect, frame);
+            }
+            InputMode::Technique => {
+                self.render_text_input(tr("Technique: poker, dots, buckets or magic followed by items separated by |"), rect, frame);
+            }
+            InputMode::RevealDelay => {
+                self.render_text_input(&tr_args("Reveal for everyone in seconds, at most {}", &[&MAX_REVEAL_DELAY.to_string()]), rect, frame);
             }
             InputMode::RevealConfirm => {
                 render_confirmation_box("Not everyone has voted yet. Confirm you want to reveal the cards?", rect, frame);
@@ -359,36 +1117,189 @@ impl VotingPage {
             InputMode::ResetConfirm => {
                 render_confirmation_box("Confirm you want to start a new round?", rect, frame);
             }
+            InputMode::SpectateConfirm => {
+                render_confirmation_box("You seem to be away. Switch to spectator?", rect, frame);
+            }
             InputMode::Menu => {
-                let entries = if app.room.phase == GamePhase::Playing {
-                    vec!["Vote", "Reveal", "History", "Name change", "Chat", "Quit"]
-                } else {
-                    vec!["Restart", "History", "Name change", "Chat", "Quit"]
-                };
-
-                frame.render_widget(footer_entries(entries), rect);
+                frame.render_widget(footer_entries(menu_entries(app.room.phase, app.estimation.is_active())), rect);
+                if self.show_legend {
+                    frame.render_widget(color_legend(app.config.vote_colors, app.config.theme), Rect { height: 1, ..rect });
+                }
+                if let Some(message) = app.current_flash() {
+                    let line = Line::from(format!("{} ", message)).yellow().right_aligned();
+                    frame.render_widget(line, Rect { height: 1, ..rect });
+                } else if app.has_duplicate_name() {
+                    let line = Line::from(tr_args("Someone else is called {} too, n to rename ", &[&sanitize(&app.name)])).red().bold().right_aligned();
+                    frame.render_widget(line, Rect { height: 1, ..rect });
+                } else if let Some(name) = &app.first_speaker {
+                    let line = Line::from(tr_args("{} explains first ", &[&sanitize(name)])).light_blue().bold().right_aligned();
+                    frame.render_widget(line, Rect { height: 1, ..rect });
+                } else if let Some(scheduled) = &app.scheduled_reveal {
+                    let message = tr_args("{} reveals in {}s, R to cancel ", &[&sanitize(&scheduled.by), &seconds_until(Some(scheduled.at)).to_string()]);
+                    let line = Line::from(message).light_blue().right_aligned();
+                    frame.render_widget(line, Rect { height: 1, ..rect });
+                }
             }
         }
     }
 
     fn render_text_input(&mut self, title: &str, rect: Rect, frame: &mut Frame) {
         let rect = render_box(title, rect, frame);
-        let buffer = self.input_buffer.as_ref().map_or("", |buffer| buffer.as_str());
-        let text_buffer = Paragraph::new(buffer);
-        frame.render_widget(text_buffer, rect);
-        frame.set_cursor(
-            rect.x + buffer.len() as u16,
-            rect.y,
-        );
+        self.input.render(rect, frame);
     }
 }
 
-pub(super) fn render_own_vote(players: &Vec<Player>, average_vote: f32, phase: GamePhase, own_vote: &Option<VoteData>, deck: &Vec<String>, rect: Rect, frame: &mut Frame) {
+/// Applies the display settings of server events, replacing runs of collapsed events by a line counting them.
+fn collapse_events<'a>(entries: impl Iterator<Item=&'a LogEntry>, events: &ServerEvents) -> Vec<Cow<'a, LogEntry>> {
+    let mut result: Vec<Cow<LogEntry>> = vec![];
+    // category and length of the run of collapsed events at the end of the result
+    let mut run: Option<(EventCategory, usize)> = None;
+    for entry in entries {
+        let category = entry.event_category();
+        match category.map(|c| (c, events.display(c))) {
+            Some((_, EventDisplay::Hide)) => {}
+            Some((category, EventDisplay::Collapse)) => match run {
+                Some((run_category, count)) if run_category == category => {
+                    let message = match category {
+                        EventCategory::JoinLeave => tr_args("{} players joined or left", &[&(count + 1).to_string()]),
+                        EventCategory::Card => tr_args("{} players changed their cards", &[&(count + 1).to_string()]),
+                        EventCategory::Rename => tr_args("{} players changed their names", &[&(count + 1).to_string()]),
+                    };
+                    if let Some(last) = result.last_mut() {
+                        *last = Cow::Owned(LogEntry { message, ..entry.clone() });
+                    }
+                    run = Some((category, count + 1));
+                }
+                _ => {
+                    result.push(Cow::Borrowed(entry));
+                    run = Some((category, 1));
+                }
+            },
+            None | Some((_, EventDisplay::Show)) => {
+                result.push(Cow::Borrowed(entry));
+                run = None;
+            }
+        }
+    }
+    result
+}
+
+fn format_timestamp(entry: &LogEntry, timestamps: LogTimestamps) -> Option<String> {
+    match timestamps {
+        LogTimestamps::Off => None,
+        LogTimestamps::Absolute => Some(format!("{} ", format_time_of_day(entry.time))),
+        LogTimestamps::Relative => Some(format!("{:>4} ", format_age(entry.timestamp.elapsed()))),
+    }
+}
+
+/// Popup with the names completing an @mention, drawn just above the chat input.
+fn render_mentions(names: &[String], selected: usize, input: Rect, frame: &mut Frame) {
+    let height = (names.len().min(5) + 2) as u16;
+    let width = names.iter().map(|name| display_width(name)).max().unwrap_or(0).max(10) as u16 + 4;
+    let popup = Rect::new(input.x + 1, input.y.saturating_sub(height), width, height)
+        .intersection(frame.size());
+    frame.render_widget(Clear, popup);
+    let inner = render_box(tr("Mention"), popup, frame);
+
+    let list = List::new(names.iter().map(|name| ListItem::new(name.as_str())))
+        .highlight_style(Style::new().on_white().black());
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, inner, &mut state);
+}
+
+fn render_technique(app: &App, rect: Rect, frame: &mut Frame) {
+    let estimation = &app.estimation;
+    let rect = render_box(estimation.technique.to_string().as_str(), rect, frame);
+
+    let rows: Vec<Row> = estimation.items.iter().enumerate().map(|(i, item)| {
+        let result = match estimation.technique {
+            Technique::DotVoting => {
+                let dots = estimation.dot_count(i);
+                Span::raw(format!("{} {}", symbols().dot.repeat(dots), dots))
+            }
+            _ => match &estimation.buckets[i] {
+                Some(bucket) => Span::raw(bucket.clone()).bold(),
+                None => Span::raw("-").style(Style::new().red()),
+            }
+        };
+        Row::new(vec![
+            Cell::from((i + 1).to_string()),
+            Cell::from(item.as_str()),
+            Cell::from(result),
+        ])
+    }).collect();
+
+    let result_header = if estimation.technique == Technique::DotVoting {
+        tr_args("Dots ({} voted)", &[&estimation.players_with_dots().to_string()])
+    } else {
+        tr("Bucket").to_string()
+    };
+    let table = Table::new(rows, [Constraint::Length(3), Constraint::Fill(2), Constraint::Fill(1)])
+        .column_spacing(2)
+        .header(Row::new(vec!["#".to_string(), tr("Item").to_string(), result_header])
+            .style(Style::new().bold())
+            .bottom_margin(1));
+    frame.render_widget(table, rect);
+}
+
+/// Subheader of a group in the players table with its progress while playing and its average once revealed.
+fn group_row(name: String, stats: &GroupStats, phase: GamePhase) -> Row<'static> {
+    let (summary, label) = match phase {
+        GamePhase::Revealed if stats.average.is_nan() => ("-".to_string(), tr("average")),
+        GamePhase::Revealed => (format!("{:.1}", stats.average), tr("average")),
+        _ => (format!("{}/{}", stats.voted, stats.total), tr("voted")),
+    };
+    let mut cells = vec![
+        Cell::from(Span::raw(name).bold().underlined()),
+        Cell::from(Span::raw(summary).bold()),
+        Cell::from(Span::raw(label).gray()),
+    ];
+    if phase == GamePhase::Playing {
+        cells.insert(0, Cell::from(""));
+    }
+    Row::new(cells)
+}
+
+/// What the vote boxes show, of the running round or the last revealed one.
+struct VoteSummary<'a> {
+    players: &'a [Player],
+    deck: &'a [String],
+    average: f32,
+    own_vote: &'a Option<VoteData>,
+    phase: GamePhase,
+    reactions: &'a [(String, Reaction)],
+    role_averages: &'a [(Role, f32)],
+}
+
+fn reaction_symbol(reaction: Reaction) -> &'static str {
+    symbols().reactions[reaction as usize]
+}
+
+/// Reaction of a chat message after the sender, like `: !pp react thumbsup`.
+fn parse_reaction(message: &str) -> Option<Reaction> {
+    message.trim_start_matches(':').trim_start()
+        .strip_prefix(COMMAND_PREFIX)
+        .and_then(|command| command.strip_prefix("react"))
+        .and_then(Reaction::parse)
+}
+
+/// Reactions counted per kind like `👍 2 🤔 1`, empty without reactions.
+fn format_reactions(reactions: &[(String, Reaction)]) -> String {
+    let counts: Vec<String> = Reaction::ALL.iter()
+        .map(|kind| (kind, reactions.iter().filter(|(_, r)| r == kind).count()))
+        .filter(|(_, count)| *count > 0)
+        .map(|(kind, count)| format!("{} {}", reaction_symbol(*kind), count))
+        .collect();
+    counts.join(" ")
+}
+
+fn render_own_vote(summary: &VoteSummary, size: BigTextSize, rect: Rect, frame: &mut Frame) {
+    let VoteSummary { players, deck, average, own_vote, phase, reactions, .. } = *summary;
     let constraints = if phase == GamePhase::Revealed {
         [
             Constraint::Length(26),
-            Constraint::Length((deck.len() * 3) as u16),
-            Constraint::Length(34),
+            Constraint::Length((vote_distribution(players, deck).len() * 3) as u16),
+            Constraint::Length(40),
         ]
     } else {
         [
@@ -403,41 +1314,115 @@ pub(super) fn render_own_vote(players: &Vec<Player>, average_vote: f32, phase: G
         .split(rect);
     let small_box = chunks[0];
     let bar_chart = chunks[1];
-    let average = chunks[2];
+    let average_box = chunks[2];
 
     if phase == GamePhase::Revealed {
-        let inner = render_box_colored("Vote distribution", colored_box_style(phase), bar_chart, frame);
+        render_distribution(players, deck, phase, bar_chart, frame);
+        render_average(players, deck, average, phase, size, average_box, frame);
+    }
+    render_your_vote(own_vote, phase, size, small_box, frame);
+    if phase == GamePhase::Revealed && !reactions.is_empty() && small_box.height > 1 {
+        // on the bottom border of the own vote, like a title
+        let line = Rect { x: small_box.x + 1, y: small_box.bottom() - 1, width: small_box.width.saturating_sub(2), height: 1 };
+        frame.render_widget(Line::from(format!(" {} ", format_reactions(reactions))), line);
+    }
+}
 
-        let mut cards = HashMap::new();
-        for player in players {
-            let card = format!("{}", player.vote);
-            cards.entry(card).or_insert(0).deref_mut().add_assign(1);
+/// The own vote and the result in one line, for terminals too small for the boxes.
+fn render_vote_line(summary: &VoteSummary, rect: Rect, frame: &mut Frame) {
+    let VoteSummary { players, deck, average, own_vote, phase, reactions, role_averages } = *summary;
+    let inner = render_box_colored(tr("Your vote"), colored_box_style(phase), rect, frame);
+    let separator = format!(" {} ", symbols().separator);
+
+    let mut spans = vec![match own_vote {
+        Some(vote) => Span::raw(vote.to_string()).green().bold(),
+        None => Span::raw("-").red().bold(),
+    }];
+    if phase == GamePhase::Revealed {
+        spans.push(Span::raw(separator.clone()));
+        spans.push(Span::raw(tr_args("Average {}", &[&format!("{:.1}", average)])).light_blue());
+        if !role_averages.is_empty() {
+            spans.push(Span::raw(format!(" ({})", format_role_averages(role_averages))).light_blue());
         }
+        if let Some(card) = nearest_card(average, deck) {
+            spans.push(Span::raw(separator.clone()));
+            spans.push(Span::raw(tr_args("Suggested: {}", &[card])).green());
+        }
+        if is_consensus(players) {
+            spans.push(Span::raw(separator.clone()));
+            spans.push(Span::raw(tr("Consensus!")).green().bold());
+        }
+        if !reactions.is_empty() {
+            spans.push(Span::raw(separator));
+            spans.push(Span::raw(format_reactions(reactions)));
+        }
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), inner);
+}
 
-        let cards: Vec<_> = deck.iter().map(|card| {
-            Bar::default()
-                .text_value(card.clone())
-                .value(*cards.get(card).unwrap_or(&0))
-        }).collect();
+/// Number of votes per card, in deck order followed by votes that are not part of the deck.
+pub(super) fn vote_distribution(players: &[Player], deck: &[String]) -> Vec<(String, u64)> {
+    let mut result: Vec<(String, u64)> = deck.iter().map(|card| (card.clone(), 0)).collect();
+    for player in players {
+        let Vote::Revealed(vote) = &player.vote else {
+            continue;
+        };
+        let card = vote.to_string();
+        match result.iter_mut().find(|(c, _)| *c == card) {
+            Some((_, count)) => *count += 1,
+            None => result.push((card, 1)),
+        }
+    }
+    result
+}
 
-        let chart = BarChart::default()
-            .bar_width(2)
-            .bar_gap(1)
-            .data(BarGroup::default().bars(cards.as_slice()));
+pub(super) fn render_distribution(players: &[Player], deck: &[String], phase: GamePhase, rect: Rect, frame: &mut Frame) {
+    let inner = render_box_colored(tr("Vote distribution"), colored_box_style(phase), rect, frame);
 
-        frame.render_widget(chart, inner);
+    let cards: Vec<_> = vote_distribution(players, deck).into_iter().map(|(card, count)| {
+        Bar::default()
+            .text_value(card)
+            .value(count)
+    }).collect();
 
-        let inner = render_box_colored("Average vote", colored_box_style(phase), average, frame);
-        let text = BigText::builder()
-            .pixel_size(PixelSize::Full)
-            .style(Style::new().light_blue())
-            .alignment(Alignment::Center)
-            .lines(vec![format!("{:.1}", average_vote).into()])
-            .build().expect("Failed to build Text widget");
-        frame.render_widget(text, inner);
+    let chart = BarChart::default()
+        .bar_width(2)
+        .bar_gap(1)
+        .bar_set(symbols().bar.clone())
+        .data(BarGroup::default().bars(cards.as_slice()));
+
+    frame.render_widget(chart, inner);
+}
+
+pub(super) fn render_average(players: &[Player], deck: &[String], average_vote: f32, phase: GamePhase, size: BigTextSize, rect: Rect, frame: &mut Frame) {
+    let consensus = is_consensus(players);
+    let (title, style) = if consensus {
+        (Line::from(format!(" {} ", tr("Consensus!"))).green().bold().reversed(), Style::new().green())
+    } else {
+        (Line::from(tr("Average vote")), colored_box_style(phase))
+    };
+    let mut block = Block::bordered()
+        .title(title)
+        .border_set(symbols().border)
+        .border_style(style);
+    if let Some(card) = nearest_card(average_vote, deck) {
+        block = block.title(Line::from(format!(" {} ", tr_args("Suggested: {}", &[card]))).green().bold().right_aligned());
+    }
+    if let Some(majority) = majority_vote(players) {
+        block = block.title_bottom(Line::from(format!(" {} ", tr_args("majority: {}", &[&majority.to_string()]))).left_aligned());
     }
+    let special_votes = count_special_votes(players);
+    if special_votes > 0 {
+        block = block.title_bottom(Line::from(format!(" {} ", tr_args("abstained/unsure: {}", &[&special_votes.to_string()]))).yellow().right_aligned());
+    }
+    let inner = block.inner(rect);
+    frame.render_widget(block, rect);
+    let style = if consensus { Style::new().green() } else { Style::new().light_blue() };
+    render_big_text(format!("{:.1}", average_vote), style, size, inner, frame);
+}
 
-    let inner = render_box_colored("Your vote", colored_box_style(phase), small_box, frame);
+pub(super) fn render_your_vote(own_vote: &Option<VoteData>, phase: GamePhase, size: BigTextSize, rect: Rect, frame: &mut Frame) {
+    let inner = render_box_colored(tr("Your vote"), colored_box_style(phase), rect, frame);
 
     let (color, text) = if let Some(vote) = &own_vote {
         (Style::new().green(), vote.to_string())
@@ -445,23 +1430,68 @@ pub(super) fn render_own_vote(players: &Vec<Player>, average_vote: f32, phase: G
         (Style::new().red(), "-".to_owned())
     };
 
-    let text = BigText::builder()
-        .pixel_size(PixelSize::Full)
-        .style(color)
-        .alignment(Alignment::Center)
-        .lines(vec![text.into()])
-        .build().expect("Failed to build text widget");
-    frame.render_widget(text, inner);
+    render_big_text(text, color, size, inner, frame);
+}
+
+/// Height of the boxes showing your vote and the average, the big text plus the borders.
+pub(super) fn vote_boxes_height(size: BigTextSize) -> u16 {
+    match size {
+        BigTextSize::Full => 9,
+        BigTextSize::Half | BigTextSize::Quadrant => 6,
+    }
+}
+
+fn pixel_size(size: BigTextSize) -> PixelSize {
+    match size {
+        BigTextSize::Full => PixelSize::Full,
+        BigTextSize::Half => PixelSize::HalfHeight,
+        BigTextSize::Quadrant => PixelSize::Quadrant,
+    }
+}
+
+/// Centered block letters, or bold text if the terminal only shows ascii.
+fn render_big_text(text: String, style: Style, size: BigTextSize, rect: Rect, frame: &mut Frame) {
+    if symbols().big_text {
+        let text = BigText::builder()
+            .pixel_size(pixel_size(size))
+            .style(style)
+            .alignment(Alignment::Center)
+            .lines(vec![text.into()])
+            .build().expect("Failed to build text widget");
+        frame.render_widget(text, rect);
+    } else {
+        let [line] = Layout::vertical([Constraint::Length(1)]).flex(layout::Flex::Center).areas(rect);
+        frame.render_widget(Paragraph::new(text).style(style.bold()).centered(), line);
+    }
+}
+
+/// Footer entries of the menu, reveal and restart are left out in a phase of a newer server version.
+fn menu_entries(phase: GamePhase, estimation: bool) -> Vec<&'static str> {
+    if estimation {
+        return vec!["Vote", "Technique", "History", "Name change", "Chat", "Quit"];
+    }
+    match phase {
+        GamePhase::Playing => vec!["Vote", "Fast vote", "Reveal", "Technique", "History", "Name change", "Chat", "Quit"],
+        GamePhase::Revealed => vec!["Restart", "Suggest", "Pick speaker", "Technique", "History", "Name change", "Chat", "Quit"],
+        GamePhase::Unknown => vec!["Technique", "History", "Name change", "Chat", "Quit"],
+    }
+}
+
+/// Warning in place of the own vote while the room is in a phase of a newer server version.
+fn render_unknown_phase(rect: Rect, frame: &mut Frame) {
+    let inner = render_box_colored(tr("Unknown state"), Style::new().yellow(), rect, frame);
+    let [line] = Layout::vertical([Constraint::Length(2)]).flex(layout::Flex::Center).areas(inner);
+    frame.render_widget(Paragraph::new(tr(UNKNOWN_PHASE_WARNING)).yellow().centered().wrap(Wrap { trim: true }), line);
 }
 
 pub(super) fn render_overview(app: &mut App, rect: Rect, frame: &mut Frame) {
-    let rect = render_box("Overview", rect, frame);
+    let rect = render_box(tr("Overview"), rect, frame);
 
     let name = trim_name(app.name.as_str());
-    let state_color = if app.room.phase == GamePhase::Playing {
-        Style::new().yellow()
-    } else {
-        Style::new().light_blue()
+    let state_color = match app.room.phase {
+        GamePhase::Playing => Style::new().yellow(),
+        GamePhase::Revealed => Style::new().light_blue(),
+        GamePhase::Unknown => Style::new().dark_gray(),
     };
 
     let duration = if app.room.phase == GamePhase::Revealed && app.history.len() > 0 {
@@ -470,23 +1500,70 @@ pub(super) fn render_overview(app: &mut App, rect: Rect, frame: &mut Frame) {
         format_duration(&(Instant::now() - app.round_start))
     };
 
+    let (server_label, server) = if let Some(socket) = &app.config.follow {
+        (tr(" | Mirror of: "), socket.as_str())
+    } else {
+        (tr(" | Server: "), app.config.server.as_str())
+    };
+
     let mut text = Line::from(vec![
-        Span::raw("Name: "),
+        Span::raw(tr("Name: ")),
         Span::raw(name).bold(),
-        Span::raw(" | Room: "),
-        Span::raw(app.room.name.as_str()).bold(),
-        Span::raw(" | Server: "),
-        Span::raw(app.config.server.as_str()).bold(),
-        Span::raw(" | State: "),
-        Span::raw(format!("{}", app.room.phase)).style(state_color.bold()),
-        Span::raw(" | Round: "),
+        Span::raw(tr(" | Room: ")),
+        Span::raw(sanitize(&app.room.name)).bold(),
+        Span::raw(server_label),
+        Span::raw(server).bold(),
+        Span::raw(tr(" | State: ")),
+        Span::raw(phase_name(app.room.phase)).style(state_color.bold()),
+        Span::raw(tr(" | Round: ")),
         Span::raw(app.round_number.to_string()).bold(),
         Span::raw(format!(" ({})", duration)),
     ]);
 
+    if let Some(topic) = &app.topic {
+        text.push_span(Span::raw(tr(" | Topic: ")));
+        text.push_span(Span::raw(sanitize(topic)).bold());
+    }
+
+    if app.room.phase == GamePhase::Playing {
+        let (voted, total) = app.room.vote_progress();
+        let style = if voted == total && total > 0 { Style::new().green().bold() } else { Style::new().bold() };
+        text.push_span(Span::raw(" | "));
+        text.push_span(Span::styled(tr_args("{}/{} voted", &[&voted.to_string(), &total.to_string()]), style));
+    }
+
+    if let (Some(timebox), GamePhase::Playing) = (app.timebox(), app.room.phase) {
+        let elapsed = app.round_start.elapsed();
+        text.push_span(Span::raw(tr(" | Timebox: ")));
+        if elapsed < timebox {
+            text.push_span(Span::raw(tr_args("{} left", &[&format_clock(&(timebox - elapsed))])).bold());
+        } else {
+            text.push_span(Span::raw(tr_args("over by {}", &[&format_clock(&(elapsed - timebox))])).red().bold().rapid_blink());
+        }
+    }
+
+    if let Some(latency) = app.latency() {
+        text.push_span(Span::raw(" | Ping: "));
+        text.push_span(Span::raw(format!("{}ms", latency.avg.as_millis())).bold());
+        if latency.jitter() >= UNSTABLE_JITTER {
+            text.push_span(Span::raw(format!(" ({}-{}ms)", latency.min.as_millis(), latency.max.as_millis())).yellow());
+        }
+    }
+
+    let churn = app.join_leave_rate();
+    if churn >= CHURN_WARNING_PER_MINUTE {
+        text.push_span(Span::raw(" | "));
+        text.push_span(Span::raw(tr_args("{} joins/leaves in the last minute, {} names seen", &[&churn.to_string(), &app.seen_names.len().to_string()])).yellow());
+    }
+
     if app.has_updates {
         text.push_span(Span::raw(" | "));
-        text.push_span(Span::raw("Has changes").yellow().rapid_blink())
+        text.push_span(Span::raw(tr("Has changes")).yellow().rapid_blink())
+    }
+
+    if let Some(update) = &app.available_update {
+        text.push_span(Span::raw(" | "));
+        text.push_span(Span::raw(tr_args("Update v{} available, press U", &[&update.release.version.to_string()])).yellow());
     }
 
     let paragraph = Paragraph::new(text)
@@ -495,25 +1572,99 @@ pub(super) fn render_overview(app: &mut App, rect: Rect, frame: &mut Frame) {
     frame.render_widget(paragraph, rect);
 }
 
-pub fn format_vote(vote: &Vote, own_vote: &Option<VoteData>) -> Span<'static> {
+/// Name of the phase in the language of the user.
+fn phase_name(phase: GamePhase) -> &'static str {
+    match phase {
+        GamePhase::Playing => tr("Playing"),
+        GamePhase::Revealed => tr("Waiting"),
+        GamePhase::Unknown => tr("Unknown"),
+    }
+}
+
+fn seconds_until(at: Option<Instant>) -> u64 {
+    at.map_or(0, |at| at.saturating_duration_since(Instant::now()).as_secs() + 1)
+}
+
+fn render_countdown(title: &str, message: &str, keys: &[(&str, &str)], rect: Rect, frame: &mut Frame) {
+    let inner = render_box_colored(title, Style::new().light_blue(), rect, frame);
+    let mut spans = vec![Span::raw(message.to_string())];
+    for (i, (key, description)) in keys.iter().enumerate() {
+        spans.push(Span::raw(if i == 0 { " " } else { " | " }));
+        spans.push(Span::raw(key.to_string()).bold());
+        spans.push(Span::raw(format!(" {}", description)));
+    }
+    frame.render_widget(Line::from(spans).centered(), inner);
+}
+
+/// Adds the confidence of a player as a small suffix after the vote.
+pub fn vote_with_confidence(vote: Span<'static>, confidence: Option<&u8>) -> Line<'static> {
+    match confidence {
+        Some(confidence) => Line::from(vec![vote, Span::raw(format!(" {}", format_confidence(*confidence, symbols().dot, symbols().empty_dot))).gray()]),
+        None => Line::from(vote),
+    }
+}
+
+/// The value `format_vote` compares the votes with for its arrows, `None` if arrows are off or there is nothing to compare.
+pub fn arrow_reference(arrows: VoteArrows, own_vote: &Option<VoteData>, average: f32) -> Option<f32> {
+    match arrows {
+        VoteArrows::Off => None,
+        VoteArrows::Own => match own_vote {
+            Some(VoteData::Number(n)) => Some(*n as f32),
+            _ => None,
+        },
+        VoteArrows::Average => Some(average).filter(|a| a.is_finite()),
+    }
+}
+
+/// The value `format_vote` colors the votes by, `None` if colors are off or there is nothing to compare.
+pub fn color_reference(colors: VoteColors, own_vote: &Option<VoteData>, average: f32) -> Option<f32> {
+    let comparison = match colors {
+        VoteColors::Own => VoteArrows::Own,
+        VoteColors::Average => VoteArrows::Average,
+        VoteColors::Off => VoteArrows::Off,
+    };
+    arrow_reference(comparison, own_vote, average)
+}
+
+/// Line explaining what the colors of `format_vote` compare with.
+fn color_legend(colors: VoteColors, theme: Theme) -> Line<'static> {
+    let same = match colors {
+        VoteColors::Own => tr("same as yours"),
+        VoteColors::Average => tr("same as the average"),
+        VoteColors::Off => return Line::from(tr(" Vote colors are off")).gray(),
+    };
+    let entry = |ordering, text: &'static str| [
+        Span::raw(" "),
+        Span::styled(symbols().dot, comparison_style(theme, ordering)),
+        Span::raw(format!(" {}", text)),
+    ];
+    Line::from([entry(Ordering::Equal, same), entry(Ordering::Greater, tr("higher")), entry(Ordering::Less, tr("lower"))].concat())
+}
+
+fn vote_arrow(vote: u8, reference: f32) -> String {
+    let vote = vote as f32;
+    if vote > reference {
+        format!(" {}", symbols().higher)
+    } else if vote < reference {
+        format!(" {}", symbols().lower)
+    } else {
+        String::new()
+    }
+}
+
+/// The vote, colored by comparing it with `color_reference` and with an arrow comparing it with `arrow_reference`.
+pub fn format_vote(vote: &Vote, color_reference: Option<f32>, theme: Theme, arrow_reference: Option<f32>) -> Span<'static> {
     match vote {
         Vote::Missing => { Span::raw("-").style(Style::new().red()) }
         Vote::Hidden => { Span::raw("#").style(Style::new().green()) }
         Vote::Revealed(data) => {
             match data {
                 VoteData::Number(n) => {
-                    let color = if let Some(VoteData::Number(n2)) = own_vote {
-                        if *n2 == *n {
-                            Style::new().green()
-                        } else if *n2 < *n {
-                            Style::new().light_blue()
-                        } else {
-                            Style::new().yellow()
-                        }
-                    } else {
-                        Style::new()
-                    };
-                    Span::raw(n.to_string()).style(color)
+                    let color = color_reference
+                        .and_then(|reference| (*n as f32).partial_cmp(&reference))
+                        .map_or(Style::new(), |ordering| comparison_style(theme, ordering));
+                    let arrow = arrow_reference.map_or(String::new(), |r| vote_arrow(*n, r));
+                    Span::raw(format!("{}{}", n, arrow)).style(color)
                 }
                 VoteData::Special(t) => {
                     if t.trim().is_empty() {
@@ -526,3 +1677,131 @@ pub fn format_vote(vote: &Vote, own_vote: &Option<VoteData>) -> Span<'static> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    #[test]
+    fn collapses_and_hides_server_events() {
+        let entry = |level, message: &str| LogEntry {
+            timestamp: Instant::now(), time: SystemTime::now(), level, message: message.to_string(), source: LogSource::Server, server_index: None,
+        };
+        let log = [
+            entry(LogLevel::Info, "alice joined the room"),
+            entry(LogLevel::Info, "bob played a card"),
+            entry(LogLevel::Info, "carol played a card"),
+            entry(LogLevel::Info, "bob joined the room"),
+            entry(LogLevel::Info, "dave played a card"),
+            entry(LogLevel::Chat, "alice: hi"),
+            entry(LogLevel::Info, "erin played a card"),
+        ];
+        let events = ServerEvents { join_leave: EventDisplay::Hide, cards: EventDisplay::Collapse, renames: EventDisplay::Show };
+        let messages: Vec<String> = collapse_events(log.iter(), &events).iter().map(|e| e.message.clone()).collect();
+        assert_eq!(messages, vec!["3 players changed their cards", "alice: hi", "erin played a card"]);
+    }
+
+    #[test]
+    fn formats_the_age_of_log_entries() {
+        assert_eq!(format_age(Duration::from_secs(59)), "now");
+        assert_eq!(format_age(Duration::from_secs(125)), "2m");
+        assert_eq!(format_age(Duration::from_secs(7300)), "2h");
+    }
+
+    #[test]
+    fn log_filter_cycles_through_all_filters() {
+        let entry = |level, source| LogEntry { timestamp: Instant::now(), time: SystemTime::now(), level, message: String::new(), source, server_index: None };
+        let filters: Vec<LogFilter> = std::iter::successors(Some(LogFilter::All), |f| Some(f.next()).filter(|f| *f != LogFilter::All)).collect();
+        assert_eq!(filters.len(), 4);
+
+        let join = entry(LogLevel::Info, LogSource::Server);
+        let chat = entry(LogLevel::Chat, LogSource::Server);
+        let error = entry(LogLevel::Error, LogSource::Client);
+        assert!(LogFilter::Server.matches(&join) && !LogFilter::Server.matches(&chat));
+        assert!(LogFilter::Chat.matches(&chat) && !LogFilter::Chat.matches(&join));
+        assert!(LogFilter::Errors.matches(&error) && !LogFilter::Server.matches(&error));
+    }
+
+    #[test]
+    fn quick_vote_hints_match_deck_positions() {
+        assert_eq!(quick_vote_hint(0), Some('1'));
+        assert_eq!(quick_vote_hint(9), Some('a'));
+        assert_eq!(quick_vote_hint(35), None);
+        assert_eq!(quick_vote_index('a'), Some(9));
+        assert_eq!(quick_vote_index('0'), None);
+        assert_eq!(QUICK_VOTE_CARDS.len(), QUICK_VOTE_HINTS.len());
+    }
+
+    #[test]
+    fn distribution_includes_special_votes() {
+        let deck = vec!["1".to_string(), "2".to_string(), "3".to_string()];
+        let players = vec![
+            Player::new("a", Vote::Revealed(VoteData::Number(2))),
+            Player::new("b", Vote::Revealed(VoteData::Special("☕".to_string()))),
+            Player::new("c", Vote::Revealed(VoteData::Number(2))),
+            Player::new("d", Vote::Missing),
+        ];
+
+        assert_eq!(vote_distribution(&players, &deck), vec![
+            ("1".to_string(), 0),
+            ("2".to_string(), 2),
+            ("3".to_string(), 0),
+            ("☕".to_string(), 1),
+        ]);
+    }
+
+    #[test]
+    fn arrows_compare_with_the_reference() {
+        let own = Some(VoteData::Number(5));
+        let vote = |n| Vote::Revealed(VoteData::Number(n));
+
+        assert_eq!(arrow_reference(VoteArrows::Off, &own, 3.0), None);
+        assert_eq!(arrow_reference(VoteArrows::Own, &own, 3.0), Some(5.0));
+        assert_eq!(arrow_reference(VoteArrows::Own, &None, 3.0), None);
+        assert_eq!(arrow_reference(VoteArrows::Average, &own, f32::NAN), None);
+
+        assert_eq!(format_vote(&vote(8), Some(5.0), Theme::Dark, Some(5.0)).content, "8 ▲");
+        assert_eq!(format_vote(&vote(3), Some(5.0), Theme::Dark, Some(5.0)).content, "3 ▼");
+        assert_eq!(format_vote(&vote(5), Some(5.0), Theme::Dark, Some(5.0)).content, "5");
+        assert_eq!(format_vote(&vote(8), Some(5.0), Theme::Dark, None).content, "8");
+    }
+
+    #[test]
+    fn colors_compare_with_the_reference() {
+        let own = Some(VoteData::Number(5));
+        let vote = |n| Vote::Revealed(VoteData::Number(n));
+        let color = |n, reference| format_vote(&vote(n), reference, Theme::Dark, None).style.fg;
+
+        assert_eq!(color_reference(VoteColors::Own, &own, 3.0), Some(5.0));
+        assert_eq!(color_reference(VoteColors::Average, &own, 3.0), Some(3.0));
+        assert_eq!(color_reference(VoteColors::Off, &own, 3.0), None);
+        assert_eq!(color(5, Some(5.0)), Some(Color::Green));
+        assert_eq!(color(8, Some(5.0)), Some(Color::LightBlue));
+        assert_eq!(color(3, Some(5.0)), Some(Color::Yellow));
+        assert_eq!(color(3, None), None);
+    }
+
+    #[test]
+    fn menu_leaves_out_reveal_and_restart_in_unknown_phases() {
+        assert!(menu_entries(GamePhase::Playing, false).contains(&"Reveal"));
+        assert!(menu_entries(GamePhase::Revealed, false).contains(&"Restart"));
+        let unknown = menu_entries(GamePhase::Unknown, false);
+        assert!(!unknown.contains(&"Reveal") && !unknown.contains(&"Restart") && !unknown.contains(&"Vote"));
+    }
+
+    #[test]
+    fn counts_reactions_and_parses_them_from_the_chat() {
+        let reactions = vec![
+            ("alice".to_string(), Reaction::Thinking),
+            ("bob".to_string(), Reaction::ThumbsUp),
+            ("carol".to_string(), Reaction::Thinking),
+        ];
+        assert_eq!(format_reactions(&reactions), "👍 1 🤔 2");
+        assert_eq!(format_reactions(&[]), "");
+        assert_eq!(parse_reaction(": !pp react surprised"), Some(Reaction::Surprised));
+        assert_eq!(parse_reaction(": !pp react dancing"), None);
+        assert_eq!(parse_reaction(": thumbsup"), None);
+    }
+}
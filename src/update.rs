@@ -1,14 +1,71 @@
 use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use self_update::{cargo_crate_version, Extract, self_replace};
+use self_update::backends::github::ReleaseList;
+use self_update::update::{Release, ReleaseAsset, ReleaseUpdate};
 use semver::Version;
+use serde::{Deserialize, Serialize};
 use snafu::Snafu;
 
+use crate::changelog::{self, ChangelogVersion};
+use crate::config::{get_datadir, UpdateChannel, UpdateSource};
+use crate::persist;
+
+/// How long "remind me later" postpones the update.
+const SNOOZE: Duration = Duration::from_secs(24 * 60 * 60);
+
 #[derive(Debug, PartialEq)]
 pub enum UpdateResult {
     UpToDate,
     Updated,
+    /// The user asked to be reminded later.
+    Snoozed,
+}
+
+/// When the update check ran last and until when it is snoozed, shared by all instances.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+struct CheckState {
+    /// Seconds since the unix epoch.
+    last_check: u64,
+    /// Seconds since the unix epoch.
+    snoozed_until: u64,
+}
+
+impl CheckState {
+    fn is_due(&self, now: u64, interval: Duration) -> bool {
+        now >= self.snoozed_until && now >= self.last_check.saturating_add(interval.as_secs())
+    }
+}
+
+fn check_state_file() -> PathBuf {
+    get_datadir().join("update.json")
+}
+
+fn parse_check_state(content: Option<String>) -> CheckState {
+    content.and_then(|content| serde_json::from_str(&content).ok()).unwrap_or_default()
+}
+
+fn update_check_state<F: FnOnce(&mut CheckState)>(f: F) {
+    let path = check_state_file();
+    let result = persist::update(&path, |content| {
+        let mut state = parse_check_state(content);
+        f(&mut state);
+        serde_json::to_string(&state).expect("Failed to serialize update check state")
+    });
+    if let Err(e) = result {
+        warn!("Failed to write update check state {:?}: {}", path, e);
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
 }
 
 #[derive(Debug, Snafu)]
@@ -42,61 +99,307 @@ impl From<semver::Error> for UpdateError {
     }
 }
 
-pub fn self_update() -> Result<UpdateResult, UpdateError> {
+/// Newest release by semantic version, including pre-releases.
+fn newest_release(releases: Vec<Release>) -> Option<Release> {
+    releases.into_iter()
+        .filter_map(|release| Version::parse(&release.version).ok().map(|version| (version, release)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, release)| release)
+}
 
-    let update = self_update::backends::github::Update::configure()
-        .repo_owner("ja-ko")
-        .repo_name("ppoker")
+fn configure(source: &UpdateSource) -> Result<Box<dyn ReleaseUpdate>, UpdateError> {
+    let mut builder = self_update::backends::github::Update::configure();
+    builder
+        .repo_owner(&source.owner)
+        .repo_name(&source.repo)
         .bin_name("ppoker")
         .show_download_progress(true)
         .current_version(cargo_crate_version!())
         .show_output(false)
-        .bin_path_in_archive("ppoker-{{ target }}/{{ bin }}")
-        .build()?;
+        .bin_path_in_archive("ppoker-{{ target }}/{{ bin }}");
+    if let Some(url) = &source.api_url {
+        builder.with_url(url);
+    }
+    if let Some(token) = &source.token {
+        builder.auth_token(token);
+    }
+    Ok(builder.build()?)
+}
+
+fn list_releases(source: &UpdateSource) -> Result<Vec<Release>, UpdateError> {
+    let mut builder = ReleaseList::configure();
+    builder.repo_owner(&source.owner).repo_name(&source.repo);
+    if let Some(url) = &source.api_url {
+        builder.with_url(url);
+    }
+    if let Some(token) = &source.token {
+        builder.auth_token(token);
+    }
+    Ok(builder.build()?.fetch()?)
+}
+
+/// Asks GitHub for a release of the channel to update to, `None` if the current version is up-to-date or the last
+/// check was less than `interval` ago.
+pub fn check_for_update(source: &UpdateSource, channel: &UpdateChannel, interval: Duration) -> Result<Option<Release>, UpdateError> {
+    let now = unix_now();
+    let state = parse_check_state(persist::read(&check_state_file())?);
+    if !state.is_due(now, interval) {
+        info!("Skipping the update check, the last check was recent or the update was snoozed.");
+        return Ok(None);
+    }
+    let update = configure(source)?;
 
     debug!("Current binary: v{} - {}", update.current_version(), update.target());
-    info!("Fetching update information.");
-    let latest_release =  update.get_latest_release()?;
+    info!("Fetching update information for the {:?} channel from {}/{}.", channel, source.owner, source.repo);
+    let latest_release = match channel {
+        UpdateChannel::Stable => update.get_latest_release()?,
+        UpdateChannel::Beta => {
+            match newest_release(list_releases(source)?) {
+                Some(release) => release,
+                None => return Ok(None),
+            }
+        }
+        UpdateChannel::Pinned(tag) => update.get_release_version(tag)?,
+    };
+    update_check_state(|state| state.last_check = now);
 
-    if Version::parse(latest_release.version.as_str())? <= Version::parse(update.current_version().as_str())? {
+    let latest_version = Version::parse(latest_release.version.as_str())?;
+    let current_version = Version::parse(update.current_version().as_str())?;
+    let up_to_date = match channel {
+        UpdateChannel::Pinned(_) => latest_version == current_version,
+        _ => latest_version <= current_version,
+    };
+    if up_to_date {
         info!("Application is up-to-date.");
-        return Ok(UpdateResult::UpToDate)
+        return Ok(None)
+    }
+    info!("Found release: v{}", latest_release.version);
+    Ok(Some(latest_release))
+}
+
+/// Release found by the background check with the notes of every release since the running version.
+#[derive(Debug, Clone)]
+pub struct AvailableUpdate {
+    pub release: Release,
+    pub notes: String,
+}
+
+/// Joins the notes of the releases after `current` up to `target`, newest first. Pre-releases other than `target` are
+/// left out, `None` if there are no such releases.
+fn combine_notes(releases: &[Release], current: &Version, target: &Version) -> Option<String> {
+    let mut skipped: Vec<(Version, &Release)> = releases.iter()
+        .filter_map(|release| Version::parse(&release.version).ok().map(|version| (version, release)))
+        .filter(|(version, _)| version > current && version <= target && (version.pre.is_empty() || version == target))
+        .collect();
+    if skipped.is_empty() {
+        return None;
+    }
+    skipped.sort_by(|(a, _), (b, _)| b.cmp(a));
+    Some(skipped.iter()
+        .map(|(version, release)| match release.body.as_deref().map(str::trim) {
+            Some(body) if !body.is_empty() => body.to_string(),
+            _ => format!("## v{}", version),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n"))
+}
+
+/// Notes of all releases the update skips, only those of `release` if the releases can't be fetched.
+fn release_notes_since(source: &UpdateSource, release: &Release) -> String {
+    let fallback = || release.body.clone().unwrap_or_default();
+    let (Ok(current), Ok(target)) = (Version::parse(cargo_crate_version!()), Version::parse(&release.version)) else {
+        return fallback();
+    };
+    match list_releases(source) {
+        Ok(releases) => combine_notes(&releases, &current, &target).unwrap_or_else(fallback),
+        Err(e) => {
+            info!("Failed to fetch the notes of the skipped releases, showing only v{}. {}", release.version, e);
+            fallback()
+        }
+    }
+}
+
+/// Checks for an update on a background thread, the receiver gets the release if there is one.
+pub fn check_in_background(source: UpdateSource, channel: UpdateChannel, interval: Duration) -> Receiver<AvailableUpdate> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        match check_for_update(&source, &channel, interval) {
+            Ok(Some(release)) => {
+                let notes = release_notes_since(&source, &release);
+                let _ = sender.send(AvailableUpdate { release, notes });
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to check for updates. {}", e),
+        }
+    });
+    receiver
+}
+
+/// Checks for an update and installs it, blocking until done.
+pub fn self_update(source: &UpdateSource, channel: &UpdateChannel, interval: Duration) -> Result<UpdateResult, UpdateError> {
+    match check_for_update(source, channel, interval)? {
+        Some(release) => install_update(source, &release, channel),
+        None => Ok(UpdateResult::UpToDate),
+    }
+}
+
+pub fn channel_name(channel: &UpdateChannel) -> String {
+    match channel {
+        UpdateChannel::Stable => "stable".to_owned(),
+        UpdateChannel::Beta => "beta (including pre-releases)".to_owned(),
+        UpdateChannel::Pinned(tag) => format!("pinned to {}", tag),
     }
-    info!("Found newer release: v{}", latest_release.version);
+}
+
+/// Postpones the update, the update check is skipped until then.
+pub fn snooze() {
+    info!("User snoozed the update.");
+    update_check_state(|state| state.snoozed_until = unix_now() + SNOOZE.as_secs());
+}
+
+fn find_asset(update: &dyn ReleaseUpdate, release: &Release) -> Result<ReleaseAsset, UpdateError> {
+    match release.asset_for(update.target().as_str(), None) {
+        Some(asset) => Ok(asset),
+        None => {
+            error!("Release {} did not contain asset for target {}", release.name, update.target().as_str());
+            Err(UpdateError::NoCompatibleAssetFound)
+        }
+    }
+}
 
-    let asset = if let Some(asset) = latest_release.asset_for(update.target().as_str(), None) {
-        asset
-    } else {
-        error!("Release {} did not contain asset for target {}", latest_release.name, update.target().as_str());
-        return Err(UpdateError::NoCompatibleAssetFound.into());
+/// Lists the features and fixes of the release notes below the update prompt.
+fn print_changes(versions: &[ChangelogVersion]) {
+    let Some(summary) = changelog::summary(versions) else {
+        return;
     };
+    println!("\nChanges ({}):", summary);
+    for version in versions {
+        match &version.date {
+            Some(date) => println!("  v{} ({})", version.version, date),
+            None => println!("  v{}", version.version),
+        }
+        for section in version.sections.iter().filter(|s| s.heading == "Features" || s.heading == "Bug Fixes") {
+            for item in &section.items {
+                println!("    * {}", changelog::plain_item(item));
+            }
+        }
+    }
+}
+
+/// Asks the user for confirmation and replaces the binary with the one of the release.
+pub fn install_update(source: &UpdateSource, latest_release: &Release, channel: &UpdateChannel) -> Result<UpdateResult, UpdateError> {
+    let update = configure(source)?;
+    let asset = find_asset(update.as_ref(), latest_release)?;
 
     println!("\nNew release found:");
+    println!("  * Update channel: {}", channel_name(channel));
     println!("  * Current release is: v{}", update.current_version());
     println!("  * Found release: {} v{}", asset.name, latest_release.version);
     println!("  * Download url: {}", asset.download_url);
+    print_changes(&changelog::parse(latest_release.body.as_deref().unwrap_or_default()));
     println!("\nThe new release will be downloaded and the existing binary will be replaced.");
-    print!("\nDo you want to continue? [Y/n] ");
+    print!("\nDo you want to continue? [Y/n, l to remind me later] ");
     ::std::io::Write::flush(&mut ::std::io::stdout())?;
 
     let mut s = String::new();
     io::stdin().read_line(&mut s)?;
     let s = s.trim().to_lowercase();
+    if s == "l" {
+        snooze();
+        return Ok(UpdateResult::Snoozed);
+    }
     if !s.is_empty() && s != "y" {
         info!("User aborted update.");
         return Err(UpdateError::UserCanceled.into());
     }
 
+    replace_binary(source, update.as_ref(), &asset, None)?;
+    info!("Update to v{} done.", latest_release.version);
+
+    Ok(UpdateResult::Updated)
+}
+
+/// State of an update installed from the tui.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstallProgress {
+    Downloading { received: u64 },
+    Installing,
+    Done,
+    Failed(String),
+}
+
+/// Counts the downloaded bytes for the progress shown in the tui.
+struct ProgressWriter<W: Write> {
+    inner: W,
+    received: u64,
+    progress: Arc<Mutex<InstallProgress>>,
+}
+
+impl<W: Write> Write for ProgressWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.received += written as u64;
+        *self.progress.lock().expect("Install progress poisoned") = InstallProgress::Downloading { received: self.received };
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Downloads and installs the release on a background thread without asking, the tui asked already.
+pub fn install_in_background(source: UpdateSource, release: Release) -> Arc<Mutex<InstallProgress>> {
+    let progress = Arc::new(Mutex::new(InstallProgress::Downloading { received: 0 }));
+    {
+        let progress = progress.clone();
+        thread::spawn(move || {
+            let result = configure(&source)
+                .and_then(|update| {
+                    let asset = find_asset(update.as_ref(), &release)?;
+                    replace_binary(&source, update.as_ref(), &asset, Some(&progress))
+                });
+            let state = match result {
+                Ok(()) => {
+                    info!("Update to v{} done.", release.version);
+                    InstallProgress::Done
+                }
+                Err(e) => {
+                    error!("Failed to update the application. {}", e);
+                    InstallProgress::Failed(e.to_string())
+                }
+            };
+            *progress.lock().expect("Install progress poisoned") = state;
+        });
+    }
+    progress
+}
+
+/// Downloads the asset and swaps the running binary, showing the download progress on stdout or passing it on.
+fn replace_binary(source: &UpdateSource, update: &dyn ReleaseUpdate, asset: &ReleaseAsset, progress: Option<&Arc<Mutex<InstallProgress>>>) -> Result<(), UpdateError> {
     let tmp_dir = tempfile::TempDir::new()?;
     let tmp_tarball_path = tmp_dir.path().join(&asset.name);
     let tmp_tarball = ::std::fs::File::create(&tmp_tarball_path)?;
 
     info!("Downloading release asset to {:?}.", tmp_tarball_path);
 
-    self_update::Download::from_url(&asset.download_url)
-        .set_header(reqwest::header::ACCEPT, "application/octet-stream".parse().unwrap())
-        .show_progress(true)
-        .download_to(&tmp_tarball)?;
+    let mut download = self_update::Download::from_url(&asset.download_url);
+    download.set_header(reqwest::header::ACCEPT, "application/octet-stream".parse().unwrap());
+    if let Some(token) = &source.token {
+        match format!("token {}", token).parse() {
+            Ok(value) => { download.set_header(reqwest::header::AUTHORIZATION, value); }
+            Err(_) => warn!("The update token contains characters not allowed in a header, downloading without it."),
+        }
+    }
+    match progress {
+        Some(progress) => {
+            download.download_to(ProgressWriter { inner: &tmp_tarball, received: 0, progress: progress.clone() })?;
+            *progress.lock().expect("Install progress poisoned") = InstallProgress::Installing;
+        }
+        None => {
+            download.show_progress(true).download_to(&tmp_tarball)?;
+        }
+    }
 
     let path_in_archive = format!("ppoker-{}/{}", update.target(), update.bin_name());
     let filename = path_in_archive.as_str();
@@ -107,7 +410,65 @@ pub fn self_update() -> Result<UpdateResult, UpdateError> {
 
     info!("Replacing binary file {:?} with {:?}", update.bin_install_path(), binary);
     self_replace::self_replace(binary)?;
-    info!("Update to v{} done.", latest_release.version);
+    Ok(())
+}
 
-    Ok(UpdateResult::Updated)
-}
\ No newline at end of file
+/// Starts the updated binary with the same arguments and waits for it.
+pub fn restart() -> io::Result<()> {
+    let mut args = std::env::args_os();
+    let program = args.next().unwrap_or_else(|| "ppoker".into());
+    info!("Restarting {:?} after the update.", program);
+    let status = std::process::Command::new(program).args(args).status()?;
+    if !status.success() {
+        warn!("Restarted application exited with {}.", status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_after_interval_and_snooze() {
+        let hour = Duration::from_secs(60 * 60);
+        assert!(CheckState::default().is_due(1_700_000_000, hour));
+
+        let state = CheckState { last_check: 1000, snoozed_until: 0 };
+        assert!(state.is_due(1000, Duration::ZERO));
+        assert!(!state.is_due(1000 + 3599, hour));
+        assert!(state.is_due(1000 + 3600, hour));
+
+        let state = CheckState { last_check: 0, snoozed_until: 5000 };
+        assert!(!state.is_due(4999, Duration::ZERO));
+        assert!(state.is_due(5000, Duration::ZERO));
+    }
+
+    #[test]
+    fn combines_notes_of_skipped_releases() {
+        let release = |version: &str, body: Option<&str>| Release {
+            version: version.to_string(),
+            body: body.map(str::to_string),
+            ..Release::default()
+        };
+        let releases = vec![
+            release("0.5.0", Some("five")),
+            release("0.4.1", None),
+            release("0.4.1-rc.1", Some("rc")),
+            release("0.4.0", Some("four\n")),
+            release("0.3.0", Some("three")),
+        ];
+        let version = |v: &str| Version::parse(v).unwrap();
+
+        assert_eq!(combine_notes(&releases, &version("0.3.0"), &version("0.4.1")), Some("## v0.4.1\n\nfour".to_string()));
+        assert_eq!(combine_notes(&releases, &version("0.4.0"), &version("0.4.1-rc.1")), Some("rc".to_string()));
+        assert_eq!(combine_notes(&releases, &version("0.5.0"), &version("0.3.0")), None);
+    }
+
+    #[test]
+    fn ignores_broken_state() {
+        assert_eq!(parse_check_state(Some("{".to_string())), CheckState::default());
+        assert_eq!(parse_check_state(Some(r#"{"last_check":1,"snoozed_until":2}"#.to_string())),
+            CheckState { last_check: 1, snoozed_until: 2 });
+    }
+}
@@ -1,19 +1,59 @@
-use std::thread;
-use std::time::Duration;
+use std::{mem, thread};
+use std::time::{Duration, Instant};
 
-use log::{error, info};
+use log::{error, info, warn};
 use snafu::Snafu;
 
 use crate::app::AppResult;
 use crate::config::Config;
-use crate::models::{LogEntry, Room};
+use crate::i18n::{tr, tr_args};
+#[cfg(unix)]
+use crate::ipc::IpcServer;
+use crate::models::{LogEntry, Room, UserType as AppUserType};
 use crate::web::client::ClientError::{ServerClosedConnection, ServerUpdateMissing};
-use crate::web::dto::UserRequest;
+use crate::web::dto;
+use crate::web::dto::{UserRequest, UserType};
+use crate::web::latency::LatencyStats;
+use crate::web::outbox::Outbox;
 use crate::web::ws::{IncomingMessage, PokerSocket};
 
+pub trait PokerClient {
+    fn get_updates(&mut self) -> AppResult<(Vec<Room>, Vec<LogEntry>)>;
+    fn vote(&mut self, card_value: Option<&str>) -> AppResult<()>;
+    fn change_name(&mut self, name: &str) -> AppResult<()>;
+    fn chat(&mut self, message: &str) -> AppResult<()>;
+    fn change_user_type(&mut self, user_type: AppUserType) -> AppResult<()>;
+    fn reveal(&mut self) -> AppResult<()>;
+    fn reset(&mut self) -> AppResult<()>;
+
+    /// Errors and unknown messages the server sent since the last call.
+    fn take_server_errors(&mut self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Chat messages waiting to be sent because of the rate limit.
+    fn queued_messages(&self) -> usize {
+        0
+    }
+
+    /// Round trip times to the server, `None` if nothing was measured yet.
+    fn latency(&self) -> Option<LatencyStats> {
+        None
+    }
+
+    /// Read-only clients only mirror a room and can't send any requests.
+    fn is_read_only(&self) -> bool {
+        false
+    }
+}
+
 #[derive(Debug)]
-pub struct PokerClient {
+pub struct WebPokerClient {
     pub socket: PokerSocket,
+    #[cfg(unix)]
+    mirror: Option<IpcServer>,
+    outbox: Outbox,
+    server_errors: Vec<String>,
 }
 
 #[derive(Debug, Snafu)]
@@ -22,23 +62,67 @@ pub enum ClientError {
     ServerUpdateMissing,
     #[snafu(display("Server closed connection."))]
     ServerClosedConnection,
+    #[snafu(display("Server did not answer the ping in time."))]
+    PongMissing,
 }
 
+pub(crate) fn convert_initial_room(room: &dto::Room) -> (Room, Vec<LogEntry>) {
+    (room.into(), room.log.iter().enumerate().map(|(i, l)| {
+        let mut result: LogEntry = l.into();
+        result.server_index = Some(i as u32);
+        result
+    }).collect())
+}
 
-impl PokerClient {
+pub(crate) fn convert_room_updates(rooms: &[dto::Room]) -> (Vec<Room>, Vec<LogEntry>) {
+    let mut result = vec![];
+    let mut log_results = vec![];
+
+    for room in rooms {
+        let logs: Vec<LogEntry> = room.log.iter()
+            .map(|l| l.into())
+            .collect();
+        for i in 0..logs.len() {
+            if log_results.len() == i {
+                let mut entry = logs[i].clone();
+                entry.server_index = Some(i as u32);
+                log_results.push(entry);
+            }
+        }
+        result.push(room.into());
+    }
+
+    (result, log_results)
+}
+
+impl WebPokerClient {
     pub fn new(config: &Config) -> AppResult<(Self, Room, Vec<LogEntry>)> {
+        Self::connect(config, UserType::Participant)
+    }
+
+    pub fn connect(config: &Config, user_type: UserType) -> AppResult<(Self, Room, Vec<LogEntry>)> {
         let mut result = Self {
-            socket: PokerSocket::connect(config)?
+            socket: PokerSocket::connect(config, user_type)?,
+            #[cfg(unix)]
+            mirror: match &config.ipc_socket {
+                Some(path) => Some(IpcServer::bind(path)?),
+                None => None,
+            },
+            outbox: Outbox::default(),
+            server_errors: vec![],
         };
+        #[cfg(not(unix))]
+        if config.ipc_socket.is_some() {
+            error!("IPC sockets are only supported on unix platforms.");
+        }
+
         for i in 0..20 {
             let room_update = result.socket.read()?;
             if let Some(IncomingMessage::RoomUpdate(room)) = room_update {
                 info!("Got initial room state with delay {}ms.", i * 20);
-                return Ok((result, (&room).into(), (&room.log).iter().enumerate().map(|(i, l)| {
-                    let mut result: LogEntry = l.into();
-                    result.server_index = Some(i as u32);
-                    result
-                }).collect()));
+                result.publish(&room);
+                let (room, log) = convert_initial_room(&room);
+                return Ok((result, room, log));
             } else {
                 thread::sleep(Duration::from_millis(20));
             }
@@ -48,55 +132,115 @@ impl PokerClient {
         return Err(Box::new(ServerUpdateMissing));
     }
 
-    pub fn get_updates(&mut self) -> AppResult<(Vec<Room>, Vec<LogEntry>)> {
+    /// Sends the votes and chat messages the outbox held back once they are due.
+    fn send_due(&mut self) -> AppResult<()> {
+        let now = Instant::now();
+        if let Some(vote) = self.outbox.due_vote(now) {
+            self.socket.send_request(UserRequest::PlayCard { card_value: vote.as_deref() })?;
+        }
+        if let Some(message) = self.outbox.due_chat(now) {
+            self.socket.send_request(UserRequest::ChatMessage { message: &message })?;
+        }
+        Ok(())
+    }
+
+    /// Sends the held back vote right away, so a reveal or a new round doesn't overtake it.
+    fn send_pending_vote(&mut self) -> AppResult<()> {
+        if let Some(vote) = self.outbox.take_vote(Instant::now()) {
+            self.socket.send_request(UserRequest::PlayCard { card_value: vote.as_deref() })?;
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn publish(&mut self, room: &dto::Room) {
+        if let Some(mirror) = &self.mirror {
+            if let Err(e) = mirror.publish(room) {
+                error!("Failed to publish room state to followers: {}", e);
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn publish(&mut self, _room: &dto::Room) {}
+}
+
+impl PokerClient for WebPokerClient {
+    fn get_updates(&mut self) -> AppResult<(Vec<Room>, Vec<LogEntry>)> {
+        self.send_due()?;
         let messages = self.socket.read_all()?;
-        let mut result = vec![];
-        let mut log_results = vec![];
+        let mut rooms = vec![];
 
         for message in messages {
-            match &message {
+            match message {
                 IncomingMessage::Close => {
                     info!("Server closed connection. Terminating.");
                     return Err(Box::new(ServerClosedConnection));
                 }
                 IncomingMessage::RoomUpdate(room) => {
-                    let logs: Vec<LogEntry> = room.log.iter()
-                        .map(|l| l.into())
-                        .collect();
-                    for i in 0..logs.len() {
-                        if log_results.len() == i {
-                            let mut entry = logs[i].clone();
-                            entry.server_index = Some(i as u32);
-                            log_results.push(entry);
-                        }
-                    }
-                    result.push(room.into());
+                    self.publish(&room);
+                    rooms.push(room);
+                }
+                IncomingMessage::Error(error) => {
+                    warn!("Server reported an error: {}", error);
+                    self.server_errors.push(tr_args("The server reported an error: {}", &[&error]));
+                }
+                IncomingMessage::Unknown(raw) => {
+                    warn!("Got an unknown message from the server: {}", raw);
+                    self.server_errors.push(tr("The server sent a message this version does not understand.").to_string());
+                }
+                IncomingMessage::Invalid(error) => {
+                    self.server_errors.push(tr_args("Skipped a message from the server that failed to parse: {}", &[&error]));
                 }
             }
         }
 
-        Ok((result, log_results))
+        Ok(convert_room_updates(&rooms))
     }
 
-    pub fn vote(&mut self, card_value: Option<&str>) -> AppResult<()> {
-        self.socket.send_request(UserRequest::PlayCard { card_value })?;
+    fn vote(&mut self, card_value: Option<&str>) -> AppResult<()> {
+        if let Some(vote) = self.outbox.vote(card_value, Instant::now()) {
+            self.socket.send_request(UserRequest::PlayCard { card_value: vote.as_deref() })?;
+        }
 
         Ok(())
     }
 
-    pub fn change_name(&mut self, name: &str) -> AppResult<()> {
+    fn change_name(&mut self, name: &str) -> AppResult<()> {
         self.socket.send_request(UserRequest::ChangeName { name })
     }
 
-    pub fn chat(&mut self, message: &str) -> AppResult<()> {
-        self.socket.send_request(UserRequest::ChatMessage { message })
+    fn chat(&mut self, message: &str) -> AppResult<()> {
+        match self.outbox.chat(message, Instant::now()) {
+            Some(message) => self.socket.send_request(UserRequest::ChatMessage { message: &message }),
+            None => Ok(()),
+        }
+    }
+
+    fn change_user_type(&mut self, user_type: AppUserType) -> AppResult<()> {
+        self.send_pending_vote()?;
+        self.socket.send_request(UserRequest::ChangeUserType { user_type: user_type.into() })
     }
 
-    pub fn reveal(&mut self) -> AppResult<()> {
+    fn reveal(&mut self) -> AppResult<()> {
+        self.send_pending_vote()?;
         self.socket.send_request(UserRequest::RevealCards)
     }
 
-    pub fn reset(&mut self) -> AppResult<()> {
+    fn reset(&mut self) -> AppResult<()> {
+        self.send_pending_vote()?;
         self.socket.send_request(UserRequest::StartNewRound)
     }
-}
\ No newline at end of file
+
+    fn queued_messages(&self) -> usize {
+        self.outbox.queued_chats()
+    }
+
+    fn take_server_errors(&mut self) -> Vec<String> {
+        mem::take(&mut self.server_errors)
+    }
+
+    fn latency(&self) -> Option<LatencyStats> {
+        self.socket.latency()
+    }
+}
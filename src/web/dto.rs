@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Instant, SystemTime};
 
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +14,8 @@ pub enum UserType {
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct User {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
     pub username: String,
     pub user_type: UserType,
     pub your_user: bool,
@@ -25,6 +27,9 @@ pub struct User {
 pub enum GamePhase {
     Playing,
     CardsRevealed,
+    /// Phases added to the server after this client was released.
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone)]
@@ -47,6 +52,7 @@ impl Into<AppLogEntry> for &LogEntry {
     fn into(self) -> AppLogEntry {
         AppLogEntry {
             timestamp: Instant::now(),
+            time: SystemTime::now(),
             level: match self.level {
                 LogLevel::Chat => { AppLogLevel::Chat }
                 LogLevel::Info => { AppLogLevel::Info }
@@ -95,6 +101,7 @@ impl Into<AppGamePhase> for GamePhase {
         match self {
             GamePhase::CardsRevealed => AppGamePhase::Revealed,
             GamePhase::Playing => AppGamePhase::Playing,
+            GamePhase::Unknown => AppGamePhase::Unknown,
         }
     }
 }
@@ -108,6 +115,15 @@ impl Into<AppUserType> for UserType {
     }
 }
 
+impl From<AppUserType> for UserType {
+    fn from(value: AppUserType) -> Self {
+        match value {
+            AppUserType::Spectator => UserType::Spectator,
+            AppUserType::Player => UserType::Participant,
+        }
+    }
+}
+
 impl Into<Player> for &User {
     fn into(self) -> Player {
         let vote = if self.your_user && self.card_value.eq("") {
@@ -117,6 +133,7 @@ impl Into<Player> for &User {
         };
 
         Player {
+            id: self.user_id.clone(),
             vote,
             name: self.username.clone(),
             is_you: self.your_user,
@@ -147,10 +164,35 @@ pub enum UserRequest<'a> {
     },
     ChangeName { name: &'a str },
     ChatMessage { message: &'a str },
+    ChangeUserType {
+        #[serde(rename = "userType")]
+        user_type: UserType,
+    },
+    RevealCards,
+    StartNewRound,
+}
+
+/// The owned counterpart of `UserRequest`, for reading the requests of other clients.
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "requestType")]
+pub enum OwnedUserRequest {
+    PlayCard {
+        #[serde(rename = "cardValue")]
+        card_value: Option<String>,
+    },
+    ChangeName { name: String },
+    ChatMessage { message: String },
+    ChangeUserType {
+        #[serde(rename = "userType")]
+        user_type: UserType,
+    },
     RevealCards,
     StartNewRound,
 }
 
+/// Deck of the rooms that don't bring their own, fibonacci up to 100.
+pub const DEFAULT_DECK: [&str; 11] = ["0", "1", "2", "3", "5", "8", "13", "21", "40", "100", "?"];
+
 #[cfg(test)]
 mod tests {
     use assert_json_diff::assert_json_eq;
@@ -164,11 +206,13 @@ mod tests {
             deck: vec!["1".to_string(), "2".to_string(), "3".to_string(), "5".to_string()],
             game_phase: GamePhase::Playing,
             users: vec![User {
+                user_id: Some("a1b2".to_string()),
                 username: "user 1".to_string(),
                 user_type: UserType::Participant,
                 your_user: true,
                 card_value: "13".to_string(),
             }, User {
+                user_id: None,
                 username: "user 2".to_string(),
                 user_type: UserType::Spectator,
                 your_user: false,
@@ -194,6 +238,18 @@ mod tests {
         assert_json_eq!(expected, request);
     }
 
+    #[test]
+    fn test_change_user_type_request() {
+        let request = UserRequest::ChangeUserType { user_type: UserType::Spectator };
+        let expected = json!(
+            {
+              "requestType": "ChangeUserType",
+              "userType": "SPECTATOR"
+            }
+        );
+        assert_json_eq!(expected, request);
+    }
+
     #[test]
     fn json_structure() {
         let room = room_fixture();
@@ -210,6 +266,7 @@ mod tests {
   "gamePhase": "PLAYING",
   "users": [
     {
+      "userId": "a1b2",
       "username": "user 1",
       "userType": "PARTICIPANT",
       "yourUser": true,
@@ -234,4 +291,11 @@ mod tests {
         println!("{}", serde_json::to_string_pretty(&room).unwrap());
         assert_json_eq!(room, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn unknown_game_phases_are_accepted() {
+        let phase: GamePhase = serde_json::from_value(json!("VOTING_CLOSED")).unwrap();
+        assert_eq!(phase, GamePhase::Unknown);
+        assert_eq!(Into::<AppGamePhase>::into(phase), AppGamePhase::Unknown);
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Round trips kept for the statistics, at one ping every few seconds this covers the last minute.
+const WINDOW: usize = 6;
+
+/// Round trip times of the last pings to the server.
+#[derive(Debug, Default)]
+pub struct Latency {
+    samples: VecDeque<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyStats {
+    pub min: Duration,
+    pub avg: Duration,
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    /// Difference between the slowest and the fastest round trip, high values point to an unstable link.
+    pub fn jitter(&self) -> Duration {
+        self.max - self.min
+    }
+}
+
+impl Latency {
+    pub fn record(&mut self, round_trip: Duration) {
+        if self.samples.len() == WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(round_trip);
+    }
+
+    /// Minimum, average and maximum of the window, `None` before the first pong.
+    pub fn stats(&self) -> Option<LatencyStats> {
+        let min = *self.samples.iter().min()?;
+        let max = *self.samples.iter().max()?;
+        let avg = self.samples.iter().sum::<Duration>() / self.samples.len() as u32;
+        Some(LatencyStats { min, avg, max })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_a_rolling_window() {
+        let mut latency = Latency::default();
+        assert_eq!(latency.stats(), None);
+
+        for ms in [500, 20, 40, 30, 10, 60, 50] {
+            latency.record(Duration::from_millis(ms));
+        }
+        let stats = latency.stats().unwrap();
+        assert_eq!(stats.min, Duration::from_millis(10));
+        assert_eq!(stats.avg, Duration::from_millis(35));
+        assert_eq!(stats.max, Duration::from_millis(60));
+        assert_eq!(stats.jitter(), Duration::from_millis(50));
+    }
+}
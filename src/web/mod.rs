@@ -1,3 +1,7 @@
 pub(crate) mod client;
+pub(crate) mod peer;
 pub(crate) mod ws;
-pub(crate) mod dto;
\ No newline at end of file
+pub(crate) mod dto;
+pub(crate) mod outbox;
+pub(crate) mod latency;
+pub(crate) mod protocol;
\ No newline at end of file
@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Votes within this interval of the previous one are coalesced, only the last of them is sent.
+const VOTE_INTERVAL: Duration = Duration::from_millis(300);
+/// Chat messages are sent at most once per interval, the rest waits in the queue.
+const CHAT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Paces the requests of a client, so mashing keys doesn't send a burst of them to the server.
+#[derive(Debug, Default)]
+pub struct Outbox {
+    last_vote: Option<Instant>,
+    /// Vote superseding the one sent last, `Some(None)` retracts it.
+    pending_vote: Option<Option<String>>,
+    last_chat: Option<Instant>,
+    chats: VecDeque<String>,
+}
+
+fn is_due(last: Option<Instant>, interval: Duration, now: Instant) -> bool {
+    last.is_none_or(|last| now.saturating_duration_since(last) >= interval)
+}
+
+impl Outbox {
+    /// Returns the vote if it may be sent right away, otherwise it replaces the pending vote.
+    pub fn vote(&mut self, card: Option<&str>, now: Instant) -> Option<Option<String>> {
+        let card = card.map(str::to_string);
+        if self.pending_vote.is_none() && is_due(self.last_vote, VOTE_INTERVAL, now) {
+            self.last_vote = Some(now);
+            Some(card)
+        } else {
+            self.pending_vote = Some(card);
+            None
+        }
+    }
+
+    /// Returns the message if it may be sent right away, otherwise it is queued.
+    pub fn chat(&mut self, message: &str, now: Instant) -> Option<String> {
+        if self.chats.is_empty() && is_due(self.last_chat, CHAT_INTERVAL, now) {
+            self.last_chat = Some(now);
+            Some(message.to_string())
+        } else {
+            self.chats.push_back(message.to_string());
+            None
+        }
+    }
+
+    /// The pending vote once its interval has passed.
+    pub fn due_vote(&mut self, now: Instant) -> Option<Option<String>> {
+        if !is_due(self.last_vote, VOTE_INTERVAL, now) {
+            return None;
+        }
+        let vote = self.pending_vote.take()?;
+        self.last_vote = Some(now);
+        Some(vote)
+    }
+
+    /// The pending vote regardless of its interval, for requests that must not overtake it.
+    pub fn take_vote(&mut self, now: Instant) -> Option<Option<String>> {
+        let vote = self.pending_vote.take()?;
+        self.last_vote = Some(now);
+        Some(vote)
+    }
+
+    /// The oldest queued message once the interval since the last one has passed.
+    pub fn due_chat(&mut self, now: Instant) -> Option<String> {
+        if !is_due(self.last_chat, CHAT_INTERVAL, now) {
+            return None;
+        }
+        let message = self.chats.pop_front()?;
+        self.last_chat = Some(now);
+        Some(message)
+    }
+
+    pub fn queued_chats(&self) -> usize {
+        self.chats.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn votes_in_a_burst_are_coalesced() {
+        let mut outbox = Outbox::default();
+        let start = Instant::now();
+
+        assert_eq!(outbox.vote(Some("1"), start), Some(Some("1".to_string())));
+        assert_eq!(outbox.vote(Some("2"), start + Duration::from_millis(50)), None);
+        assert_eq!(outbox.vote(Some("3"), start + Duration::from_millis(100)), None);
+        assert_eq!(outbox.due_vote(start + Duration::from_millis(200)), None);
+        assert_eq!(outbox.due_vote(start + VOTE_INTERVAL), Some(Some("3".to_string())));
+        assert_eq!(outbox.due_vote(start + VOTE_INTERVAL * 3), None);
+        assert_eq!(outbox.vote(None, start + VOTE_INTERVAL * 3), Some(None));
+    }
+
+    #[test]
+    fn chat_messages_keep_their_order() {
+        let mut outbox = Outbox::default();
+        let start = Instant::now();
+
+        assert_eq!(outbox.chat("first", start).as_deref(), Some("first"));
+        assert_eq!(outbox.chat("second", start), None);
+        assert_eq!(outbox.chat("third", start + CHAT_INTERVAL * 2), None);
+        assert_eq!(outbox.queued_chats(), 2);
+        assert_eq!(outbox.due_chat(start + CHAT_INTERVAL * 2).as_deref(), Some("second"));
+        assert_eq!(outbox.due_chat(start + CHAT_INTERVAL * 2), None);
+        assert_eq!(outbox.due_chat(start + CHAT_INTERVAL * 3).as_deref(), Some("third"));
+    }
+}
@@ -0,0 +1,415 @@
+//! Peer mode of `ppoker lan`: the members of a room in the local network find each other over mDNS and send their
+//! requests directly to each other, without a server. Every peer keeps its own copy of the room and builds the room
+//! updates from it that the server would send.
+
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::app::{random, AppResult};
+use crate::config::Config;
+use crate::discovery::Discovery;
+use crate::models::{LogEntry as AppLogEntry, Room as AppRoom, UserType as AppUserType};
+use crate::web::client::{convert_initial_room, convert_room_updates, PokerClient};
+use crate::web::dto::{GamePhase, LogEntry, LogLevel, OwnedUserRequest, Room, User, UserRequest, UserType, DEFAULT_DECK};
+
+/// Interval of the messages that keep the peers in sync while nothing happens.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/// Peers not heard of for this long left the room.
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// One datagram between the peers, with a `UserRequest` when sending and an `OwnedUserRequest` when receiving.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct PeerMessage<R> {
+    room_id: String,
+    /// Rounds started in the room so far, the peers follow the one furthest ahead.
+    round: u64,
+    game_phase: GamePhase,
+    /// The sender, with its card even before the reveal. The others hide it until then.
+    user: User,
+    /// What the sender just did, `None` for the heartbeats.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request: Option<R>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Member {
+    name: String,
+    user_type: UserType,
+    card: Option<String>,
+}
+
+#[derive(Debug)]
+struct Peer {
+    id: String,
+    member: Member,
+    last_seen: Instant,
+}
+
+/// The room as one peer sees it.
+#[derive(Debug)]
+struct PeerRoom {
+    id: String,
+    room_id: String,
+    deck: Vec<String>,
+    round: u64,
+    phase: GamePhase,
+    you: Member,
+    /// The others in the order they joined.
+    peers: Vec<Peer>,
+    log: Vec<LogEntry>,
+}
+
+impl PeerRoom {
+    fn info(&mut self, message: String) {
+        self.log.push(LogEntry { level: LogLevel::Info, message });
+    }
+
+    fn start_round(&mut self, round: u64) {
+        self.round = round;
+        self.phase = GamePhase::Playing;
+        self.you.card = None;
+        self.peers.iter_mut().for_each(|p| p.member.card = None);
+    }
+
+    fn message<'a>(&'a self, request: Option<UserRequest<'a>>) -> PeerMessage<UserRequest<'a>> {
+        PeerMessage {
+            room_id: self.room_id.clone(),
+            round: self.round,
+            game_phase: self.phase,
+            user: User {
+                user_id: Some(self.id.clone()),
+                username: self.you.name.clone(),
+                user_type: self.you.user_type,
+                your_user: false,
+                card_value: self.you.card.clone().unwrap_or_default(),
+            },
+            request,
+        }
+    }
+
+    /// Applies a request of this peer, like the server would before passing it on.
+    fn apply(&mut self, request: &UserRequest) {
+        let name = self.you.name.clone();
+        match *request {
+            UserRequest::PlayCard { card_value } => {
+                self.you.card = card_value.filter(|_| self.you.user_type == UserType::Participant).map(str::to_string);
+            }
+            UserRequest::ChangeName { name: new_name } => {
+                self.you.name = new_name.to_string();
+                self.info(format!("{} is now known as {}.", name, new_name));
+            }
+            UserRequest::ChatMessage { message } => {
+                self.log.push(LogEntry { level: LogLevel::Chat, message: format!("{}: {}", name, message) });
+            }
+            UserRequest::ChangeUserType { user_type } => {
+                self.you.user_type = user_type;
+                if user_type == UserType::Spectator {
+                    self.you.card = None;
+                }
+            }
+            UserRequest::RevealCards => {
+                self.phase = GamePhase::CardsRevealed;
+                self.info(format!("{} revealed the cards.", name));
+            }
+            UserRequest::StartNewRound => {
+                self.start_round(self.round + 1);
+                self.info(format!("{} started a new round.", name));
+            }
+        }
+    }
+
+    /// Takes over the state of another peer. A peer further ahead brings the room to its round, the card of a peer
+    /// behind is from an earlier round and doesn't count.
+    fn receive(&mut self, message: PeerMessage<OwnedUserRequest>, now: Instant) {
+        let Some(id) = message.user.user_id.filter(|id| *id != self.id && message.room_id == self.room_id) else {
+            return;
+        };
+        if message.round > self.round {
+            self.start_round(message.round);
+        }
+        if message.round == self.round && message.game_phase == GamePhase::CardsRevealed {
+            self.phase = GamePhase::CardsRevealed;
+        }
+        let member = Member {
+            card: Some(message.user.card_value).filter(|c| !c.is_empty() && message.round == self.round),
+            name: message.user.username,
+            user_type: message.user.user_type,
+        };
+        let name = member.name.clone();
+        match self.peers.iter_mut().find(|p| p.id == id) {
+            Some(peer) => {
+                let previous = std::mem::replace(&mut peer.member, member);
+                peer.last_seen = now;
+                if previous.name != name {
+                    self.info(format!("{} is now known as {}.", previous.name, name));
+                }
+            }
+            None => {
+                self.peers.push(Peer { id, member, last_seen: now });
+                self.info(format!("{} joined the room.", name));
+            }
+        }
+        match message.request {
+            Some(OwnedUserRequest::ChatMessage { message }) => {
+                self.log.push(LogEntry { level: LogLevel::Chat, message: format!("{}: {}", name, message) });
+            }
+            Some(OwnedUserRequest::RevealCards) => self.info(format!("{} revealed the cards.", name)),
+            Some(OwnedUserRequest::StartNewRound) => self.info(format!("{} started a new round.", name)),
+            _ => {}
+        }
+    }
+
+    /// Removes the peers that said goodbye or weren't heard of for a while.
+    fn remove_peers(&mut self, gone: impl Fn(&Peer) -> bool) {
+        let (left, stayed): (Vec<Peer>, Vec<Peer>) = std::mem::take(&mut self.peers).into_iter().partition(gone);
+        self.peers = stayed;
+        for peer in left {
+            self.info(format!("{} left the room.", peer.member.name));
+        }
+    }
+
+    /// The room like the server sends it: the cards of the others stay hidden until the reveal.
+    fn view(&self) -> Room {
+        let revealed = self.phase == GamePhase::CardsRevealed;
+        let members = std::iter::once((&self.id, &self.you, true))
+            .chain(self.peers.iter().map(|p| (&p.id, &p.member, false)));
+        let users = members.clone().map(|(id, member, you)| {
+            let card_value = match (&member.card, member.user_type) {
+                (_, UserType::Spectator) => String::new(),
+                (Some(card), _) if revealed || you => card.clone(),
+                (Some(_), _) => "✅".to_string(),
+                (None, _) if you => String::new(),
+                (None, _) => "❌".to_string(),
+            };
+            User {
+                user_id: Some(id.clone()),
+                username: member.name.clone(),
+                user_type: member.user_type,
+                your_user: you,
+                card_value,
+            }
+        }).collect();
+        let numbers: Vec<f32> = members
+            .filter(|(_, member, _)| member.user_type == UserType::Participant)
+            .filter_map(|(_, member, _)| member.card.as_ref()?.parse().ok())
+            .collect();
+        let average = if revealed && !numbers.is_empty() {
+            format!("{:.1}", numbers.iter().sum::<f32>() / numbers.len() as f32)
+        } else {
+            String::new()
+        };
+        Room {
+            room_id: self.room_id.clone(),
+            deck: self.deck.clone(),
+            game_phase: self.phase,
+            users,
+            average,
+            log: self.log.iter().map(|entry| LogEntry { level: entry.level, message: entry.message.clone() }).collect(),
+        }
+    }
+}
+
+/// Client of the peer mode, sending the requests to every peer of the room instead of a server.
+#[derive(Debug)]
+pub struct PeerPokerClient {
+    socket: UdpSocket,
+    discovery: Discovery,
+    /// Where the other peers take their requests, by peer id.
+    addresses: HashMap<String, SocketAddr>,
+    room: PeerRoom,
+    last_heartbeat: Instant,
+}
+
+impl PeerPokerClient {
+    pub fn new(config: &Config) -> AppResult<(Self, AppRoom, Vec<AppLogEntry>)> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+        socket.set_nonblocking(true)?;
+        let id = format!("{:016x}", random());
+        let discovery = Discovery::start(&id, &config.room, socket.local_addr()?.port())?;
+        info!("Joining room {} as peer {} in the local network.", config.room, id);
+
+        let mut room = PeerRoom {
+            id,
+            room_id: config.room.clone(),
+            deck: config.custom_deck().unwrap_or_else(|| DEFAULT_DECK.iter().map(|c| c.to_string()).collect()),
+            round: 0,
+            phase: GamePhase::Playing,
+            you: Member { name: config.name.clone(), user_type: UserType::Participant, card: None },
+            peers: vec![],
+            log: vec![],
+        };
+        room.info(format!("{} joined the room.", config.name));
+        let (app_room, log) = convert_initial_room(&room.view());
+        let result = Self { socket, discovery, addresses: HashMap::new(), room, last_heartbeat: Instant::now() };
+        Ok((result, app_room, log))
+    }
+
+    fn send_to(&self, address: SocketAddr, message: &PeerMessage<UserRequest>) -> AppResult<()> {
+        if let Err(e) = self.socket.send_to(serde_json::to_string(message)?.as_bytes(), address) {
+            warn!("Failed to send to the peer at {}: {}", address, e);
+        }
+        Ok(())
+    }
+
+    /// Applies the request and passes it on to every peer.
+    fn send(&mut self, request: UserRequest) -> AppResult<()> {
+        self.room.apply(&request);
+        let message = self.room.message(Some(request));
+        for address in self.addresses.values() {
+            self.send_to(*address, &message)?;
+        }
+        self.last_heartbeat = Instant::now();
+        Ok(())
+    }
+
+    fn heartbeat(&mut self) -> AppResult<()> {
+        let message = self.room.message(None);
+        for address in self.addresses.values() {
+            self.send_to(*address, &message)?;
+        }
+        self.last_heartbeat = Instant::now();
+        Ok(())
+    }
+}
+
+impl PokerClient for PeerPokerClient {
+    fn get_updates(&mut self) -> AppResult<(Vec<AppRoom>, Vec<AppLogEntry>)> {
+        let before = self.room.view();
+        let now = Instant::now();
+
+        let mut gone = vec![];
+        for (address, announcement) in self.discovery.poll()? {
+            if announcement.gone {
+                self.addresses.remove(&announcement.peer);
+                gone.push(announcement.peer);
+            } else if self.addresses.insert(announcement.peer, address).is_none() {
+                // the new peer learns about this one right away
+                self.send_to(address, &self.room.message(None))?;
+            }
+        }
+
+        let mut buffer = [0; 65536];
+        loop {
+            let (length, from) = match self.socket.recv_from(&mut buffer) {
+                Ok(received) => received,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e.into()),
+            };
+            match serde_json::from_slice::<PeerMessage<OwnedUserRequest>>(&buffer[..length]) {
+                Ok(message) => {
+                    // peers whose announcement got lost are known from their messages
+                    if let Some(id) = &message.user.user_id {
+                        self.addresses.entry(id.clone()).or_insert(from);
+                    }
+                    self.room.receive(message, now);
+                }
+                Err(e) => debug!("Ignoring an invalid message from the peer at {}: {}", from, e),
+            }
+        }
+
+        self.room.remove_peers(|peer| gone.contains(&peer.id) || now.duration_since(peer.last_seen) > PEER_TIMEOUT);
+        if self.last_heartbeat.elapsed() >= HEARTBEAT_INTERVAL {
+            self.heartbeat()?;
+        }
+
+        let view = self.room.view();
+        if view == before {
+            return Ok((vec![], vec![]));
+        }
+        Ok(convert_room_updates(&[view]))
+    }
+
+    fn vote(&mut self, card_value: Option<&str>) -> AppResult<()> {
+        self.send(UserRequest::PlayCard { card_value })
+    }
+
+    fn change_name(&mut self, name: &str) -> AppResult<()> {
+        self.send(UserRequest::ChangeName { name })
+    }
+
+    fn chat(&mut self, message: &str) -> AppResult<()> {
+        self.send(UserRequest::ChatMessage { message })
+    }
+
+    fn change_user_type(&mut self, user_type: AppUserType) -> AppResult<()> {
+        self.send(UserRequest::ChangeUserType { user_type: user_type.into() })
+    }
+
+    fn reveal(&mut self) -> AppResult<()> {
+        self.send(UserRequest::RevealCards)
+    }
+
+    fn reset(&mut self) -> AppResult<()> {
+        self.send(UserRequest::StartNewRound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(id: &str, name: &str) -> PeerRoom {
+        PeerRoom {
+            id: id.to_string(),
+            room_id: "hallway".to_string(),
+            deck: vec!["1".to_string(), "3".to_string()],
+            round: 0,
+            phase: GamePhase::Playing,
+            you: Member { name: name.to_string(), user_type: UserType::Participant, card: None },
+            peers: vec![],
+            log: vec![],
+        }
+    }
+
+    /// Sends the request of `from` to `to` the way it travels between the peers.
+    fn pass(from: &mut PeerRoom, to: &mut PeerRoom, request: Option<UserRequest>) {
+        if let Some(request) = &request {
+            from.apply(request);
+        }
+        let json = serde_json::to_string(&from.message(request)).unwrap();
+        to.receive(serde_json::from_str(&json).unwrap(), Instant::now());
+    }
+
+    fn cards(room: &PeerRoom) -> Vec<String> {
+        room.view().users.into_iter().map(|u| u.card_value).collect()
+    }
+
+    #[test]
+    fn peers_exchange_votes_and_reveal() {
+        let (mut alice, mut bob) = (room("a", "alice"), room("b", "bob"));
+        pass(&mut bob, &mut alice, None);
+        pass(&mut alice, &mut bob, Some(UserRequest::PlayCard { card_value: Some("3") }));
+        pass(&mut bob, &mut alice, Some(UserRequest::PlayCard { card_value: Some("1") }));
+        assert_eq!(cards(&alice), vec!["3", "✅"]);
+        assert_eq!(cards(&bob), vec!["1", "✅"]);
+
+        pass(&mut bob, &mut alice, Some(UserRequest::ChatMessage { message: "say \"hi\"" }));
+        pass(&mut alice, &mut bob, Some(UserRequest::RevealCards));
+        assert_eq!(cards(&bob), vec!["1", "3"]);
+        assert_eq!(bob.view().average, "2.0");
+        assert_eq!(alice.log.iter().map(|e| e.message.as_str()).collect::<Vec<_>>(),
+            vec!["bob joined the room.", "bob: say \"hi\"", "alice revealed the cards."]);
+    }
+
+    #[test]
+    fn peers_behind_catch_up_with_the_round() {
+        let (mut alice, mut bob) = (room("a", "alice"), room("b", "bob"));
+        pass(&mut alice, &mut bob, Some(UserRequest::PlayCard { card_value: Some("3") }));
+        pass(&mut bob, &mut alice, Some(UserRequest::PlayCard { card_value: Some("1") }));
+        // the new round of alice gets lost, her next heartbeat brings bob along
+        alice.apply(&UserRequest::StartNewRound);
+        pass(&mut bob, &mut alice, None);
+        assert_eq!(cards(&alice), vec!["", "❌"]);
+        pass(&mut alice, &mut bob, None);
+        assert_eq!((bob.round, cards(&bob)), (1, vec!["".to_string(), "❌".to_string()]));
+
+        bob.remove_peers(|peer| peer.id == "a");
+        assert_eq!(bob.log.last().unwrap().message, "alice left the room.");
+        assert_eq!(bob.view().users.len(), 1);
+    }
+}
@@ -0,0 +1,87 @@
+//! Message formats of the supported servers. A protocol maps the requests of the client and the messages of its server
+//! to the DTOs of the ppoker server, everything above the socket only knows those.
+
+use std::fmt::Debug;
+
+use crate::app::AppResult;
+use crate::config::{Config, Protocol};
+use crate::web::dto::{UserRequest, UserType};
+use crate::web::ws::IncomingMessage;
+
+pub trait ServerProtocol: Debug + Send {
+    /// Websocket url joining the room of the config.
+    fn room_url(&self, config: &Config, user_type: UserType) -> String;
+
+    /// Text frame of a request.
+    fn encode(&self, request: &UserRequest) -> AppResult<String>;
+
+    /// Parses a text frame of the server, an error if it doesn't parse at all.
+    fn decode(&self, text: &str) -> AppResult<IncomingMessage>;
+}
+
+pub fn server_protocol(protocol: Protocol) -> Box<dyn ServerProtocol> {
+    match protocol {
+        Protocol::Ppoker => Box::new(PpokerProtocol),
+    }
+}
+
+#[derive(Debug)]
+pub struct PpokerProtocol;
+
+impl ServerProtocol for PpokerProtocol {
+    fn room_url(&self, config: &Config, user_type: UserType) -> String {
+        let user_type = match user_type {
+            UserType::Participant => "PARTICIPANT",
+            UserType::Spectator => "SPECTATOR",
+        };
+        format!("{}/rooms/{}?user={}&userType={}", config.server, urlencoding::encode(config.room.as_str()), urlencoding::encode(config.name.as_str()), user_type)
+    }
+
+    fn encode(&self, request: &UserRequest) -> AppResult<String> {
+        Ok(serde_json::to_string(request)?)
+    }
+
+    /// Tells room updates from the error payloads of the server, anything else is passed on as unknown.
+    fn decode(&self, text: &str) -> AppResult<IncomingMessage> {
+        let value: serde_json::Value = serde_json::from_str(text)?;
+        if value.get("roomId").is_some() {
+            return Ok(IncomingMessage::RoomUpdate(serde_json::from_value(value)?));
+        }
+        let error = ["error", "message"].iter().find_map(|key| value.get(key)?.as_str());
+        Ok(match error {
+            Some(error) => IncomingMessage::Error(error.to_string()),
+            None => IncomingMessage::Unknown(text.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_matches;
+
+    use super::*;
+
+    #[test]
+    fn parses_errors_and_unknown_messages() {
+        let protocol = PpokerProtocol;
+        let room = r#"{"roomId":"a","deck":["1"],"gamePhase":"PLAYING","users":[],"average":"?","log":[]}"#;
+        assert_matches!(protocol.decode(room).unwrap(), IncomingMessage::RoomUpdate(room) if room.room_id == "a");
+        assert_matches!(protocol.decode(r#"{"error":"Room is full"}"#).unwrap(), IncomingMessage::Error(e) if e == "Room is full");
+        assert_matches!(protocol.decode(r#"{"type":"hello"}"#).unwrap(), IncomingMessage::Unknown(raw) if raw == r#"{"type":"hello"}"#);
+        assert!(protocol.decode("not json").is_err());
+    }
+
+    #[test]
+    fn room_url_encodes_room_and_name() {
+        let config = Config {
+            server: "wss://poker.example.com/api".to_string(),
+            room: "sprint 42".to_string(),
+            name: "Zoë".to_string(),
+            ..Config::default()
+        };
+        assert_eq!(
+            PpokerProtocol.room_url(&config, UserType::Spectator),
+            "wss://poker.example.com/api/rooms/sprint%2042?user=Zo%C3%AB&userType=SPECTATOR",
+        );
+    }
+}
@@ -1,29 +1,60 @@
 use std::net::TcpStream;
+use std::thread;
 use std::time::{Duration, Instant};
 
-use log::{debug, info};
+use log::{debug, info, warn};
 use tungstenite::{Message, WebSocket};
 use tungstenite::stream::MaybeTlsStream;
 
 use crate::app::AppResult;
 use crate::config::Config;
-use crate::web::dto::{Room, UserRequest};
+use crate::diagnose;
+use crate::web::client::ClientError;
+use crate::web::dto::{Room, UserRequest, UserType};
+use crate::web::latency::{Latency, LatencyStats};
+use crate::web::protocol::{server_protocol, ServerProtocol};
 
 #[derive(Debug)]
 pub struct PokerSocket {
     socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    protocol: Box<dyn ServerProtocol>,
     last_ping: Instant,
+    /// Fail on messages that don't parse instead of skipping them.
+    strict: bool,
+    /// When the ping that still waits for its pong was sent.
+    ping_sent: Option<Instant>,
+    latency: Latency,
 }
 
+/// Pings keep the connection alive and measure the round trip time.
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
 #[derive(Debug)]
 pub enum IncomingMessage {
     Close,
     RoomUpdate(Room),
+    /// Error the server reported instead of a room update.
+    Error(String),
+    /// Payload that is neither a room update nor an error, kept raw for the log.
+    Unknown(String),
+    /// Payload that failed to parse and was skipped, with the parse error.
+    Invalid(String),
+}
+
+/// Longest part of a payload written to the log, room updates with a long chat log can get big.
+const MAX_LOGGED_PAYLOAD: usize = 500;
+
+fn truncate_payload(text: &str) -> String {
+    match text.char_indices().nth(MAX_LOGGED_PAYLOAD) {
+        Some((end, _)) => format!("{}... ({} bytes)", &text[..end], text.len()),
+        None => text.to_string(),
+    }
 }
 
 impl PokerSocket {
-    pub fn connect(config: &Config) -> AppResult<Self> {
-        let url = format!("{}/rooms/{}?user={}&userType=PARTICIPANT", config.server, urlencoding::encode(config.room.as_str()), urlencoding::encode(config.name.as_str()));
+    pub fn connect(config: &Config, user_type: UserType) -> AppResult<Self> {
+        let protocol = server_protocol(config.protocol);
+        let url = protocol.room_url(config, user_type);
         let (mut socket, _response) = tungstenite::connect(url)?;
         match socket.get_mut() {
             MaybeTlsStream::NativeTls(t) => {
@@ -39,19 +70,24 @@ impl PokerSocket {
 
         Ok(Self {
             socket,
+            protocol,
             last_ping: Instant::now(),
+            strict: config.strict_protocol,
+            ping_sent: None,
+            latency: Latency::default(),
         })
     }
 
     pub fn send_request(&mut self, request: UserRequest) -> AppResult<()> {
-        let body = serde_json::to_string(&request)?;
+        let body = self.protocol.encode(&request)?;
         debug!("Sending message: {:?}", body);
+        diagnose::record_frame(">", &body);
         self.socket.send(Message::Text(body))?;
         Ok(())
     }
 
     pub fn read(&mut self) -> AppResult<Option<IncomingMessage>> {
-        if Instant::now() - self.last_ping > Duration::from_secs(30) {
+        if Instant::now() - self.last_ping > PING_INTERVAL {
             self.ping()?;
         }
         let result = self.socket.read();
@@ -64,14 +100,26 @@ impl PokerSocket {
         match message {
             Message::Text(text) => {
                 debug!("Got message from server: {}", text);
-                return Ok(Some(IncomingMessage::RoomUpdate(serde_json::from_str(&text)?)));
+                diagnose::record_frame("<", &text);
+                return match self.protocol.decode(&text) {
+                    Ok(message) => Ok(Some(message)),
+                    Err(e) if !self.strict => {
+                        warn!("Skipping a message that failed to parse ({}): {}", e, truncate_payload(&text));
+                        Ok(Some(IncomingMessage::Invalid(e.to_string())))
+                    }
+                    Err(e) => Err(e),
+                };
             }
             Message::Binary(_) => {}
             Message::Ping(d) => {
                 debug!("Ping: {:?}", d);
             }
             Message::Pong(d) => {
-                debug!("Pong: {:?}", d)
+                debug!("Pong: {:?}", d);
+                // measured when the pong is read, so a sample may include up to one tick of the event loop
+                if let Some(sent) = self.ping_sent.take() {
+                    self.latency.record(sent.elapsed());
+                }
             }
             Message::Close(_) => {
                 debug!("Server closed connection.");
@@ -94,9 +142,36 @@ impl PokerSocket {
         }
     }
 
+    /// Whether the connection is encrypted. The handshake only succeeds if the certificate was accepted.
+    pub fn is_tls(&self) -> bool {
+        matches!(self.socket.get_ref(), MaybeTlsStream::NativeTls(_))
+    }
+
+    /// Pings the server and waits for the pong, other messages read meanwhile are dropped.
+    pub fn round_trip(&mut self, timeout: Duration) -> AppResult<Duration> {
+        self.ping()?;
+        let sent = self.last_ping;
+        while self.ping_sent.is_some() {
+            if sent.elapsed() > timeout {
+                return Err(Box::new(ClientError::PongMissing));
+            }
+            if let Some(IncomingMessage::Close) = self.read()? {
+                return Err(Box::new(ClientError::ServerClosedConnection));
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        Ok(sent.elapsed())
+    }
+
+    /// Round trip times of the recent pings.
+    pub fn latency(&self) -> Option<LatencyStats> {
+        self.latency.stats()
+    }
+
     pub fn ping(&mut self) -> AppResult<()> {
         self.socket.send(Message::Ping(vec![0x13, 0x37]))?;
         self.last_ping = Instant::now();
+        self.ping_sent = Some(self.last_ping);
 
         Ok(())
     }
@@ -112,7 +187,7 @@ mod tests {
     use crate::app::AppResult;
     use crate::config::Config;
     use crate::web::dto::{GamePhase, UserRequest, UserType};
-    use crate::web::ws::{IncomingMessage, PokerSocket};
+    use crate::web::ws::{truncate_payload, IncomingMessage, PokerSocket, MAX_LOGGED_PAYLOAD};
 
     fn get_config() -> Config {
         let mut config = Config::default();
@@ -120,9 +195,16 @@ mod tests {
         return config;
     }
 
+    #[test]
+    fn truncates_long_payloads() {
+        assert_eq!(truncate_payload("{}"), "{}");
+        let long = "ä".repeat(MAX_LOGGED_PAYLOAD + 1);
+        assert_eq!(truncate_payload(&long), format!("{}... ({} bytes)", "ä".repeat(MAX_LOGGED_PAYLOAD), long.len()));
+    }
+
     #[test]
     fn connect() {
-        let mut client = PokerSocket::connect(&get_config()).unwrap();
+        let mut client = PokerSocket::connect(&get_config(), UserType::Participant).unwrap();
         thread::sleep(Duration::from_millis(250));
         let message = client.read().unwrap();
         if let Some(message) = message {
@@ -135,7 +217,7 @@ mod tests {
     #[test]
     fn send_commands() -> AppResult<()> {
         let config = get_config();
-        let mut client = PokerSocket::connect(&config).unwrap();
+        let mut client = PokerSocket::connect(&config, UserType::Participant).unwrap();
         thread::sleep(Duration::from_millis(250));
         let messages = client.read_all().unwrap();
         assert_eq!(messages.len(), 1);
@@ -180,8 +262,8 @@ mod tests {
         let mut config2 = config1.clone();
         config2.name = "Ralph Muller".to_string();
 
-        let mut client1 = PokerSocket::connect(&config1).unwrap();
-        let mut client2 = PokerSocket::connect(&config2).unwrap();
+        let mut client1 = PokerSocket::connect(&config1, UserType::Participant).unwrap();
+        let mut client2 = PokerSocket::connect(&config2, UserType::Participant).unwrap();
         client1.send_request(UserRequest::PlayCard { card_value: Some("5") })?;
         client2.send_request(UserRequest::PlayCard { card_value: Some("8") })?;
 